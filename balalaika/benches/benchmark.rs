@@ -1,14 +1,22 @@
 use balalaika::board_generator::static_board;
 use balalaika::engine::EngineSettings;
+use balalaika::engine::GameRng;
+use balalaika::engine::StepJournal;
 use balalaika::engine::Movement;
 use balalaika::engine::advance_one_step_with_settings;
+use balalaika::engine::advance_one_step_undoable;
+use balalaika::engine::undo_step;
 use balalaika::engine::food_spawner::get_food_spawn_spots;
+use balalaika::engine::hazard_spawner;
+use balalaika::engine::new_rng;
 use balalaika::engine::safe_zone_shrinker::shrink;
 use balalaika::features::composite::CompositeFeatures;
 use balalaika::game::Board;
 use balalaika::game::GridPoint;
-use balalaika::game::HEIGHT;
+use balalaika::game::MAX_WIDTH;
 use balalaika::mcts::utils::get_first_able_actions_from_masks;
+use balalaika::mcts::utils::get_masks;
+use balalaika::mcts::heuristics::flood_fill::flood_fill;
 use balalaika::mcts::seq::SequentialMCTS;
 use balalaika::mcts::seq::SequentialMCTSConfig;
 use balalaika::mcts::search::Search;
@@ -43,17 +51,17 @@ fn mcts_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-pub fn static_food_spawner(board: &mut Board) {
+pub fn static_food_spawner(board: &mut Board, _: &mut GameRng) {
     // For engine use only! It changes board.objects internal state
-    let mut spawn_spots: Vec<_> = get_food_spawn_spots(board).into_iter().collect();
-    spawn_spots.sort_by(|p1, p2| (p1.y * HEIGHT as usize + p1.x).cmp(&(p2.y * HEIGHT as usize + p2.x)));
+    let mut spawn_spots = get_food_spawn_spots(board);
+    spawn_spots.sort_by(|p1, p2| (p1.y * MAX_WIDTH as usize + p1.x).cmp(&(p2.y * MAX_WIDTH as usize + p2.x)));
 
     if !spawn_spots.is_empty() && (board.foods.len() < 1 || board.turn % 5 == 0) {
-        board.put_food(GridPoint::from(*spawn_spots[0]));
+        board.put_food(GridPoint::from(spawn_spots[0]));
     }
 }
 
-pub fn static_safe_zone_shrinker(board: &mut Board) {
+pub fn static_safe_zone_shrinker(board: &mut Board, _: &mut GameRng) {
     if board.turn == 0 || board.turn % 20 != 0 || board.safe_zone.empty() {
         return;
     }
@@ -64,11 +72,17 @@ pub fn static_safe_zone_shrinker(board: &mut Board) {
 fn engine_benchmark(c: &mut Criterion) {    
     let mut group = c.benchmark_group("engine");
     group.sample_size(1000);
+    group.bench_function("clone", |b| {
+        let board = static_board();
+        b.iter(|| black_box(&board).clone())
+    });
     group.bench_function("static", |b| b.iter(|| {
         let mut board = static_board();
         let mut settings = EngineSettings {
             food_spawner: &mut static_food_spawner,
             safe_zone_shrinker: &mut static_safe_zone_shrinker,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
         };
         while !board.is_terminal() {
             let actions = get_first_able_actions_from_masks(&board);
@@ -78,6 +92,56 @@ fn engine_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
+fn make_unmake_benchmark(c: &mut Criterion) {
+    // Plays the static game and visits one child of every position, like search does.
+    let mut group = c.benchmark_group("make/unmake");
+    group.sample_size(1000);
+    group.bench_function("clone + step", |b| b.iter(|| {
+        let mut board = static_board();
+        let mut settings = EngineSettings {
+            food_spawner: &mut static_food_spawner,
+            safe_zone_shrinker: &mut static_safe_zone_shrinker,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        while !board.is_terminal() {
+            let actions = get_first_able_actions_from_masks(&board);
+            let mut child = board.clone();
+            advance_one_step_with_settings(&mut child, &mut settings, actions);
+            black_box(&child);
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+        }
+    }));
+    group.bench_function("step + undo", |b| b.iter(|| {
+        let mut board = static_board();
+        let mut settings = EngineSettings {
+            food_spawner: &mut static_food_spawner,
+            safe_zone_shrinker: &mut static_safe_zone_shrinker,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        let mut journal = StepJournal::default();
+        while !board.is_terminal() {
+            let actions = get_first_able_actions_from_masks(&board);
+            let (_, undo) = advance_one_step_undoable(&mut board, &mut settings, actions, &mut journal);
+            black_box(&board);
+            undo_step(&mut board, undo, &mut journal);
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+        }
+    }));
+    group.finish();
+}
+
+fn heuristics_benchmark(c: &mut Criterion) {
+    let board = static_board();
+
+    let mut group = c.benchmark_group("heuristics");
+    group.sample_size(1000);
+    group.bench_function("flood fill", |b| b.iter(|| flood_fill(black_box(&board))));
+    group.bench_function("masks", |b| b.iter(|| get_masks(black_box(&board))));
+    group.finish();
+}
+
 
-criterion_group!(benches, mcts_benchmark, predict_benchmark, engine_benchmark);
+criterion_group!(benches, mcts_benchmark, predict_benchmark, engine_benchmark, make_unmake_benchmark, heuristics_benchmark);
 criterion_main!(benches);
\ No newline at end of file
@@ -2,6 +2,7 @@ pub mod objects {
     use std::fmt;
     use serde::{Serialize, Deserialize};
     use crate::game::{GridPoint, CoordType};
+    use crate::settings::GameSettings;
 
     #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
     pub struct State {
@@ -15,6 +16,8 @@ pub mod objects {
     pub struct Game {
         pub id: String,
         pub ruleset: Ruleset,
+        #[serde(default)]
+        pub map: String,
         pub timeout: i32,
     }
 
@@ -22,6 +25,44 @@ pub mod objects {
     pub struct Ruleset {
         pub name: String,
         pub version: String,
+        #[serde(default)]
+        pub settings: RulesetSettings,
+    }
+
+    // Missing values are filled with the defaults of `GameSettings`.
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+    #[serde(rename_all = "camelCase", default)]
+    pub struct RulesetSettings {
+        pub food_spawn_chance: i32,
+        pub minimum_food: i32,
+        pub hazard_damage_per_turn: i32,
+        pub royale: RoyaleSettings,
+    }
+
+    impl Default for RulesetSettings {
+        fn default() -> Self {
+            let settings = GameSettings::default();
+            RulesetSettings {
+                food_spawn_chance: settings.food_spawn_chance as i32,
+                minimum_food: settings.minimum_food as i32,
+                hazard_damage_per_turn: settings.hazard_damage_per_turn,
+                royale: RoyaleSettings::default(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+    #[serde(rename_all = "camelCase", default)]
+    pub struct RoyaleSettings {
+        pub shrink_every_n_turns: i32,
+    }
+
+    impl Default for RoyaleSettings {
+        fn default() -> Self {
+            RoyaleSettings {
+                shrink_every_n_turns: GameSettings::default().shrink_every_n_turns,
+            }
+        }
     }
 
     #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -45,7 +86,8 @@ pub mod objects {
         #[serde(default)]
         pub shout: String,
         // Present only in Squad Mode games
-        // pub squad: String,
+        #[serde(default)]
+        pub squad: String,
     }
 
     #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Copy, Clone)]
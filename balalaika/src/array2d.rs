@@ -2,16 +2,16 @@ use std::ops::{Index, IndexMut};
 
 use serde::{Serialize, Deserialize};
 
-use crate::game::{WIDTH, HEIGHT};
+use crate::game::{MAX_WIDTH, MAX_HEIGHT};
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Array2D<T> {
-    pub data: [[T; HEIGHT as usize]; WIDTH as usize],
+    pub data: [[T; MAX_HEIGHT as usize]; MAX_WIDTH as usize],
 }
 
 impl<T: Copy> Array2D<T> {
     pub fn init_same(value: T) -> Self {
-        let data = [[value; HEIGHT as usize]; WIDTH as usize];
+        let data = [[value; MAX_HEIGHT as usize]; MAX_WIDTH as usize];
         Array2D {
             data
         }
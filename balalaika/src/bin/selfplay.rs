@@ -1,10 +1,13 @@
 use std::env;
 
-use balalaika::board_generator::generate_board;
-use balalaika::mcts::utils::SearchOptions;
+use balalaika::board_generator::generate_board_with_settings;
+use balalaika::mcts::utils::{SearchOptions, parse_env};
 use mongodb::sync::Client;
-use balalaika::engine::{food_spawner, EngineSettings, safe_zone_shrinker, advance_one_step_with_settings};
-use balalaika::game::MAX_SNAKE_COUNT;
+use balalaika::engine::{food_spawner, EngineSettings, safe_zone_shrinker, hazard_spawner, advance_one_step_with_settings, new_rng};
+use rand::Rng;
+use balalaika::game::{MAX_SNAKE_COUNT, DEFAULT_WIDTH, DEFAULT_HEIGHT};
+use balalaika::ruleset::RulesetKind;
+use balalaika::settings::{GameMap, GameSettings};
 use balalaika::game_log::{save_game_log, GameLogBuilder};
 use mcts::search::Search;
 use mcts::utils::search;
@@ -21,18 +24,31 @@ fn main() {
     let mcts_config = MCTSConfig::from_env();
     let search_options = SearchOptions::from_env();
 
+    let ruleset = parse_env("SELFPLAY_RULESET").unwrap_or(RulesetKind::Wrapped);
+    let width = parse_env("SELFPLAY_WIDTH").unwrap_or(DEFAULT_WIDTH);
+    let height = parse_env("SELFPLAY_HEIGHT").unwrap_or(DEFAULT_HEIGHT);
+    let snake_count = parse_env("SELFPLAY_SNAKES").unwrap_or(4);
+    // Healing pools need negative damage. Hazard walls are lethal by default.
+    let map = parse_env("SELFPLAY_MAP").unwrap_or(GameMap::Standard);
+    let default_hazard_damage = if map.has_hazard_walls() { 100 } else { GameSettings::default().hazard_damage_per_turn };
+    let settings = GameSettings {
+        map,
+        hazard_damage_per_turn: parse_env("SELFPLAY_HAZARD_DAMAGE").unwrap_or(default_hazard_damage),
+        ..GameSettings::default()
+    };
+
+    // Same seed replays the same games, if search is limited by MCTS_ITERATIONS and not by time.
     let mut engine_settings = EngineSettings {
         food_spawner: &mut food_spawner::create_standard,
         safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+        hazard_spawner: &mut hazard_spawner::standard,
+        rng: &mut new_rng(parse_env("SELFPLAY_SEED")),
     };
 
     loop {
-        let mut board = generate_board();
-        let mut game_log_builder = GameLogBuilder::new(
-            board.snakes.clone(),
-            board.safe_zone,
-            &board.foods,
-        );
+        let mut board = generate_board_with_settings(engine_settings.rng, ruleset, settings, width, height, snake_count)
+            .unwrap_or_else(|error| panic!("Can't set up the board: {}", error));
+        let mut game_log_builder = GameLogBuilder::new_from_board(&board);
         if let Some(tag) = &tag_option {
             game_log_builder.set_tag(tag.clone());
         }
@@ -40,26 +56,32 @@ fn main() {
         // Play game
         println!("Starting new game");
         while !board.is_terminal() {
-            let mut mcts = MCTS::new(mcts_config.clone());
+            let mut mcts = MCTS::new(MCTSConfig {
+                seed: Some(engine_settings.rng.gen()),
+                ..mcts_config
+            });
             search(&mut mcts, &board, search_options);
 
             let mut actions = [0; MAX_SNAKE_COUNT];
 
-            let mut alive_i = 0;
-            for snake_i in 0..board.snakes.len() {
-                if !board.snakes[snake_i].is_alive() {
+            for (snake_i, snake) in board.snakes.iter().enumerate() {
+                if !snake.is_alive() {
                     continue;
                 }
-                let action = mcts.get_final_movement(&board, alive_i, true);
+                let action = mcts.get_final_movement(&board, snake_i, true);
                 actions[snake_i] = action as usize;
-                alive_i += 1;
             }
-            advance_one_step_with_settings(&mut board, &mut engine_settings, actions);
+            let outcome = advance_one_step_with_settings(&mut board, &mut engine_settings, actions);
             game_log_builder.add_turn_from_board(&board);
+            for (snake_i, cause) in outcome.eliminated() {
+                println!("Snake {} is eliminated on turn {}: {:?}", snake_i, board.turn, cause);
+            }
             // TODO: verbose
             println!("{}", board);
         }
 
+        println!("Placements: {:?}", &board.placements()[..board.snakes.len()]);
+
         // Upload game
         let game_log = game_log_builder.finalize();
         save_game_log(&client, &game_log).expect("Error");
@@ -23,7 +23,7 @@ use balalaika::game_log::save_game_log;
 use balalaika::mcts::search::Search;
 use balalaika::mcts::utils::get_best_movement;
 use balalaika::{api, mcts};
-use balalaika::game::Board;
+use balalaika::game::{Board, BoardError};
 use balalaika::game_log::GameLogBuilder;
 
 
@@ -40,6 +40,8 @@ cfg_if::cfg_if! {
 struct GameSession {
     mcts: Option<MCTS>,
     game_log_builder: Option<GameLogBuilder>,
+    // Board of the last request. New boards keep its snake indices, so the persistent tree stays valid.
+    board: Option<Board>,
 }
 
 struct Storage {
@@ -60,10 +62,20 @@ fn index() -> Json<api::responses::Info> {
     })
 }
 
+fn parse_state(body: &str) -> Result<api::objects::State, Status> {
+    serde_json::from_str(body).map_err(|error| {
+        warn!("Invalid request body: {}", error);
+        Status::BadRequest
+    })
+}
+
 #[post("/start", data = "<body>")]
 fn start(body: String, storage: &State<Storage>) -> Status {
     info!("START - {}", body);
-    let state = serde_json::from_str::<api::objects::State>(&body).unwrap();
+    let state = match parse_state(&body) {
+        Ok(state) => state,
+        Err(status) => return status,
+    };
 
     let mcts = if env::var("MCTS_PERSISTENT").is_ok() {
         Some(MCTS::new(MCTSConfig::from_env()))
@@ -72,12 +84,26 @@ fn start(body: String, storage: &State<Storage>) -> Status {
     };
 
     let game_log_builder = if env::var("GAME_LOG").is_ok() {
-        Some(GameLogBuilder::new_from_state(&state))
+        match GameLogBuilder::new_from_state(&state) {
+            Ok(game_log_builder) => Some(game_log_builder),
+            Err(error) => {
+                warn!("Game is not logged, invalid state: {}", error);
+                None
+            },
+        }
     } else {
         None
     };
 
-    let game_session = GameSession {mcts, game_log_builder};
+    let board = match Board::try_from_api(&state) {
+        Ok(board) => Some(board),
+        Err(error) => {
+            warn!("Invalid state: {}", error);
+            None
+        },
+    };
+
+    let game_session = GameSession {mcts, game_log_builder, board};
 
     if let Some(mut game_session_mutex) = storage.game_sessions.insert(state.game.id, Mutex::new(game_session)) {
         warn!("Game with given id already exists! Replacing...");
@@ -97,26 +123,32 @@ fn movement_options() -> Status {
     Status::Ok
 }
 
-fn get_our_snake_alive_index(state: &api::objects::State) -> usize {
-    let snake_index = state.board.snakes
-        .iter()
-        .filter(|snake| snake.health > 0)
-        .position(|snake| snake.id == state.you.id)
-        .unwrap();
-    
-    snake_index
+fn invalid_state(error: BoardError) -> Status {
+    warn!("Invalid state: {}", error);
+    Status::BadRequest
+}
+
+fn get_our_snake_index(board: &Board, state: &api::objects::State) -> Result<usize, Status> {
+    board.snake_index(&state.you.id).ok_or_else(|| {
+        warn!("Snake {} is not on the board", state.you.id);
+        Status::BadRequest
+    })
 }
 
 #[post("/move", data = "<body>")]
-fn movement(storage: &State<Storage>, body: String) -> Json<api::responses::Move> {
+fn movement(storage: &State<Storage>, body: String) -> Result<Json<api::responses::Move>, Status> {
     info!("MOVE - {}", body);
-    let state = serde_json::from_str::<api::objects::State>(&body).unwrap();
-    let board = Board::from_api(&state);
-    let our_snake_alive_index = get_our_snake_alive_index(&state);
+    let state = parse_state(&body)?;
 
-    if let Some(game_session_mutex) = storage.game_sessions.get(&state.game.id) {
+    let board = if let Some(game_session_mutex) = storage.game_sessions.get(&state.game.id) {
         let mut game_session = game_session_mutex.lock().unwrap();
 
+        let board = match &game_session.board {
+            Some(previous) => Board::try_from_api_after(&state, previous),
+            None => Board::try_from_api(&state),
+        }.map_err(invalid_state)?;
+        game_session.board = Some(board.clone());
+
         // Skip first turn because board is the same as in /start.
         if state.turn > 0 {
             if let Some(game_log_builder) = game_session.game_log_builder.as_mut() {
@@ -125,16 +157,21 @@ fn movement(storage: &State<Storage>, body: String) -> Json<api::responses::Move
         }
 
         if let Some(mcts) = game_session.mcts.as_mut() {
-            let movement = get_best_movement(mcts, &board, our_snake_alive_index, storage.search_options);
-            return Json(api::responses::Move::new(movement));
+            let our_snake_index = get_our_snake_index(&board, &state)?;
+            let movement = get_best_movement(mcts, &board, our_snake_index, storage.search_options);
+            return Ok(Json(api::responses::Move::new(movement)));
         }
-    }
+        board
+    } else {
+        Board::try_from_api(&state).map_err(invalid_state)?
+    };
 
+    let our_snake_index = get_our_snake_index(&board, &state)?;
     let mut mcts = MCTS::new(MCTSConfig::from_env());
-    let movement = get_best_movement(&mut mcts, &board, our_snake_alive_index, storage.search_options);
+    let movement = get_best_movement(&mut mcts, &board, our_snake_index, storage.search_options);
     mcts.shutdown();
     
-    Json(api::responses::Move::new(movement))
+    Ok(Json(api::responses::Move::new(movement)))
 }
 
 // This route is needed for CORS
@@ -144,10 +181,10 @@ fn ff_options() -> Status {
 }
 
 #[post("/flood_fill", data = "<body>")]
-fn flood_fill(body: String) -> Json<Rewards> {
+fn flood_fill(body: String) -> Result<Json<Rewards>, Status> {
     info!("FLOOD - {}", body);
-    let state = serde_json::from_str::<api::objects::State>(&body).unwrap();
-    let board = Board::from_api(&state);
+    let state = parse_state(&body)?;
+    let board = Board::try_from_api(&state).map_err(invalid_state)?;
 
     let f = mcts::heuristics::flood_fill::flood_fill(&board);
 
@@ -162,19 +199,23 @@ fn flood_fill(body: String) -> Json<Rewards> {
 
     info!("{:?}", d.as_micros() as f32 / 100000.0);
 
-    Json(f)
+    Ok(Json(f))
 }
 
 #[post("/end", data = "<body>")]
 fn end(storage: &State<Storage>, body: String) -> Status {
     info!("END - {}", body);
-    let state = serde_json::from_str::<api::objects::State>(&body).unwrap();
+    let state = match parse_state(&body) {
+        Ok(state) => state,
+        Err(status) => return status,
+    };
     if let Some((_, mut game_session_mutex)) = storage.game_sessions.remove(&state.game.id) {
         let game_session = game_session_mutex.get_mut().unwrap();
 
         if let Some(game_log_builder) = game_session.game_log_builder.as_mut() {
-            game_log_builder.add_turn_from_state(&state);
-            if let Some(client) = storage.client.as_ref() {
+            if let Err(error) = game_log_builder.add_turn_from_state(&state) {
+                warn!("Game log is not saved, invalid state: {}", error);
+            } else if let Some(client) = storage.client.as_ref() {
                 let game_log = game_log_builder.finalize();
                 save_game_log(client, &game_log);
             }
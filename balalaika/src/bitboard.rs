@@ -0,0 +1,293 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+use serde::{Serialize, Deserialize};
+
+use crate::api::objects::Movement;
+use crate::game::{CoordType, GridPoint, PointUsize, MAX_WIDTH, MAX_HEIGHT, MAX_SIZE};
+
+const WORDS: usize = MAX_SIZE.div_ceil(64);
+const COLUMN: usize = MAX_HEIGHT as usize;
+
+/// Set of cells, one bit per cell. Bits go column by column, like `Array2D`:
+/// the cell (x, y) is bit `x * MAX_HEIGHT + y`, so moving up is a shift by 1
+/// and moving right is a shift by `MAX_HEIGHT`.
+/// Cells outside of the board are never set.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Default, Serialize, Deserialize)]
+pub struct Bitboard {
+    words: [u64; WORDS],
+}
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard { words: [0; WORDS] };
+
+    /// All cells of the board of this size.
+    pub fn full(width: CoordType, height: CoordType) -> Bitboard {
+        let mut bitboard = Bitboard::EMPTY;
+        let column = (1u64 << height) - 1;
+        for x in 0..width as usize {
+            bitboard.or_bits(x * COLUMN, column);
+        }
+        bitboard
+    }
+
+    fn index(p: PointUsize) -> usize {
+        debug_assert!(p.x < MAX_WIDTH as usize && p.y < MAX_HEIGHT as usize);
+        p.x * COLUMN + p.y
+    }
+
+    pub fn get(&self, p: PointUsize) -> bool {
+        let i = Bitboard::index(p);
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, p: PointUsize) {
+        self.set_index(Bitboard::index(p));
+    }
+
+    pub fn unset(&mut self, p: PointUsize) {
+        let i = Bitboard::index(p);
+        self.words[i / 64] &= !(1 << (i % 64));
+    }
+
+    fn set_index(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    // Sets up to 64 bits starting from bit `i`.
+    fn or_bits(&mut self, i: usize, bits: u64) {
+        self.words[i / 64] |= bits << (i % 64);
+        if !i.is_multiple_of(64) && i / 64 + 1 < WORDS {
+            self.words[i / 64 + 1] |= bits >> (64 - i % 64);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Set cells in the order of bits.
+    pub fn iter(&self) -> impl Iterator<Item = PointUsize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let i = word_i * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(PointUsize {x: i / COLUMN, y: i % COLUMN})
+            })
+        })
+    }
+
+    fn shl(&self, n: usize) -> Bitboard {
+        let mut result = Bitboard::EMPTY;
+        let (words, bits) = (n / 64, n % 64);
+        for i in (words..WORDS).rev() {
+            result.words[i] = self.words[i - words] << bits;
+            if bits != 0 && i > words {
+                result.words[i] |= self.words[i - words - 1] >> (64 - bits);
+            }
+        }
+        result.words[WORDS - 1] &= LAST_WORD_MASK;
+        result
+    }
+
+    fn shr(&self, n: usize) -> Bitboard {
+        let mut result = Bitboard::EMPTY;
+        let (words, bits) = (n / 64, n % 64);
+        for i in 0..WORDS - words {
+            result.words[i] = self.words[i + words] >> bits;
+            if bits != 0 && i + words + 1 < WORDS {
+                result.words[i] |= self.words[i + words + 1] << (64 - bits);
+            }
+        }
+        result
+    }
+
+    /// Cells where the snakes from these cells get by the movement.
+    /// Movements out of the board are dropped unless the board is wrapped.
+    pub fn moved(&self, movement: Movement, width: CoordType, height: CoordType, wrapped: bool) -> Bitboard {
+        // Rows and columns are full, but it's fine because the cells are inside of the board.
+        let (edge, moved, wrapped_distance) = match movement {
+            Movement::Up => {
+                let edge = ROWS[height as usize - 1];
+                (edge, (*self & !edge).shl(1), (height - 1) as usize)
+            },
+            Movement::Down => {
+                let edge = ROWS[0];
+                (edge, (*self & !edge).shr(1), (height - 1) as usize)
+            },
+            Movement::Right => {
+                let edge = COLUMNS[width as usize - 1];
+                (edge, (*self & !edge).shl(COLUMN), (width - 1) as usize * COLUMN)
+            },
+            Movement::Left => {
+                let edge = COLUMNS[0];
+                (edge, (*self & !edge).shr(COLUMN), (width - 1) as usize * COLUMN)
+            },
+        };
+
+        if !wrapped {
+            return moved;
+        }
+        let crossed = *self & edge;
+        moved | match movement {
+            Movement::Up | Movement::Right => crossed.shr(wrapped_distance),
+            Movement::Down | Movement::Left => crossed.shl(wrapped_distance),
+        }
+    }
+
+    /// Cells next to the set cells.
+    pub fn neighbours(&self, width: CoordType, height: CoordType, wrapped: bool) -> Bitboard {
+        self.moved(Movement::Up, width, height, wrapped)
+            | self.moved(Movement::Down, width, height, wrapped)
+            | self.moved(Movement::Right, width, height, wrapped)
+            | self.moved(Movement::Left, width, height, wrapped)
+    }
+}
+
+const LAST_WORD_MASK: u64 = if MAX_SIZE.is_multiple_of(64) { u64::MAX } else { (1 << (MAX_SIZE % 64)) - 1 };
+
+// Full rows and columns of the largest board
+static ROWS: [Bitboard; MAX_HEIGHT as usize] = {
+    let mut rows = [Bitboard::EMPTY; MAX_HEIGHT as usize];
+    let mut y = 0;
+    while y < MAX_HEIGHT as usize {
+        let mut x = 0;
+        while x < MAX_WIDTH as usize {
+            let i = x * COLUMN + y;
+            rows[y].words[i / 64] |= 1 << (i % 64);
+            x += 1;
+        }
+        y += 1;
+    }
+    rows
+};
+
+static COLUMNS: [Bitboard; MAX_WIDTH as usize] = {
+    let mut columns = [Bitboard::EMPTY; MAX_WIDTH as usize];
+    let mut x = 0;
+    while x < MAX_WIDTH as usize {
+        let mut y = 0;
+        while y < MAX_HEIGHT as usize {
+            let i = x * COLUMN + y;
+            columns[x].words[i / 64] |= 1 << (i % 64);
+            y += 1;
+        }
+        x += 1;
+    }
+    columns
+};
+
+impl From<GridPoint> for Bitboard {
+    fn from(p: GridPoint) -> Bitboard {
+        let mut bitboard = Bitboard::EMPTY;
+        bitboard.set(p.into());
+        bitboard
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(mut self, other: Bitboard) -> Bitboard {
+        self &= other;
+        self
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, other: Bitboard) {
+        for (word, other_word) in self.words.iter_mut().zip(other.words) {
+            *word &= other_word;
+        }
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(mut self, other: Bitboard) -> Bitboard {
+        self |= other;
+        self
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, other: Bitboard) {
+        for (word, other_word) in self.words.iter_mut().zip(other.words) {
+            *word |= other_word;
+        }
+    }
+}
+
+/// Complement inside of `MAX_WIDTH` × `MAX_HEIGHT`. Intersect it with the board to stay inside.
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(mut self) -> Bitboard {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self.words[WORDS - 1] &= LAST_WORD_MASK;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::MOVEMENTS;
+    use crate::game::{GridPoint, Point, PointUsize, MAX_WIDTH, MAX_HEIGHT};
+    use crate::ruleset::{Ruleset, RulesetKind};
+    use super::Bitboard;
+
+    #[test]
+    fn test_moved_matches_ruleset() {
+        for ruleset in [RulesetKind::Standard, RulesetKind::Wrapped] {
+            for (width, height) in [(11, 11), (7, 19), (MAX_WIDTH, MAX_HEIGHT)] {
+                let full = Bitboard::full(width, height);
+                assert_eq!(full.count(), (width * height) as usize);
+
+                for x in 0..width {
+                    for y in 0..height {
+                        let p: GridPoint = Point {x, y};
+                        for movement in MOVEMENTS {
+                            let expected = ruleset.movement_position(p, movement, width, height);
+                            let moved = Bitboard::from(p).moved(movement, width, height, ruleset.is_wrapped());
+                            let expected_inside = expected.x >= 0 && expected.x < width && expected.y >= 0 && expected.y < height;
+
+                            if expected_inside {
+                                assert_eq!(moved.iter().collect::<Vec<_>>(), vec![expected.into()]);
+                            } else {
+                                assert!(moved.is_empty(), "{:?} moved {:?} out of {}x{}", p, movement, width, height);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut a = Bitboard::EMPTY;
+        a.set(PointUsize {x: 0, y: 0});
+        a.set(PointUsize {x: 18, y: 18});
+        a.set(PointUsize {x: 3, y: 7});
+        assert_eq!(a.count(), 3);
+        assert!(a.get(PointUsize {x: 18, y: 18}));
+
+        let b = Bitboard::full(MAX_WIDTH, MAX_HEIGHT);
+        assert_eq!(a & b, a);
+        assert_eq!((!a & b).count(), b.count() - 3);
+        assert_eq!(!b, Bitboard::EMPTY);
+
+        a.unset(PointUsize {x: 3, y: 7});
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![PointUsize {x: 0, y: 0}, PointUsize {x: 18, y: 18}]);
+    }
+}
@@ -1,84 +1,134 @@
 use arrayvec::ArrayVec;
 use rand::{
     seq::SliceRandom,
-    thread_rng,
     Rng,
 };
 
+use crate::ruleset::{Ruleset, RulesetKind};
+use crate::settings::GameSettings;
+use crate::maps;
 use crate::game::{
     Board,
+    BoardError,
     Snake,
     MAX_SNAKE_COUNT,
     GridPoint,
-    WIDTH, HEIGHT, CENTER,
+    CoordType,
+    DEFAULT_WIDTH,
+    DEFAULT_HEIGHT,
 };
 
-pub fn generate_board() -> Board {
-    let snakes = make_snakes();
-    let foods = make_food(&snakes);
+pub fn generate_board(rng: &mut impl Rng, ruleset: RulesetKind, width: CoordType, height: CoordType, snake_count: usize) -> Board {
+    generate_board_with_settings(rng, ruleset, GameSettings::default(), width, height, snake_count)
+        .expect("Standard map fits any board")
+}
+
+/// Board with the initial hazards of the settings' map. Snakes start at the map's positions if it has them.
+/// Fails if the map doesn't support the size of the board or the number of snakes.
+pub fn generate_board_with_settings(
+    rng: &mut impl Rng,
+    ruleset: RulesetKind,
+    settings: GameSettings,
+    width: CoordType,
+    height: CoordType,
+    snake_count: usize,
+) -> Result<Board, BoardError> {
+    maps::check_map_size(settings.map, width, height, snake_count)?;
+
+    let snakes = match maps::start_positions(rng, settings.map, width, height, snake_count) {
+        Some(positions) => positions[..snake_count].iter().map(|&p| new_snake(p)).collect(),
+        None => make_snakes(rng, width, height, snake_count),
+    };
+    let mut foods = if ruleset.is_constrictor() {
+        Vec::new()
+    } else {
+        maps::initial_food(&settings, width, height).unwrap_or_else(|| make_food(rng, width, height, &snakes))
+    };
+    let hazards = maps::initial_hazards(rng, settings.map, width, height);
+    // Food isn't placed on hazards, e.g. the center of rivers and bridges is a lake
+    foods.retain(|p| hazards[(p.x as usize, p.y as usize)] == 0);
     let board = Board::new(
+        ruleset,
+        settings,
+        width,
+        height,
         0,
         Some(foods),
         None,
+        Some(hazards),
         snakes,
     );
 
-    board
+    Ok(board)
 }
 
-fn make_snakes() -> ArrayVec<Snake, MAX_SNAKE_COUNT> {
+fn make_snakes(rng: &mut impl Rng, width: CoordType, height: CoordType, snake_count: usize) -> ArrayVec<Snake, MAX_SNAKE_COUNT> {
     // With fixed positions
-    let rng = &mut thread_rng();
-
 	// Create start 8 points
 	let mn = 1;
-    let md = (WIDTH - 1)/2;
-    let mx = WIDTH - 2;
-
-    let mut start_points = if rng.gen_bool(0.5) {
-        let corner_points = [
-            GridPoint {x: mn, y: mn},
-            GridPoint {x: mn, y: mx},
-            GridPoint {x: mx, y: mn},
-            GridPoint {x: mx, y: mx},
-        ];
-        corner_points
+    let md_x = (width - 1)/2;
+    let md_y = (height - 1)/2;
+    let mx_x = width - 2;
+    let mx_y = height - 2;
+
+    let mut corner_points = [
+        GridPoint {x: mn, y: mn},
+        GridPoint {x: mn, y: mx_y},
+        GridPoint {x: mx_x, y: mn},
+        GridPoint {x: mx_x, y: mx_y},
+    ];
+    let mut cardinal_points = [
+        GridPoint {x: mn, y: md_y},
+        GridPoint {x: md_x, y: mn},
+        GridPoint {x: md_x, y: mx_y},
+        GridPoint {x: mx_x, y: md_y},
+    ];
+    assert!(snake_count <= corner_points.len() + cardinal_points.len(), "Too many snakes: {}", snake_count);
+
+	// Randomly order them
+    corner_points.shuffle(rng);
+    cardinal_points.shuffle(rng);
+
+    let start_points: Vec<_> = if rng.gen_bool(0.5) {
+        corner_points.into_iter().chain(cardinal_points).collect()
     } else {
-        let cardinal_points = [
-            GridPoint {x: mn, y: md},
-            GridPoint {x: md, y: mn},
-            GridPoint {x: md, y: mx},
-            GridPoint {x: mx, y: md},
-        ];
-        cardinal_points
+        cardinal_points.into_iter().chain(corner_points).collect()
     };
-	start_points.shuffle(rng);
 	
 	// Assign to snakes in order given
     let mut snakes = ArrayVec::new();
-    for i in 0..MAX_SNAKE_COUNT {
-        let p = start_points[i];
-        let snake = Snake {
-            health: 100,
-            body: [p, p, p].try_into().unwrap(),
-        };
-        snakes.push(snake);
+    for i in 0..snake_count {
+        snakes.push(new_snake(start_points[i]));
     }
     snakes
 }
 
-fn make_food(snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>) -> Vec<GridPoint> {
-    let rng = &mut thread_rng();
+fn new_snake(p: GridPoint) -> Snake {
+    Snake {
+        health: 100,
+        body: [p, p, p].into(),
+    }
+}
 
+fn make_food(rng: &mut impl Rng, width: CoordType, height: CoordType, snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>) -> Vec<GridPoint> {
 	let center = GridPoint {
-        x: (WIDTH - 1) / 2,
-        y: (HEIGHT - 1) / 2,
+        x: (width - 1) / 2,
+        y: (height - 1) / 2,
     };
 
     let mut foods = Vec::new();
+
+    // Up to 4 snakes can be placed such that food is nearby on small boards.
+    // Otherwise, we skip this and only place food in the center.
+    let is_small_board = width * height < 11 * 11;
+    let snakes_to_feed = if snakes.len() <= 4 || !is_small_board {
+        snakes.as_slice()
+    } else {
+        &[]
+    };
 	
     // Place 1 food within exactly 2 moves of each snake, but never towards the center or in a corner
-    for snake in snakes {
+    for snake in snakes_to_feed {
         let head = snake.head();
         let possible_food_locations = [
             GridPoint {x: head.x - 1, y: head.y - 1},
@@ -90,6 +140,11 @@ fn make_food(snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>) -> Vec<GridPoint> {
         // Remove any invalid/unwanted positions
         let mut available_food_locations = Vec::new();
         for p in possible_food_locations {
+            // Ignore points already occupied by food
+            if foods.contains(&p) {
+                continue;
+            }
+
             // Food must be further than snake from center on at least one axis
             if !(
                 p.x < head.x && head.x < center.x ||
@@ -101,7 +156,7 @@ fn make_food(snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>) -> Vec<GridPoint> {
             }
 
             // Don't spawn food in corners
-            if (p.x == 0 || p.x == (WIDTH - 1)) && (p.y == 0 || p.y == (HEIGHT - 1)) {
+            if (p.x == 0 || p.x == (width - 1)) && (p.y == 0 || p.y == (height - 1)) {
                 continue
             }
 
@@ -145,11 +200,16 @@ pub fn static_board() -> Board {
             body: vec![GridPoint {x: 1, y: 9}, GridPoint {x: 1, y: 9}, GridPoint {x: 1, y: 9}].into(),
         }
     );
-    let foods = vec![CENTER];
+    let foods = vec![GridPoint {x: DEFAULT_WIDTH / 2, y: DEFAULT_HEIGHT / 2}];
     let board = Board::new(
+        RulesetKind::Wrapped,
+        GameSettings::default(),
+        DEFAULT_WIDTH,
+        DEFAULT_HEIGHT,
         0,
         Some(foods),
         None,
+        None,
         snakes,
     );
     board
@@ -2,15 +2,19 @@ use std::mem::{self, MaybeUninit};
 
 use arrayvec::ArrayVec;
 use rand::distributions::{Distribution, Standard};
-use rand::Rng;
-use crate::zobrist::body_direction;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Serialize, Deserialize};
+use crate::ruleset::Ruleset;
+use crate::zobrist::{BodyDirections, ZobristHash, body_direction};
+use crate::bitboard::Bitboard;
+use crate::maps;
 use crate::game::{
     Board,
     MAX_SNAKE_COUNT,
     GridPoint,
-    WIDTH,
-    HEIGHT,
-    SIZE,
+    Rectangle,
+    ObjectsChange,
     Object,
     FOOD,
     BODY,
@@ -49,64 +53,160 @@ impl Distribution<Movement> for Standard {
     }
 }
 
-pub struct EngineSettings<'a, 'b> {
+/// Random generator of the engine and the search.
+/// All randomness goes through it, so a seed reproduces a whole game or search.
+pub type GameRng = StdRng;
+
+/// Seeded generator, or seeded from the OS entropy when there is no seed.
+pub fn new_rng(seed: Option<u64>) -> GameRng {
+    match seed {
+        Some(seed) => GameRng::seed_from_u64(seed),
+        None => GameRng::from_entropy(),
+    }
+}
+
+/// Why a snake was eliminated. Snake indices point to the snake it collided with.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum EliminationCause {
+    OutOfBounds,
+    SelfCollision,
+    BodyCollision(usize),
+    HeadToHead(usize),
+    Starvation,
+    Hazard,
+}
+
+/// Recorded in the board for each eliminated snake.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Elimination {
+    pub turn: i32,
+    pub cause: EliminationCause,
+}
+
+/// What happened on a turn.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct StepOutcome {
+    /// Snakes eliminated on this turn.
+    pub eliminations: [Option<EliminationCause>; MAX_SNAKE_COUNT],
+    pub eaten_food: ArrayVec<GridPoint, MAX_SNAKE_COUNT>,
+    pub spawned_food: Vec<GridPoint>,
+}
+
+impl StepOutcome {
+    pub fn eliminated(&self) -> impl Iterator<Item = (usize, EliminationCause)> + '_ {
+        self.eliminations
+            .iter()
+            .enumerate()
+            .filter_map(|(i, cause)| cause.map(|cause| (i, cause)))
+    }
+}
+
+/// Changes made by undoable steps, reverted by `undo_step` in reverse order.
+/// Search keeps one journal for the whole path, so steps don't allocate.
+#[derive(Default)]
+pub struct StepJournal {
+    objects: Vec<ObjectsChange>,
+    // (cell, stacks before the change)
+    hazards: Vec<(PointUsize, u8)>,
+    // (index in `board.foods`, food) of eaten food
+    eaten_food: Vec<(usize, GridPoint)>,
+}
+
+/// Everything besides the journal needed to revert a step with `undo_step`.
+pub struct StepUndo {
+    turn: i32,
+    zobrist_hash: ZobristHash,
+    safe_zone: Rectangle,
+    eliminations: [Option<Elimination>; MAX_SNAKE_COUNT],
+    snake_bitboards: [Bitboard; MAX_SNAKE_COUNT],
+    hazard_bitboard: Bitboard,
+    // (health, body length, tail) before the step
+    snakes: ArrayVec<(i32, usize, GridPoint), MAX_SNAKE_COUNT>,
+    spawned_food: usize,
+    // Lengths of the journal before the step
+    objects_changes: usize,
+    hazards_changes: usize,
+    eaten_food: usize,
+}
+
+/// Tail which left its cell on the turn and the length of its snake before the move.
+pub type VacatedTail = (GridPoint, usize);
+
+pub struct EngineSettings<'a, 'b, 'c, 'd> {
     // Can append elements to `board.food`, but must not mutate anything else.
-    pub food_spawner: &'a mut dyn FnMut(&mut Board),
+    pub food_spawner: &'a mut dyn FnMut(&mut Board, &mut GameRng),
 
     // Can shrink `board.safe_zone`, but must not mutate anything else.
-    pub safe_zone_shrinker: &'b mut dyn FnMut(&mut Board),
-}
+    pub safe_zone_shrinker: &'b mut dyn FnMut(&mut Board, &mut GameRng),
 
-pub mod food_spawner {
-    use std::collections::{HashSet, hash_map::RandomState};
+    // Can change stacks in `board.hazards`, but must not mutate anything else.
+    pub hazard_spawner: &'c mut dyn FnMut(&mut Board, &[VacatedTail], &mut GameRng),
 
-    use crate::mcts::utils::movement_positions_standard;
+    // Passed to the spawners and the shrinker.
+    pub rng: &'d mut GameRng,
+}
 
+pub mod food_spawner {
     use super::*;
-    use rand::{self, seq::IteratorRandom};
+    use rand::{self, seq::SliceRandom};
+
+    /// Empty cells except the ones next to alive heads, and hazards on maps with hazard walls.
+    /// Official rules don't wrap head neighbours, so it's the same for all rulesets.
+    pub fn get_food_spawn_spots(board: &Board) -> Vec<PointUsize> {
+        let mut heads = Bitboard::EMPTY;
+        for snake in board.snakes.iter().filter(|snake| snake.is_alive()) {
+            heads.set(snake.head().into());
+        }
+        let head_neighbours = heads.neighbours(board.width, board.height, false);
+        let mut empties = board.cells() & !board.objects.occupied & !board.objects.food;
+        if board.settings.map.has_hazard_walls() {
+            empties &= !board.hazard_bitboard;
+        }
 
-    pub fn get_food_spawn_spots(board: &Board) -> HashSet<&PointUsize> {
-        let mut spawn_spots: HashSet<&PointUsize, RandomState> = HashSet::from_iter(board.objects.empties.iter());
-        for snake in &board.snakes {
-            if !snake.is_alive() {
-                continue;
-            }
-            for move_pos in movement_positions_standard(snake.head()) {
-                if board.contains(move_pos) {
-                    let move_pos: PointUsize = move_pos.into();
-                    spawn_spots.remove(&move_pos);
-                }
-            }
+        (empties & !head_neighbours).iter().collect()
+    }
+
+    /// How many food must be spawned after the turn (standard map).
+    pub fn food_needed(rng: &mut impl rand::Rng, board: &Board) -> usize {
+        let settings = &board.settings;
+        if board.foods.len() < settings.minimum_food {
+            return settings.minimum_food - board.foods.len();
+        }
+        // Same as `(100 - rand.Intn(100)) < foodSpawnChance` in official rules.
+        if settings.food_spawn_chance > 0 && 100 - rng.gen_range(0..100) < settings.food_spawn_chance {
+            1
+        } else {
+            0
         }
-        spawn_spots
     }
 
-    fn spawn_one(rng: &mut impl rand::Rng, board: &mut Board) {
+    pub fn spawn_randomly(rng: &mut impl rand::Rng, board: &mut Board, n: usize) {
         let spawn_spots = get_food_spawn_spots(board);
 
-        if let Some(&&food_spot) = spawn_spots.iter().choose(rng) {
+        for &food_spot in spawn_spots.choose_multiple(rng, n) {
             board.put_food(GridPoint::from(food_spot));
         }
     }
 
-    pub fn create_standard(board: &mut Board) {
+    pub fn create_standard(board: &mut Board, rng: &mut GameRng) {
         // For engine use only! It changes board.objects internal state
-        let random = &mut rand::thread_rng();
-        if board.foods.len() < 1 || random.gen_ratio(20, 100) {
-            spawn_one(random, board);
+        if maps::spawn_food(board, rng) {
+            return;
+        }
+        let n = food_needed(rng, board);
+        if n > 0 {
+            spawn_randomly(rng, board, n);
         }
     }
 
     #[allow(dead_code)]
-    pub fn noop(_: &mut Board) {
+    pub fn noop(_: &mut Board, _: &mut GameRng) {
     }
 }
 
 pub mod safe_zone_shrinker {
     use super::*;
 
-    pub const SHRINK_EVERY: i32 = 20;
-
     pub fn shrink(board: &mut Board, side: Movement) {
         match side {
             Movement::Left => board.safe_zone.p0.x += 1,
@@ -116,24 +216,73 @@ pub mod safe_zone_shrinker {
         }
     }
 
-    pub fn standard(board: &mut Board) {
-        if board.turn == 0 || board.turn % SHRINK_EVERY != 0 || board.safe_zone.empty(){
-            return;
+    /// Royale (ruleset or map) shrinks a side of the safe zone on the turn.
+    pub fn is_shrink_turn(board: &Board, turn: i32) -> bool {
+        if !board.ruleset.is_royale() && !board.settings.map.shrinks_safe_zone() {
+            return false;
+        }
+        let shrink_every = board.settings.shrink_every_n_turns;
+        turn > 0 && shrink_every > 0 && turn % shrink_every == 0 && !board.safe_zone.empty()
+    }
+
+    /// Shrinks a random side, the rng is the only source of randomness.
+    pub fn standard(board: &mut Board, rng: &mut GameRng) {
+        if is_shrink_turn(board, board.turn) {
+            let side: Movement = rng.gen();
+            shrink(board, side);
+        }
+    }
+
+    /// Shrinks the given side on the turns when the standard shrinker shrinks a random one.
+    pub fn fixed(side: Movement) -> impl FnMut(&mut Board, &mut GameRng) {
+        move |board, _| {
+            if is_shrink_turn(board, board.turn) {
+                shrink(board, side);
+            }
         }
-        let side: Movement = rand::random();
-        shrink(board, side);
     }
 
     #[allow(dead_code)]
-    pub fn noop(_: &mut Board) {
+    pub fn noop(_: &mut Board, _: &mut GameRng) {
+    }
+}
+
+pub mod hazard_spawner {
+    use super::*;
+
+    /// Hazards of the board's map.
+    pub fn standard(board: &mut Board, vacated_tails: &[VacatedTail], rng: &mut GameRng) {
+        maps::update_hazards(board, vacated_tails, rng);
+    }
+
+    /// Spiral with the known center, e.g. found by `maps::find_spiral_center`.
+    /// Spiral hazards only depend on the turn, without searching the center on every step.
+    pub fn spiral(center: GridPoint) -> impl FnMut(&mut Board, &[VacatedTail], &mut GameRng) {
+        move |board, _, _| maps::add_spiral_hazard(board, center)
+    }
+
+    /// Spawner for search: `spiral` with the center found at the root by `maps::spiral_center`,
+    /// otherwise `standard`.
+    pub fn search(spiral_center: Option<GridPoint>) -> impl FnMut(&mut Board, &[VacatedTail], &mut GameRng) {
+        let mut spiral = spiral_center.map(spiral);
+        move |board, vacated_tails, rng| match &mut spiral {
+            Some(spiral) => spiral(board, vacated_tails, rng),
+            None => standard(board, vacated_tails, rng),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn noop(_: &mut Board, _: &[VacatedTail], _: &mut GameRng) {
     }
 }
 
 #[allow(dead_code)]
-pub fn advance_one_step(board: &mut Board, actions: [usize; MAX_SNAKE_COUNT]) {
+pub fn advance_one_step(board: &mut Board, actions: [usize; MAX_SNAKE_COUNT]) -> StepOutcome {
     let mut settings = EngineSettings {
         food_spawner: &mut food_spawner::create_standard,
         safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+        hazard_spawner: &mut hazard_spawner::standard,
+        rng: &mut new_rng(None),
     };
 
     advance_one_step_with_settings(board, &mut settings, actions)
@@ -144,9 +293,21 @@ pub fn advance_one_step_with_settings(
     board: &mut Board,
     engine_settings: &mut EngineSettings,
     actions: [usize; MAX_SNAKE_COUNT]
-) {
+) -> StepOutcome {
+    advance(board, engine_settings, actions, None)
+}
+
+// Step which records food and hazard changes to the journal, if it's given.
+fn advance(
+    board: &mut Board,
+    engine_settings: &mut EngineSettings,
+    actions: [usize; MAX_SNAKE_COUNT],
+    mut journal: Option<&mut StepJournal>,
+) -> StepOutcome {
     debug_assert!(!board.is_terminal(), "{}", board);
 
+    let mut outcome = StepOutcome::default();
+
     board.zobrist_hash.xor_turn(board.turn);
     board.turn += 1;
     board.zobrist_hash.xor_turn(board.turn);
@@ -155,47 +316,24 @@ pub fn advance_one_step_with_settings(
         board.snakes.iter().all(|snake| snake.body.len() > 2)
     );
 
-    // Pre update
-    
-    // ... Empty in royale
-
-    
-    // Ruleset (Wrapped)
+    // Tails are gone after the move, but the hazard spawner may leave something in their place.
+    let vacated_tails = vacated_tails(board);
 
     let alive_snakes: ArrayVec<usize, MAX_SNAKE_COUNT> = (0..board.snakes.len()).filter(|&i| board.snakes[i].is_alive()).collect();
 
+    // Health is hashed back when all the health changes are applied.
+    for &i in &alive_snakes {
+        board.zobrist_hash.xor_health(i, board.snakes[i].health);
+    }
+
     let objects_under_head: [Object; MAX_SNAKE_COUNT] = {
         let mut objects_under_head: [MaybeUninit<Object>; MAX_SNAKE_COUNT] = unsafe { MaybeUninit::uninit().assume_init() };
         let mut eaten_food = ArrayVec::<_, MAX_SNAKE_COUNT>::new();
 
+        // Ruleset: Move snakes. Tails move first, so a head can follow any tail which is not stacked.
         for &snake_i in &alive_snakes {
-
-            // Ruleset: Move snakes (standard)
-            let movement = actions[snake_i];
             let snake = &mut board.snakes[snake_i];
             debug_assert!(snake.body.len() > 2);
-            let old_head = snake.head();
-            let mut new_head = old_head + Movement::from_usize(movement).to_direction();
-
-            // Ruleset: Move snakes (wrapped)
-            if new_head.x < 0 {
-                new_head.x = WIDTH - 1;
-            } else
-            if new_head.y < 0 {
-                new_head.y = HEIGHT - 1;
-            } else
-            if new_head.x == WIDTH {
-                new_head.x = 0;
-            } else
-            if new_head.y == HEIGHT {
-                new_head.y = 0;
-            }
-
-            snake.body.push_front(new_head);
-
-            // Move neck (first body part next to head)
-            let new_neck_direction = body_direction(old_head, new_head);
-            board.zobrist_hash.xor_body_direction(old_head.into(), snake_i, new_neck_direction);
 
             // Remove old tail
             let old_tail = snake.body.pop_back().unwrap();
@@ -212,33 +350,68 @@ pub fn advance_one_step_with_settings(
 
             if old_tail != new_tail {
                 board.objects.set_empty_on_body(old_tail.into());
+                board.snake_bitboards[snake_i].unset(old_tail.into());
             }
+        }
+
+        for &snake_i in &alive_snakes {
+
+            // Ruleset: Move heads (standard or wrapped)
+            let movement = Movement::from_usize(actions[snake_i]);
+            let old_head = board.snakes[snake_i].head();
+            let new_head = board.movement_position(old_head, movement);
+            let is_inside = board.contains(new_head);
+            // Negative damage heals (healing pools)
+            let hazard_damage = if is_inside { board.settings.hazard_damage_per_turn * board.hazard_stacks(new_head) as i32 } else { 0 };
+
+            let snake = &mut board.snakes[snake_i];
+            let new_tail = snake.body[snake.body.len() - 1];
+
+            snake.body.push_front(new_head);
+
+            // Move neck (first body part next to head)
+            let new_neck_direction = body_direction(old_head, new_head);
+            board.zobrist_hash.xor_body_direction(old_head.into(), snake_i, new_neck_direction);
             
             // WARN: The head will be set in a separate loop.
 
             // Ruleset: starvation (standard)
             snake.health -= 1;
+            let is_starved = snake.health <= 0;
             
             // Rulset: Feed or maybe do hazard damage
-            let object_under_head = board.objects.get(new_head.into());
+            // Out of bounds head is eliminated as if it has collided with a wall (body).
+            let object_under_head = if is_inside { board.objects.get(new_head.into()) } else { BODY };
             if object_under_head == FOOD {
                 snake.health = 100;
                 snake.body.push_back(new_tail);
+                board.zobrist_hash.xor_body_direction(new_tail.into(), snake_i, BodyDirections::Still);
                 eaten_food.push(new_head);
-            } else
-            if !board.safe_zone.contains(new_head) {
-                snake.health -= 14;
+            } else if hazard_damage != 0 {
+                snake.health = (snake.health - hazard_damage).clamp(0, 100);
             }
 
+            // Same priority as in official rules: out of health, then out of bounds, then collisions.
+            outcome.eliminations[snake_i] = if !snake.is_alive() {
+                Some(if is_starved { EliminationCause::Starvation } else { EliminationCause::Hazard })
+            } else if !is_inside {
+                Some(EliminationCause::OutOfBounds)
+            } else {
+                None
+            };
+
             objects_under_head[snake_i] = MaybeUninit::new(object_under_head);
 
             debug_assert!(board.objects.is_body(new_tail.into()));
-            debug_assert!(board.contains(new_head));
         }
 
         for food in eaten_food {
             if let Some(food_i) = board.foods.iter().position(|&x| x == food) {
+                outcome.eaten_food.push(food);
                 board.foods.swap_remove(food_i);
+                if let Some(journal) = &mut journal {
+                    journal.eaten_food.push((food_i, food));
+                }
                 // TODO: Проверить, что никто этот эмпти потом не будет изменять под другим типом
                 board.objects.set_empty_on_food(food.into());
                 board.zobrist_hash.xor_food(food.into());
@@ -255,8 +428,14 @@ pub fn advance_one_step_with_settings(
         for &i in &alive_snakes {
             let object_under_head = objects_under_head[i];
 
+            if outcome.eliminations[i].is_some() {
+                died_snakes.push(i);
+                continue;
+            }
+
             // Collided with themselves or another battlesnake.
             if object_under_head == BODY {
+                outcome.eliminations[i] = Some(body_collision_cause(board, i, &alive_snakes));
                 died_snakes.push(i);
                 continue;
             }
@@ -274,6 +453,7 @@ pub fn advance_one_step_with_settings(
                     && snake.body.len() <= other_snake.body.len()
                     && snake.head() == other_snake.head()
                 {
+                    outcome.eliminations[i] = Some(EliminationCause::HeadToHead(j));
                     died_snakes.push(i);
                     break;
                 }
@@ -282,6 +462,7 @@ pub fn advance_one_step_with_settings(
 
         for i in died_snakes {
             board.snakes[i].health = 0;
+            board.eliminations[i] = outcome.eliminations[i].map(|cause| Elimination {turn: board.turn, cause});
         }
     }
 
@@ -302,91 +483,190 @@ pub fn advance_one_step_with_settings(
 
                 let mut front = snake.head();
                 
-                for j in 1..snake.body.len() - 1 {
+                for j in 1..snake.body.len() {
                     let back = snake.body[j];
                     let body_part_direction = body_direction( back, front);
-                    
-                    board.objects.set_empty_on_body(back.into());
                     board.zobrist_hash.xor_body_direction(back.into(), i, body_part_direction);
 
-                    front = back;
-                }
+                    // Don't remove stacked tail cell twice (when ate food or on first turn).
+                    if back != front {
+                        board.objects.set_empty_on_body(back.into());
+                    }
 
-                // TODO: Check hashing behaviour. Hash must be consistent in
-                //  multiple bodies on the cell problem.
-                // Don't remove tail cell twice when on tail other part (when ate food or on first turn).
-                let back = snake.body[snake.body.len() - 1];
-                if back != front {
-                    let body_part_direction = body_direction( back, front);                    
-                    board.objects.set_empty_on_body(back.into());
-                    board.zobrist_hash.xor_body_direction(back.into(), i, body_part_direction);
+                    front = back;
                 }
+                board.snake_bitboards[i] = Bitboard::EMPTY;
             }
         }
 
         // Restore alive heads
-        for i in alive_snakes {
+        for &i in &alive_snakes {
             if board.snakes[i].is_alive() {
                 board.objects.set_body_on_empty(board.snakes[i].head().into());
+                board.snake_bitboards[i].set(board.snakes[i].head().into());
             }
         }
     }
 
     // Post map update (Royale).
     
-    // Food spawn (Standard)
-    (engine_settings.food_spawner)(board);
+    if board.ruleset.is_constrictor() {
+        // Food spawn (Constrictor): remove all food
+        for food in mem::take(&mut board.foods) {
+            board.objects.set_empty_on_food(food.into());
+            board.zobrist_hash.xor_food(food.into());
+        }
 
-    // Hazard spawn (Royale)
-    (engine_settings.safe_zone_shrinker)(board);
+        // Ruleset: always grow (Constrictor)
+        for (i, snake) in board.snakes.iter_mut().enumerate().filter(|(_, snake)| snake.is_alive()) {
+            snake.health = 100;
+            let tail = snake.body[snake.body.len() - 1];
+            let pretail = snake.body[snake.body.len() - 2];
+            if tail != pretail {
+                snake.body.push_back(tail);
+                board.zobrist_hash.xor_body_direction(tail.into(), i, BodyDirections::Still);
+            }
+        }
+    }
 
+    for &i in &alive_snakes {
+        board.zobrist_hash.xor_health(i, board.snakes[i].health);
+    }
 
-    if cfg!(debug_assertions) {
-        let mut empties_on_map = 0;
-        for x in 0..WIDTH as usize {
-            for y in 0..HEIGHT as usize {
-                let pos = PointUsize {x, y};
-                if board.objects.is_empty(pos) {
-                    empties_on_map += 1;
-                    let i = board.objects.get(pos);
-                    assert_eq!(board.objects.empties[i as usize], pos, "empties[{}] is not {:?}", i, pos);
+    if !board.ruleset.is_constrictor() {
+        // Food spawn (Standard)
+        let foods_count = board.foods.len();
+        (engine_settings.food_spawner)(board, engine_settings.rng);
+        outcome.spawned_food.extend_from_slice(&board.foods[foods_count..]);
+    }
+
+    // Hazard spawn (Royale)
+    let safe_zone = board.safe_zone;
+    (engine_settings.safe_zone_shrinker)(board, engine_settings.rng);
+    if board.safe_zone != safe_zone {
+        board.zobrist_hash.xor_safe_zone(&safe_zone);
+        board.zobrist_hash.xor_safe_zone(&board.safe_zone);
+        board.hazard_bitboard = board.calculate_hazard_bitboard();
+    }
+
+    // Hazard spawn (maps)
+    let hazards = board.hazards.clone();
+    (engine_settings.hazard_spawner)(board, &vacated_tails, engine_settings.rng);
+    if board.hazards != hazards {
+        for x in 0..board.width as usize {
+            for y in 0..board.height as usize {
+                let stacks = board.hazards[(x, y)];
+                if stacks != hazards[(x, y)] {
+                    board.zobrist_hash.xor_hazard(PointUsize {x, y}, hazards[(x, y)]);
+                    board.zobrist_hash.xor_hazard(PointUsize {x, y}, stacks);
+                    if let Some(journal) = &mut journal {
+                        journal.hazards.push((PointUsize {x, y}, hazards[(x, y)]));
+                    }
                 }
             }
         }
-        assert_eq!(empties_on_map, board.objects.empties.len());
+        board.hazard_bitboard = board.calculate_hazard_bitboard();
     }
 
-    debug_assert_eq!(
-        board.objects.empties_count(),
-        SIZE as usize - board.foods.len() - board.snakes
-            .iter()
-            .filter(|snake| snake.is_alive())
-            .map(|snake| {
-                let mut body_occupied = snake.body.len();
-                for i in 1..snake.body.len() {
-                    if snake.body[i - 1] == snake.body[i] {
-                        body_occupied = i;
-                        break;
-                    }
-                }
-                body_occupied
-            })
-            .sum::<usize>(),
-        "{}",
-        board,
-    );
+    if cfg!(debug_assertions) {
+        if let Err(error) = board.validate() {
+            panic!("{}\n{}", error, board);
+        }
+    }
 
-    debug_assert!(
-        board.snakes
-            .iter()
-            .filter(|snake| snake.is_alive())
-            .all(|snake| board.foods.iter().all(|food| !snake.body.contains(&food)))
-    );
+    outcome
+}
 
-    // if cfg!(debug_assertions) {
-    //     println!("{:?} {}", board, board);
-    //     println!("{}", board);
-    // }
+/// Must be called before the snakes move. Stacked tails stay in place and are not vacated.
+fn vacated_tails(board: &Board) -> ArrayVec<VacatedTail, MAX_SNAKE_COUNT> {
+    board.snakes
+        .iter()
+        .filter(|snake| snake.is_alive() && snake.body[snake.body.len() - 2] != snake.tail())
+        .map(|snake| (snake.tail(), snake.body.len()))
+        .collect()
+}
+
+/// Same as `advance_one_step_with_settings`, but the step can be reverted with `undo_step`.
+/// Search uses it to walk down and back up a single board instead of cloning it.
+/// Changes are recorded to the journal, steps must be undone in reverse order with the same journal.
+/// Spawners and shrinkers must not change the board except appending to `foods`, `safe_zone` and `hazards`.
+pub fn advance_one_step_undoable(
+    board: &mut Board,
+    engine_settings: &mut EngineSettings,
+    actions: [usize; MAX_SNAKE_COUNT],
+    journal: &mut StepJournal,
+) -> (StepOutcome, StepUndo) {
+    let mut undo = StepUndo {
+        turn: board.turn,
+        zobrist_hash: board.zobrist_hash,
+        safe_zone: board.safe_zone,
+        eliminations: board.eliminations,
+        snake_bitboards: board.snake_bitboards,
+        hazard_bitboard: board.hazard_bitboard,
+        snakes: board.snakes.iter().map(|snake| (snake.health, snake.body.len(), snake.tail())).collect(),
+        spawned_food: 0,
+        objects_changes: journal.objects.len(),
+        hazards_changes: journal.hazards.len(),
+        eaten_food: journal.eaten_food.len(),
+    };
+
+    board.objects.start_journal(mem::take(&mut journal.objects));
+    let outcome = advance(board, engine_settings, actions, Some(journal));
+    journal.objects = board.objects.take_journal();
+    undo.spawned_food = outcome.spawned_food.len();
+
+    (outcome, undo)
+}
+
+/// Reverts the last step made by `advance_one_step_undoable`. The board is restored exactly.
+pub fn undo_step(board: &mut Board, undo: StepUndo, journal: &mut StepJournal) {
+    for (snake, &(health, len, tail)) in board.snakes.iter_mut().zip(&undo.snakes) {
+        if health > 0 {
+            snake.body.pop_front();
+            snake.body.truncate(len - 1);
+            snake.body.push_back(tail);
+        }
+        snake.health = health;
+    }
+
+    board.objects.revert(&mut journal.objects, undo.objects_changes);
+
+    board.foods.truncate(board.foods.len() - undo.spawned_food);
+    while journal.eaten_food.len() > undo.eaten_food {
+        let (index, food) = journal.eaten_food.pop().unwrap();
+        // Reverse of `swap_remove`
+        if index == board.foods.len() {
+            board.foods.push(food);
+        } else {
+            let moved = mem::replace(&mut board.foods[index], food);
+            board.foods.push(moved);
+        }
+    }
+
+    for (p, stacks) in journal.hazards.drain(undo.hazards_changes..) {
+        board.hazards[(p.x, p.y)] = stacks;
+    }
+
+    board.turn = undo.turn;
+    board.zobrist_hash = undo.zobrist_hash;
+    board.safe_zone = undo.safe_zone;
+    board.eliminations = undo.eliminations;
+    board.snake_bitboards = undo.snake_bitboards;
+    board.hazard_bitboard = undo.hazard_bitboard;
+}
+
+/// Whose body the head of snake `i` hit. Own body is checked first, as in official rules.
+fn body_collision_cause(board: &Board, i: usize, alive_snakes: &[usize]) -> EliminationCause {
+    let head = board.snakes[i].head();
+    let hits_body = |j: usize| board.snakes[j].body.iter().skip(1).any(|&p| p == head);
+
+    if hits_body(i) {
+        return EliminationCause::SelfCollision;
+    }
+
+    let other = alive_snakes.iter().copied().find(|&j| j != i && hits_body(j));
+    debug_assert!(other.is_some(), "No body under the head of snake {}\n{}", i, board);
+    EliminationCause::BodyCollision(other.unwrap_or(i))
 }
 
 #[cfg(test)]
@@ -496,9 +776,10 @@ mod tests {
         let mut board = create_board(data::BODY_COLLISION);
         assert!(board.snakes[0].is_alive());
         assert!(board.snakes[1].is_alive());
-        advance_one_step(&mut board, [Movement::Right as usize; MAX_SNAKE_COUNT]);
+        let outcome = advance_one_step(&mut board, [Movement::Right as usize; MAX_SNAKE_COUNT]);
         assert!(!board.snakes[0].is_alive());
         assert!(board.snakes[1].is_alive());
+        assert_eq!(outcome.eliminated().collect::<Vec<_>>(), vec![(0, EliminationCause::BodyCollision(1))]);
     }
 
     #[test]
@@ -507,18 +788,22 @@ mod tests {
         let mut actions = [0; MAX_SNAKE_COUNT];
         actions[0] = Movement::Down as usize;
         actions[1] = Movement::Right as usize;
-        advance_one_step(&mut board, actions);
+        let outcome = advance_one_step(&mut board, actions);
         assert!(!board.snakes[0].is_alive());
         assert!(board.snakes[1].is_alive());
+        assert_eq!(outcome.eliminated().collect::<Vec<_>>(), vec![(0, EliminationCause::HeadToHead(1))]);
 
 
         let mut board = create_board(data::FOOD_HEAD_TO_HEAD_EQUAL);
         let mut actions = [0; MAX_SNAKE_COUNT];
         actions[0] = Movement::Right as usize;
         actions[1] = Movement::Left as usize;
-        advance_one_step(&mut board, actions);
+        let outcome = advance_one_step(&mut board, actions);
         assert!(!board.snakes[0].is_alive());
         assert!(!board.snakes[1].is_alive());
+        assert_eq!(outcome.eliminations[0], Some(EliminationCause::HeadToHead(1)));
+        assert_eq!(outcome.eliminations[1], Some(EliminationCause::HeadToHead(0)));
+        assert_eq!(outcome.eaten_food.len(), 1);
 
 
         let mut board = create_board(data::FOOD_HEAD_TO_HEAD_EQUAL_V2);
@@ -550,9 +835,10 @@ mod tests {
         let mut actions = [0; MAX_SNAKE_COUNT];
         actions[0] = Movement::Down as usize;
         actions[1] = Movement::Left as usize;
-        advance_one_step(&mut board, actions);
+        let outcome = advance_one_step(&mut board, actions);
         assert!(!board.snakes[0].is_alive());
         assert!(board.snakes[1].is_alive());
+        assert_eq!(outcome.eliminated().collect::<Vec<_>>(), vec![(0, EliminationCause::Starvation)]);
     }
 
     #[test]
@@ -610,18 +896,449 @@ mod tests {
         assert_eq!(board, board_copy);
     }
 
+    #[test]
+    fn snake_wraps_around_board_of_any_size() {
+        use crate::game::{Snake, Point};
+        use crate::ruleset::RulesetKind;
+        use crate::settings::GameSettings;
+
+        for size in [7, 11, 19] {
+            let mut snakes = ArrayVec::new();
+            snakes.push(Snake {
+                health: 100,
+                body: [Point {x: 0, y: 1}, Point {x: 1, y: 1}, Point {x: 2, y: 1}].into(),
+            });
+            snakes.push(Snake {
+                health: 100,
+                body: [Point {x: 3, y: size - 1}, Point {x: 3, y: size - 2}, Point {x: 3, y: size - 3}].into(),
+            });
+            let mut board = Board::new(RulesetKind::Wrapped, GameSettings::default(), size, size, 0, Some(vec![Point {x: 5, y: 5}]), None, None, snakes);
+
+            let mut actions = [0; MAX_SNAKE_COUNT];
+            actions[0] = Movement::Left as usize;
+            actions[1] = Movement::Up as usize;
+
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::noop,
+                safe_zone_shrinker: &mut safe_zone_shrinker::noop,
+                hazard_spawner: &mut hazard_spawner::noop,
+                rng: &mut new_rng(Some(0)),
+            };
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+            assert!(board.snakes.iter().all(|snake| snake.is_alive()));
+            assert_eq!(board.snakes[0].head(), Point {x: size - 1, y: 1});
+            assert_eq!(board.snakes[1].head(), Point {x: 3, y: 0});
+            assert_eq!(board.objects.empties_count(), board.size() - 1 - 6);
+        }
+    }
+
+    #[test]
+    fn snake_hits_wall_in_standard_ruleset() {
+        use crate::game::{Snake, Point};
+        use crate::ruleset::RulesetKind;
+        use crate::settings::GameSettings;
+
+        let mut snakes = ArrayVec::new();
+        snakes.push(Snake {
+            health: 100,
+            body: [Point {x: 0, y: 1}, Point {x: 1, y: 1}, Point {x: 2, y: 1}].into(),
+        });
+        snakes.push(Snake {
+            health: 100,
+            body: [Point {x: 5, y: 8}, Point {x: 5, y: 7}, Point {x: 5, y: 6}].into(),
+        });
+        let mut board = Board::new(RulesetKind::Standard, GameSettings::default(), 11, 11, 0, Some(vec![Point {x: 5, y: 5}]), None, None, snakes);
+
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Left as usize;
+        actions[1] = Movement::Up as usize;
+
+        let mut settings = EngineSettings {
+            food_spawner: &mut food_spawner::noop,
+            safe_zone_shrinker: &mut safe_zone_shrinker::noop,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        let outcome = advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+        assert!(!board.snakes[0].is_alive());
+        assert!(board.snakes[1].is_alive());
+        assert_eq!(board.objects.empties_count(), board.size() - 1 - 3);
+        assert_eq!(outcome.eliminated().collect::<Vec<_>>(), vec![(0, EliminationCause::OutOfBounds)]);
+    }
+
+    #[test]
+    fn constrictor_snakes_grow_every_turn() {
+        use crate::game::{Snake, Point};
+        use crate::ruleset::RulesetKind;
+        use crate::settings::GameSettings;
+
+        let mut snakes = ArrayVec::new();
+        snakes.push(Snake {
+            health: 100,
+            body: [Point {x: 1, y: 1}, Point {x: 1, y: 1}, Point {x: 1, y: 1}].into(),
+        });
+        snakes.push(Snake {
+            health: 100,
+            body: [Point {x: 9, y: 9}, Point {x: 9, y: 9}, Point {x: 9, y: 9}].into(),
+        });
+        let mut board = Board::new(RulesetKind::Constrictor, GameSettings::default(), 11, 11, 0, Some(vec![Point {x: 5, y: 5}]), None, None, snakes);
+
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Up as usize;
+        actions[1] = Movement::Down as usize;
+
+        for turn in 1..=5 {
+            advance_one_step(&mut board, actions);
+
+            assert!(board.foods.is_empty());
+            for snake in &board.snakes {
+                assert_eq!(snake.health, 100);
+                assert_eq!(snake.tail(), snake.body[snake.body.len() - 2]);
+            }
+            // Stacked tail unfolds on the first two turns, then snakes grow by one every turn.
+            let expected_len = if turn < 2 { 3 } else { turn + 2 };
+            assert_eq!(board.snakes[0].body.len(), expected_len as usize);
+        }
+        assert_eq!(board.snakes[0].tail(), Point {x: 1, y: 1});
+    }
+
+    #[test]
+    fn eight_snakes_game() {
+        use crate::board_generator::generate_board;
+        use crate::mcts::utils::get_first_able_actions_from_masks;
+        use crate::ruleset::RulesetKind;
+
+        let mut board = generate_board(&mut new_rng(None), RulesetKind::Standard, 19, 19, 8);
+        assert_eq!(board.snakes.len(), 8);
+        assert_eq!(board.foods.len(), 9);
+
+        // Engine's debug checks validate board consistency on each step
+        for _ in 0..200 {
+            if board.is_terminal() {
+                break;
+            }
+            let actions = get_first_able_actions_from_masks(&board);
+            advance_one_step(&mut board, actions);
+        }
+    }
+
+    #[test]
+    fn same_seed_reproduces_game() {
+        use crate::board_generator::generate_board;
+        use crate::mcts::utils::get_random_actions_from_masks;
+        use crate::ruleset::RulesetKind;
+
+        let play = |seed| {
+            let rng = &mut new_rng(Some(seed));
+            let mut board = generate_board(rng, RulesetKind::Standard, 11, 11, 4);
+            let mut boards = vec![board.clone()];
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::create_standard,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+            while !board.is_terminal() {
+                let actions = get_random_actions_from_masks(settings.rng, &board);
+                advance_one_step_with_settings(&mut board, &mut settings, actions);
+                boards.push(board.clone());
+            }
+            boards
+        };
+
+        let boards = play(7);
+        let boards_again = play(7);
+        assert_eq!(boards.len(), boards_again.len());
+        for (board, board_again) in boards.iter().zip(&boards_again) {
+            assert_eq!(board.snakes, board_again.snakes);
+            assert_eq!(board.foods, board_again.foods);
+            assert_eq!(board.safe_zone, board_again.safe_zone);
+            assert_eq!(board.zobrist_hash, board_again.zobrist_hash);
+        }
+    }
+
+    #[test]
+    fn undo_step_restores_board() {
+        use crate::board_generator::generate_board_with_settings;
+        use crate::mcts::utils::get_random_actions_from_masks;
+        use crate::ruleset::RulesetKind;
+        use crate::settings::{GameMap, GameSettings};
+
+        let sinkholes = GameSettings {shrink_every_n_turns: 3, map: GameMap::Sinkholes, ..GameSettings::default()};
+        let snail_mode = GameSettings {map: GameMap::SnailMode, ..GameSettings::default()};
+        let spiral = GameSettings {map: GameMap::Spiral, ..GameSettings::default()};
+        let royale = GameSettings {shrink_every_n_turns: 5, map: GameMap::Royale, ..GameSettings::default()};
+        for (ruleset, settings) in [
+            (RulesetKind::Standard, GameSettings::default()),
+            (RulesetKind::Royale, GameSettings {shrink_every_n_turns: 5, ..GameSettings::default()}),
+            (RulesetKind::Wrapped, royale),
+            (RulesetKind::Wrapped, GameSettings::default()),
+            (RulesetKind::Constrictor, GameSettings::default()),
+            (RulesetKind::Standard, sinkholes),
+            (RulesetKind::Wrapped, snail_mode),
+            (RulesetKind::Wrapped, spiral),
+        ] {
+            let rng = &mut new_rng(Some(3));
+            let mut board = generate_board_with_settings(rng, ruleset, settings, 11, 11, 4).unwrap();
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::create_standard,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+
+            let mut boards = vec![board.clone()];
+            let mut undos = Vec::new();
+            let mut journal = StepJournal::default();
+            while !board.is_terminal() {
+                let actions = get_random_actions_from_masks(settings.rng, &board);
+                let (_, undo) = advance_one_step_undoable(&mut board, &mut settings, actions, &mut journal);
+                undos.push(undo);
+                boards.push(board.clone());
+            }
+
+            boards.pop();
+            while let Some(undo) = undos.pop() {
+                undo_step(&mut board, undo, &mut journal);
+                assert_eq!(board, boards.pop().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn food_spawns_up_to_minimum() {
+        use crate::game::Point;
+
+        let mut board = two_snakes_with_hazards(vec![Point {x: 5, y: 5}]);
+        board.settings.minimum_food = 4;
+        board.settings.food_spawn_chance = 0;
+        let rng = &mut new_rng(Some(0));
+
+        food_spawner::create_standard(&mut board, rng);
+        assert_eq!(board.foods.len(), 4);
+        for snake in &board.snakes {
+            for movement in MOVEMENTS {
+                assert!(!board.foods.contains(&(snake.head() + movement.to_direction())));
+            }
+        }
+
+        // Enough food and no chance to spawn
+        food_spawner::create_standard(&mut board, rng);
+        assert_eq!(board.foods.len(), 4);
+    }
+
+    #[test]
+    fn only_royale_shrinks_safe_zone() {
+        use crate::ruleset::RulesetKind;
+        use crate::settings::GameMap;
+
+        let mut board = two_snakes_with_hazards(vec![]);
+        board.turn = board.settings.shrink_every_n_turns;
+        let safe_zone = board.safe_zone;
+        let rng = &mut new_rng(Some(0));
+
+        safe_zone_shrinker::standard(&mut board, rng);
+        assert_eq!(board.safe_zone, safe_zone);
+
+        for (ruleset, map) in [(RulesetKind::Royale, GameMap::Standard), (RulesetKind::Wrapped, GameMap::Royale)] {
+            let mut royale = board.clone();
+            royale.ruleset = ruleset;
+            royale.settings.map = map;
+            safe_zone_shrinker::standard(&mut royale, rng);
+            assert_ne!(royale.safe_zone, safe_zone);
+
+            let mut royale = board.clone();
+            royale.ruleset = ruleset;
+            royale.settings.map = map;
+            safe_zone_shrinker::fixed(Movement::Left)(&mut royale, rng);
+            assert_eq!(royale.safe_zone.p0.x, safe_zone.p0.x + 1);
+
+            // Not a shrink turn
+            royale.turn += 1;
+            safe_zone_shrinker::fixed(Movement::Left)(&mut royale, rng);
+            assert_eq!(royale.safe_zone.p0.x, safe_zone.p0.x + 1);
+        }
+    }
+
     #[test]
     fn snake_dies_from_hunger() {
-        // TODO
+        use crate::game::Point;
+
+        let mut board = two_snakes_with_hazards(vec![Point {x: 5, y: 5}]);
+        board.snakes[0].health = 1;
+        board.zobrist_hash = board.calculate_zobrist_hash();
+
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Right as usize;
+        actions[1] = Movement::Left as usize;
+        let outcome = advance_one_step(&mut board, actions);
+
+        assert!(!board.snakes[0].is_alive());
+        assert_eq!(outcome.eliminated().collect::<Vec<_>>(), vec![(0, EliminationCause::Starvation)]);
+        assert_eq!(board.eliminations[0], Some(Elimination {turn: 1, cause: EliminationCause::Starvation}));
+        assert_eq!(board.eliminations[1], None);
+        assert_eq!(board.placements()[..2], [1, 0]);
+    }
+
+    #[test]
+    fn snake_dies_in_hazard() {
+        use crate::game::Point;
+
+        let mut board = two_snakes_with_hazards(vec![Point {x: 5, y: 5}]);
+        board.snakes[0].health = 20;
+        board.zobrist_hash = board.calculate_zobrist_hash();
+        board.settings.minimum_food = 3;
+
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Up as usize;
+        actions[1] = Movement::Left as usize;
+        let outcome = advance_one_step(&mut board, actions);
+
+        assert!(!board.snakes[0].is_alive());
+        assert_eq!(outcome.eliminated().collect::<Vec<_>>(), vec![(0, EliminationCause::Hazard)]);
+        assert!(outcome.eaten_food.is_empty());
+        assert_eq!(outcome.spawned_food.len(), 2);
+        assert_eq!(outcome.spawned_food, board.foods[1..]);
+    }
+
+    #[test]
+    fn snake_can_follow_tail_of_another_snake() {
+        use crate::game::{Snake, Point};
+        use crate::ruleset::RulesetKind;
+        use crate::settings::GameSettings;
+
+        let chaser = [Point {x: 2, y: 1}, Point {x: 1, y: 1}, Point {x: 0, y: 1}];
+        let leader = [Point {x: 4, y: 2}, Point {x: 4, y: 1}, Point {x: 3, y: 1}];
+
+        // The result must not depend on the order in which snakes are moved.
+        for chaser_i in 0..2 {
+            let mut snakes = ArrayVec::new();
+            for body in if chaser_i == 0 { [chaser, leader] } else { [leader, chaser] } {
+                snakes.push(Snake {health: 100, body: body.into()});
+            }
+            let mut board = Board::new(RulesetKind::Standard, GameSettings::default(), 11, 11, 0, Some(vec![Point {x: 8, y: 8}]), None, None, snakes);
+
+            let mut actions = [0; MAX_SNAKE_COUNT];
+            actions[chaser_i] = Movement::Right as usize;
+            actions[1 - chaser_i] = Movement::Up as usize;
+            let outcome = advance_one_step(&mut board, actions);
+
+            assert_eq!(outcome.eliminated().count(), 0);
+            assert!(board.snakes.iter().all(|snake| snake.is_alive()));
+            assert_eq!(board.snakes[chaser_i].head(), Point {x: 3, y: 1});
+        }
+    }
+
+    fn two_snakes_with_hazards(foods: Vec<GridPoint>) -> Board {
+        use crate::game::{Snake, Point, Rectangle};
+        use crate::array2d::Array2D;
+        use crate::ruleset::RulesetKind;
+        use crate::settings::GameSettings;
+
+        let mut snakes = ArrayVec::new();
+        snakes.push(Snake {
+            health: 100,
+            body: [Point {x: 1, y: 1}, Point {x: 1, y: 0}, Point {x: 0, y: 0}].into(),
+        });
+        snakes.push(Snake {
+            health: 100,
+            body: [Point {x: 8, y: 8}, Point {x: 8, y: 9}, Point {x: 9, y: 9}].into(),
+        });
+
+        // Two stacks in front of the first snake, royale hazard on the right column
+        let mut hazards = Array2D::init_same(0);
+        hazards[(1, 2)] = 2;
+        let safe_zone = Rectangle {p0: Point {x: 0, y: 0}, p1: Point {x: 10, y: 11}};
+
+        Board::new(RulesetKind::Standard, GameSettings::default(), 11, 11, 0, Some(foods), Some(safe_zone), Some(hazards), snakes)
     }
 
     #[test]
     fn hazard_zone_depletes_hunger() {
-        // TODO
+        use crate::game::Point;
+
+        let mut board = two_snakes_with_hazards(vec![Point {x: 5, y: 5}]);
+        assert_eq!(board.hazard_stacks(Point {x: 1, y: 2}), 2);
+        assert_eq!(board.hazard_stacks(Point {x: 10, y: 3}), 1);
+        assert!(!board.is_hazard(Point {x: 9, y: 3}));
+
+        let mut settings = EngineSettings {
+            food_spawner: &mut food_spawner::noop,
+            safe_zone_shrinker: &mut safe_zone_shrinker::noop,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Up as usize;
+        actions[1] = Movement::Left as usize;
+        advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+        assert_eq!(board.snakes[0].health, 100 - 1 - 2 * board.settings.hazard_damage_per_turn);
+        assert_eq!(board.snakes[1].health, 99);
+    }
+
+    #[test]
+    fn healing_hazard_restores_health() {
+        use crate::game::Point;
+
+        let mut board = two_snakes_with_hazards(vec![Point {x: 5, y: 5}]);
+        board.settings.hazard_damage_per_turn = -10;
+        board.snakes[0].health = 50;
+        board.snakes[1].health = 2;
+        board.zobrist_hash = board.calculate_zobrist_hash();
+
+        let mut settings = EngineSettings {
+            food_spawner: &mut food_spawner::noop,
+            safe_zone_shrinker: &mut safe_zone_shrinker::noop,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        let mut step = |board: &mut Board, first: Movement, second: Movement| {
+            let mut actions = [0; MAX_SNAKE_COUNT];
+            actions[0] = first as usize;
+            actions[1] = second as usize;
+            advance_one_step_with_settings(board, &mut settings, actions);
+        };
+
+        // Two stacks heal 20
+        step(&mut board, Movement::Up, Movement::Right);
+        assert_eq!(board.snakes[0].health, 69);
+        assert_eq!(board.snakes[1].health, 1);
+
+        // Royale hazard saves the starving snake
+        step(&mut board, Movement::Left, Movement::Right);
+        assert_eq!(board.snakes[0].health, 68);
+        assert_eq!(board.snakes[1].health, 10);
+
+        // Health doesn't exceed the maximum
+        board.snakes[1].health = 95;
+        board.zobrist_hash = board.calculate_zobrist_hash();
+        step(&mut board, Movement::Down, Movement::Up);
+        assert_eq!(board.snakes[1].health, 100);
     }
 
     #[test]
     fn food_eaten_in_hazard_zone() {
-        // TODO
+        use crate::game::Point;
+
+        let mut board = two_snakes_with_hazards(vec![Point {x: 1, y: 2}]);
+
+        let mut settings = EngineSettings {
+            food_spawner: &mut food_spawner::noop,
+            safe_zone_shrinker: &mut safe_zone_shrinker::noop,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Up as usize;
+        actions[1] = Movement::Left as usize;
+        let outcome = advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+        assert_eq!(board.snakes[0].health, 100);
+        assert_eq!(board.snakes[0].body.len(), 4);
+        assert_eq!(outcome.eaten_food.as_slice(), [Point {x: 1, y: 2}]);
+        assert!(outcome.spawned_food.is_empty());
     }
 }
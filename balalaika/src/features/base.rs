@@ -8,7 +8,6 @@ use crate::{
         GridPoint,
     },
     zobrist::{body_direction, BodyDirections},
-    engine::safe_zone_shrinker::SHRINK_EVERY
 };
 
 use super::{
@@ -19,7 +18,7 @@ use super::{
         get_point_index,
         ALL_PLAYERS_GRIDS_SIZE,
         MAX_SNAKE_COUNT,
-        SIZE,
+        MAX_SIZE,
         ExamplesHandler, ValueType
     },
     examples::ExamplesCollector, Example
@@ -94,13 +93,13 @@ impl FeaturesHandler for BaseFeatures {
         self.collector.add(self.offset.get_food_index(pos), 1.0);
     }
 
-    fn hazard(&mut self, pos: GridPoint) {
-        // (hazard[grid], )
-        self.collector.add(self.offset.get_hazard_index(pos), 1.0);
+    fn hazard(&mut self, pos: GridPoint, stacks: u8) {
+        // (hazard[grid], ) with stacks count as value
+        self.collector.add(self.offset.get_hazard_index(pos), stacks as ValueType);
     }
 
     fn post(&mut self, board: &Board) {
-        let turns_left_before_shrink = (board.turn % SHRINK_EVERY) as f32 / SHRINK_EVERY as f32;
+        let turns_left_before_shrink = board.settings.shrink_progress(board.turn);
         self.collector.add(self.offset.get_turn_left_before_shrink_index(), turns_left_before_shrink);
     }
 
@@ -121,8 +120,8 @@ const TAILS_AT: IndexType = ALL_PLAYERS_GRIDS_SIZE;
 const BODY_PARTS_AT: IndexType = TAILS_AT + ALL_PLAYERS_GRIDS_SIZE;
 const BODIES_ON_TAIL_AT: IndexType = BODY_PARTS_AT + ALL_PLAYERS_GRIDS_SIZE * 5;
 const FOOD_AT: IndexType = BODIES_ON_TAIL_AT + MAX_BODIES_ON_TAIL * MAX_SNAKE_COUNT;
-const HAZARD_AT: IndexType = FOOD_AT + SIZE;
-const HEALTH_AT: IndexType = HAZARD_AT + SIZE;
+const HAZARD_AT: IndexType = FOOD_AT + MAX_SIZE;
+const HEALTH_AT: IndexType = HAZARD_AT + MAX_SIZE;
 const TURNS_LEFT_BEFORE_SHRINK_AT: IndexType = HEALTH_AT + MAX_SNAKE_COUNT * HEALTH_BARS;
 pub const NUM_FEATURES: IndexType = TURNS_LEFT_BEFORE_SHRINK_AT + 1;
 
@@ -131,7 +130,7 @@ pub const NUM_FEATURES: IndexType = TURNS_LEFT_BEFORE_SHRINK_AT + 1;
 fn calculate_snake_heads_at(offset: IndexType) -> [IndexType; game::MAX_SNAKE_COUNT] {
     let mut owner_heads_at = [0; game::MAX_SNAKE_COUNT];
     for i in 0..MAX_SNAKE_COUNT {
-        owner_heads_at[i as usize] = offset + HEADS_AT + i * SIZE;
+        owner_heads_at[i as usize] = offset + HEADS_AT + i * MAX_SIZE;
     }
     owner_heads_at
 }
@@ -139,7 +138,7 @@ fn calculate_snake_heads_at(offset: IndexType) -> [IndexType; game::MAX_SNAKE_CO
 fn calculate_snake_tails_at(offset: IndexType) -> [IndexType; game::MAX_SNAKE_COUNT] {
     let mut owner_tails_at = [0; game::MAX_SNAKE_COUNT];
     for i in 0..MAX_SNAKE_COUNT {
-        owner_tails_at[i as usize] = offset + TAILS_AT + i * SIZE;
+        owner_tails_at[i as usize] = offset + TAILS_AT + i * MAX_SIZE;
     }
     owner_tails_at
 }
@@ -149,9 +148,9 @@ fn calculate_snake_body_parts_at(offset: IndexType) -> [[IndexType; 5]; game::MA
     let mut owner_body_parts_at = [[0; 5]; game::MAX_SNAKE_COUNT];
     let mut owner_offset;
     for i in 0..MAX_SNAKE_COUNT {
-        owner_offset = BODY_PARTS_AT + i * SIZE * 5;
+        owner_offset = BODY_PARTS_AT + i * MAX_SIZE * 5;
         for d in 0..5 {
-            owner_body_parts_at[i as usize][d as usize] = offset + owner_offset + d * SIZE;
+            owner_body_parts_at[i as usize][d as usize] = offset + owner_offset + d * MAX_SIZE;
         }
     }
     owner_body_parts_at
@@ -385,17 +384,17 @@ impl ExamplesHandler for BaseExamples {
         )
     }
 
-    fn hazard(&mut self, pos: GridPoint) {
+    fn hazard(&mut self, pos: GridPoint, stacks: u8) {
         self.examples_collector.borrow_mut().grid_point_filler(
             pos,
             |collector, point| {
-                collector.add(self.offset.get_hazard_index(point), 1.0);
+                collector.add(self.offset.get_hazard_index(point), stacks as ValueType);
             }
         )
     }
 
     fn post(&mut self, board: &Board) {
-        let turns_left_before_shrink = (board.turn % SHRINK_EVERY) as f32 / SHRINK_EVERY as f32 ;
+        let turns_left_before_shrink = board.settings.shrink_progress(board.turn);
         self.examples_collector.borrow_mut().parameter(
             self.offset.get_turn_left_before_shrink_index(),
             turns_left_before_shrink,
@@ -1,12 +1,13 @@
-use crate::game::{GridPoint, Snake, Board, self, WIDTH, HEIGHT, Point};
+use crate::game::{GridPoint, Snake, Board, self, MAX_WIDTH, Point};
 
 // i64 for torch sparse tensor
 pub type IndexType = i64;
 pub type ValueType = f32;
 
-pub const SIZE: IndexType = game::SIZE as IndexType;
+// Grids are laid out for the largest supported board, so feature indices don't depend on the board size.
+pub const MAX_SIZE: IndexType = game::MAX_SIZE as IndexType;
 pub const MAX_SNAKE_COUNT: IndexType = game::MAX_SNAKE_COUNT as IndexType;
-pub const ALL_PLAYERS_GRIDS_SIZE: IndexType = MAX_SNAKE_COUNT * SIZE;
+pub const ALL_PLAYERS_GRIDS_SIZE: IndexType = MAX_SNAKE_COUNT * MAX_SIZE;
 
 #[derive(Clone)]
 pub struct SparseCollector {
@@ -32,7 +33,7 @@ pub trait FeaturesHandler {
     fn pre(&mut self, board: &Board);
     fn alive_snake(&mut self, snake: &Snake, alive_index: usize, snake_index: usize, board: &Board);
     fn snake(&mut self, snake: &Snake, snake_index: usize, board: &Board);
-    fn hazard(&mut self, pos: GridPoint);
+    fn hazard(&mut self, pos: GridPoint, stacks: u8);
     fn food(&mut self, pos: GridPoint);
     fn post(&mut self, board: &Board);
     fn num_features(&self) -> IndexType;
@@ -43,7 +44,7 @@ pub trait ExamplesHandler {
     fn pre(&mut self, board: &Board);
     fn alive_snake(&mut self, snake: &Snake, alive_index: usize, snake_index: usize, board: &Board);
     fn snake(&mut self, snake: &Snake, snake_index: usize, board: &Board);
-    fn hazard(&mut self, pos: GridPoint);
+    fn hazard(&mut self, pos: GridPoint, stacks: u8);
     fn food(&mut self, pos: GridPoint);
     fn post(&mut self, board: &Board);
     fn num_examples(&self) -> usize;
@@ -72,11 +73,12 @@ pub fn collect_features(board: &Board, handler: &mut impl FeaturesHandler) {
     }
     
     // (hazard[grid], )
-    for x in 0..WIDTH {
-        for y in 0..HEIGHT {
+    for x in 0..board.width {
+        for y in 0..board.height {
             let p = Point {x, y};
-            if board.is_hazard(p) {
-                handler.hazard(p);
+            let stacks = board.hazard_stacks(p);
+            if stacks > 0 {
+                handler.hazard(p, stacks);
             }
         }
     }
@@ -105,11 +107,12 @@ pub fn collect_examples(board: &Board, handler: &mut impl ExamplesHandler) {
     }
     
     // (hazard[grid], )
-    for x in 0..WIDTH {
-        for y in 0..HEIGHT {
+    for x in 0..board.width {
+        for y in 0..board.height {
             let p = Point {x, y};
-            if board.is_hazard(p) {
-                handler.hazard(p);
+            let stacks = board.hazard_stacks(p);
+            if stacks > 0 {
+                handler.hazard(p, stacks);
             }
         }
     }
@@ -133,6 +136,19 @@ pub fn get_rewards(board: &Board) -> (Rewards, bool) {
     (rewards, draw)
 }
 
+/// Linear reward by placement: 1 for the first place and 0 for the last one.
+/// Unlike `get_rewards`, it tells apart snakes eliminated early from the ones eliminated late.
+pub fn get_placement_rewards(board: &Board) -> Rewards {
+    let placements = board.placements();
+    let last_place = (board.snakes.len() - 1).max(1) as f32;
+
+    let mut rewards = [0.0; game::MAX_SNAKE_COUNT];
+    for i in 0..board.snakes.len() {
+        rewards[i] = 1.0 - placements[i] as f32 / last_place;
+    }
+    rewards
+}
+
 pub fn get_point_index(p: GridPoint) -> IndexType {
-    (p.y * HEIGHT + p.x) as IndexType
+    (p.y * MAX_WIDTH + p.x) as IndexType
 }
\ No newline at end of file
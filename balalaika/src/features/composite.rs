@@ -80,9 +80,9 @@ impl FeaturesHandler for CompositeFeatures {
         }
     }
 
-    fn hazard(&mut self, pos: GridPoint) {
+    fn hazard(&mut self, pos: GridPoint, stacks: u8) {
         for handler in &mut self.handlers {
-            handler.hazard(pos);
+            handler.hazard(pos, stacks);
         }
     }
 
@@ -181,9 +181,9 @@ impl ExamplesHandler for CompositeExamples {
         }
     }
 
-    fn hazard(&mut self, pos: GridPoint) {
+    fn hazard(&mut self, pos: GridPoint, stacks: u8) {
         for handler in &mut self.handlers {
-            handler.hazard(pos);
+            handler.hazard(pos, stacks);
         }
     }
 
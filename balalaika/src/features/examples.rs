@@ -2,9 +2,9 @@ use std::mem;
 
 use arrayvec::ArrayVec;
 
-use crate::{game::{Board, self, GridPoint, CENTER, HEIGHT, WIDTH, Point, MAX_SNAKE_COUNT}, zobrist::BodyDirections};
+use crate::{game::{Board, self, GridPoint, MAX_SNAKE_COUNT, CoordType, DEFAULT_WIDTH, DEFAULT_HEIGHT}, symmetry::{Symmetry, SYMMETRIES}, zobrist::BodyDirections};
 
-use super::{collector::{SparseCollector, get_rewards, ValueType, IndexType, Rewards}, Example};
+use super::{collector::{SparseCollector, get_placement_rewards, ValueType, IndexType, Rewards}, Example};
 
 pub struct ExamplesContext {
     // rewards with the same size as collectors
@@ -12,6 +12,10 @@ pub struct ExamplesContext {
     pub actual_rewards: Rewards,
     pub alive_permutations: Vec<Vec<usize>>,
     pub alive_snakes: ArrayVec<usize, MAX_SNAKE_COUNT>,
+    // Board symmetries used to augment examples
+    pub symmetries: ArrayVec<Symmetry, 8>,
+    pub width: CoordType,
+    pub height: CoordType,
 }
 
 impl ExamplesContext {
@@ -22,19 +26,33 @@ impl ExamplesContext {
             actual_rewards: [0.0; MAX_SNAKE_COUNT],
             alive_permutations: Vec::new(),
             alive_snakes: ArrayVec::new(),
+            symmetries: ArrayVec::new(),
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
         }
     }
 
     pub fn set_actual_rewards(&mut self, board: &Board) {
-        let actual_rewards = get_rewards(board).0;
+        let actual_rewards = get_placement_rewards(board);
         self.actual_rewards = actual_rewards;
     }
 
     pub fn set_board(&mut self, board: &Board) {
-        self.alive_snakes = (0..MAX_SNAKE_COUNT)
-            .filter(|i| board.snakes[*i].is_alive())
+        self.width = board.width;
+        self.height = board.height;
+        // Symmetries swapping the sides don't keep the size of a non-square board.
+        self.symmetries = SYMMETRIES
+            .into_iter()
+            .filter(|symmetry| board.width == board.height || !symmetry.swaps_sides())
             .collect();
-        self.alive_permutations = get_permutations(self.alive_snakes.len());
+        self.alive_snakes = board.snakes
+            .iter()
+            .enumerate()
+            .filter(|(_, snake)| snake.is_alive())
+            .map(|(i, _)| i)
+            .collect();
+        // Alive snakes take only owner slots of the game's snakes
+        self.alive_permutations = get_permutations(self.alive_snakes.len(), board.snakes.len());
         self.refresh_example_rewards();
     }
 
@@ -44,18 +62,16 @@ impl ExamplesContext {
         let actual_rewards = &self.actual_rewards;
 
         let perm_count = perms.len();
-        let mut rewards = vec![[0.0; game::MAX_SNAKE_COUNT]; 12 * perm_count];
-
-        for rot_i in 0..4 {
-            let flips_at = rot_i * 3 * perm_count;
-            for flip_i in 0..3 {
-                let perms_at = flips_at + flip_i * perm_count;
-                for perm_i in 0..perm_count {
-                    let perm = &perms[perm_i];
-                    for (alive_index, snake_index) in alive_snakes.iter().copied().enumerate() {
-                        let owner = perm[alive_index];
-                        rewards[perms_at + perm_i][owner] = actual_rewards[snake_index];
-                    }
+        let mut rewards = vec![[0.0; game::MAX_SNAKE_COUNT]; self.symmetries.len() * perm_count];
+
+        // Rewards don't depend on the symmetry
+        for symmetry_i in 0..self.symmetries.len() {
+            let perms_at = symmetry_i * perm_count;
+            for perm_i in 0..perm_count {
+                let perm = &perms[perm_i];
+                for (alive_index, snake_index) in alive_snakes.iter().copied().enumerate() {
+                    let owner = perm[alive_index];
+                    rewards[perms_at + perm_i][owner] = actual_rewards[snake_index];
                 }
             }
         }
@@ -77,8 +93,8 @@ impl ExamplesCollector {
         // TODO: precalulate permutations
         let permutations_count = context.alive_permutations.len();
 
-        // 3 (orginal, flip x, flip y) * 4 (rotates) * snakes permutations
-        let collectors = vec![SparseCollector::new(); 12 * permutations_count];
+        // symmetries * snakes permutations
+        let collectors = vec![SparseCollector::new(); context.symmetries.len() * permutations_count];
         let examples_collector = ExamplesCollector {
             collectors,
             context,
@@ -92,18 +108,17 @@ impl ExamplesCollector {
         alive_index: usize,
         func: F,
     ) {
+        let (width, height) = (self.context.width, self.context.height);
         let perms = &self.context.alive_permutations;
         let perm_count = perms.len();
-        for (rot_i, rot) in rotate_point(point).into_iter().enumerate() {
-            let flips_at = rot_i * 3 * perm_count;
-            for (flip_i, rot_flip) in flip_point(rot).into_iter().enumerate() {
-                let perms_at = flips_at + flip_i * perm_count;
-                for perm_i in 0..perm_count {
-                    let perm = &perms[perm_i];
-                    let owner = perm[alive_index];
-                    let collector = &mut self.collectors[perms_at + perm_i];
-                    func(collector, owner, rot_flip);
-                }
+        for (symmetry_i, symmetry) in self.context.symmetries.iter().enumerate() {
+            let symmetry_point = symmetry.point(point, width, height);
+            let perms_at = symmetry_i * perm_count;
+            for perm_i in 0..perm_count {
+                let perm = &perms[perm_i];
+                let owner = perm[alive_index];
+                let collector = &mut self.collectors[perms_at + perm_i];
+                func(collector, owner, symmetry_point);
             }
         }
     }
@@ -115,16 +130,13 @@ impl ExamplesCollector {
     ) {
         let perms = &self.context.alive_permutations;
         let perm_count = perms.len();
-        for rot_i in 0..4 {
-            let flips_at = rot_i * 3 * perm_count;
-            for flip_i in 0..3 {
-                let perms_at = flips_at + flip_i * perm_count;
-                for perm_i in 0..perm_count {
-                    let perm = &perms[perm_i];
-                    let owner = perm[alive_index];
-                    let collector = &mut self.collectors[perms_at + perm_i];
-                    func(collector, owner);
-                }
+        for symmetry_i in 0..self.context.symmetries.len() {
+            let perms_at = symmetry_i * perm_count;
+            for perm_i in 0..perm_count {
+                let perm = &perms[perm_i];
+                let owner = perm[alive_index];
+                let collector = &mut self.collectors[perms_at + perm_i];
+                func(collector, owner);
             }
         }
     }
@@ -136,18 +148,18 @@ impl ExamplesCollector {
         alive_index: usize,
         func: F,
     ) {
+        let (width, height) = (self.context.width, self.context.height);
         let perms = &self.context.alive_permutations;
         let perm_count = perms.len();
-        for (rot_i, (rot_point, rot_dir)) in rotate_point(point).into_iter().zip(rotate_body_direction(direction)).enumerate() {
-            let flips_at = rot_i * 3 * perm_count;
-            for (flip_i, (rot_flip_point, rot_flip_dir)) in flip_point(rot_point).into_iter().zip(flip_body_direction(rot_dir)).enumerate() {
-                let perms_at = flips_at + flip_i * perm_count;
-                for perm_i in 0..perm_count {
-                    let perm = &perms[perm_i];
-                    let owner = perm[alive_index];
-                    let collector = &mut self.collectors[perms_at + perm_i];
-                    func(collector, owner, rot_flip_point, rot_flip_dir);
-                }
+        for (symmetry_i, symmetry) in self.context.symmetries.iter().enumerate() {
+            let symmetry_point = symmetry.point(point, width, height);
+            let symmetry_direction = symmetry.body_direction(direction);
+            let perms_at = symmetry_i * perm_count;
+            for perm_i in 0..perm_count {
+                let perm = &perms[perm_i];
+                let owner = perm[alive_index];
+                let collector = &mut self.collectors[perms_at + perm_i];
+                func(collector, owner, symmetry_point, symmetry_direction);
             }
         }
     }
@@ -158,16 +170,15 @@ impl ExamplesCollector {
         point: GridPoint,
         func: F,
     ) {
+        let (width, height) = (self.context.width, self.context.height);
         let permutations_count = self.context.alive_permutations.len();
-        for (rot_i, rot) in rotate_point(point).into_iter().enumerate() {
-            let flips_at = rot_i * 3 * permutations_count;
-            for (flip_i, rot_flip) in flip_point(rot).into_iter().enumerate() {
-                let perms_at = flips_at + flip_i * permutations_count;
-                // Fill all permutations with the same func
-                for perm_i in 0..permutations_count {
-                    let collector = &mut self.collectors[perms_at + perm_i];
-                    func(collector, rot_flip);
-                }
+        for (symmetry_i, symmetry) in self.context.symmetries.iter().enumerate() {
+            let symmetry_point = symmetry.point(point, width, height);
+            let perms_at = symmetry_i * permutations_count;
+            // Fill all permutations with the same func
+            for perm_i in 0..permutations_count {
+                let collector = &mut self.collectors[perms_at + perm_i];
+                func(collector, symmetry_point);
             }
         }
     }
@@ -180,15 +191,12 @@ impl ExamplesCollector {
         // 4 snakes, each has 3 relations.
         let perms = &self.context.alive_permutations;
         let perm_count = perms.len();
-        for rot_i in 0..4 {
-            let flips_at = rot_i * 3 * perm_count;
-            for flip_i in 0..3 {
-                let perms_at = flips_at + flip_i * perm_count;
-                for perm_i in 0..perm_count {
-                    let perm = &perms[perm_i];
-                    let collector = &mut self.collectors[perms_at + perm_i];
-                    func(collector, perm);
-                }
+        for symmetry_i in 0..self.context.symmetries.len() {
+            let perms_at = symmetry_i * perm_count;
+            for perm_i in 0..perm_count {
+                let perm = &perms[perm_i];
+                let collector = &mut self.collectors[perms_at + perm_i];
+                func(collector, perm);
             }
         }
     }
@@ -221,62 +229,16 @@ impl ExamplesCollector {
     pub fn refresh_collectors(&mut self) {
         let permutations_count = self.context.alive_permutations.len();
 
-        // 3 (orginal, flip x, flip y) * 4 (rotates) * snakes permutations
-        self.collectors = vec![SparseCollector::new(); 12 * permutations_count];
+        // symmetries * snakes permutations
+        self.collectors = vec![SparseCollector::new(); self.context.symmetries.len() * permutations_count];
     }
 }
 
 
-// NOTE: Flips for x and y together are created by 180 rotation so
-// we don't need to do x + y flip
-
-fn flip_point(p: GridPoint) -> [GridPoint; 3] {
-    [
-        Point {x: p.x, y: p.y},                             // original
-        Point {x: WIDTH - p.x - 1, y: p.y},                 // x-flip
-        Point {x: p.x, y: HEIGHT - p.y - 1},                // y-flip
-        // Point {x: WIDTH - p.x - 1, y: HEIGHT - p.y - 1},    // x and y flip
-    ]
-}
-
-fn rotate_point(p: GridPoint) -> [GridPoint; 4] {
-    let diff = Point {x: p.x - CENTER.x, y: p.y - CENTER.y};
-    [
-        GridPoint {x: p.x, y: p.y},
-        GridPoint {x: CENTER.x + 0 * diff.x + 1 * diff.y, y: CENTER.y - 1 * diff.x + 0 * diff.y},  // Units x=(0, -1); y=(1, 0)
-        GridPoint {x: CENTER.x - 1 * diff.x + 0 * diff.y, y: CENTER.y + 0 * diff.x - 1 * diff.y},  // Units x=(-1, 0); y=(0, -1)
-        GridPoint {x: CENTER.x + 0 * diff.x - 1 * diff.y, y: CENTER.y + 1 * diff.x + 0 * diff.y},  // Units x=(0, 1); y=(-1, 0)
-    ]
-}
-
-fn flip_body_direction(body_direction: BodyDirections) -> [BodyDirections; 3] {
-    if body_direction == BodyDirections::Still {
-        return [BodyDirections::Still; 3];
-    }
-    let b = body_direction as usize;
-    [
-        BodyDirections::from(b),                                    // original
-        BodyDirections::from((b + 2 * (1 - (b + 1) % 2)) % 4),      // x-flip
-        BodyDirections::from((b + 2 * ((b + 1) % 2)) % 4),          // y-flip
-        // BodyDirections::from((b + 2) % 4),                          // x and y flip
-    ]
-}
-
-fn rotate_body_direction(body_direction: BodyDirections) -> [BodyDirections; 4] {
-    if body_direction == BodyDirections::Still {
-        return [BodyDirections::Still; 4];
-    }
-    let b = body_direction as usize;
-    [
-        BodyDirections::from(b),
-        BodyDirections::from((b + 1) % 4),
-        BodyDirections::from((b + 2) % 4),
-        BodyDirections::from((b + 3) % 4),
-    ]
-}
-
-fn get_permutations(alive_snakes_count: usize) -> Vec<Vec<usize>> {
-    assert!(alive_snakes_count <= game::MAX_SNAKE_COUNT);
+/// All ordered placements of alive snakes into `slots_count` owner slots.
+fn get_permutations(alive_snakes_count: usize, slots_count: usize) -> Vec<Vec<usize>> {
+    assert!(alive_snakes_count <= slots_count);
+    assert!(slots_count <= game::MAX_SNAKE_COUNT);
 
     let mut is_selected = [false; game::MAX_SNAKE_COUNT];
     let mut permutations = Vec::new();
@@ -287,7 +249,7 @@ fn get_permutations(alive_snakes_count: usize) -> Vec<Vec<usize>> {
 
     loop {
         loop {
-            while n == game::MAX_SNAKE_COUNT {
+            while n == slots_count {
                 if i == 0 {
                     return permutations;
                 }
@@ -320,51 +282,23 @@ fn get_permutations(alive_snakes_count: usize) -> Vec<Vec<usize>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{flip_body_direction, rotate_body_direction, rotate_point, flip_point, get_permutations};
+    use super::{get_permutations, ExamplesCollector, ExamplesContext};
+    use crate::board_generator::generate_board;
+    use crate::engine::new_rng;
+    use crate::features::collector::{collect_examples, get_point_index, ExamplesHandler};
     use crate::game::Point;
-    use crate::zobrist::BodyDirections;
-
-    #[test]
-    fn test_rotate_point() {
-        assert_eq!(rotate_point(Point {x: 10, y: 10}), [Point {x: 10, y: 10}, Point {x: 10, y: 0}, Point {x: 0, y: 0}, Point {x: 0, y: 10}]);
-        assert_eq!(rotate_point(Point {x: 9, y: 2}), [Point {x: 9, y: 2}, Point {x: 2, y: 1}, Point {x: 1, y: 8}, Point {x: 8, y: 9}]);
-        assert_eq!(rotate_point(Point {x: 5, y: 5}), [Point {x: 5, y: 5}, Point {x: 5, y: 5}, Point {x: 5, y: 5}, Point {x: 5, y: 5}]);
-    }
-
-    #[test]
-    fn test_flip_point() {
-        assert_eq!(flip_point(Point {x: 10, y: 10}), [Point {x: 10, y: 10}, Point {x: 0, y: 10}, Point {x: 10, y: 0}]);
-        assert_eq!(flip_point(Point {x: 9, y: 2}), [Point {x: 9, y: 2}, Point {x: 1, y: 2}, Point {x: 9, y: 8}]);
-        assert_eq!(flip_point(Point {x: 5, y: 5}), [Point {x: 5, y: 5}, Point {x: 5, y: 5}, Point {x: 5, y: 5}]);
-    }
-
-    #[test]
-    fn test_flip_body_direction() {
-        assert_eq!(flip_body_direction(BodyDirections::Up), [BodyDirections::Up, BodyDirections::Up, BodyDirections::Down]);
-        assert_eq!(flip_body_direction(BodyDirections::Right), [BodyDirections::Right, BodyDirections::Left, BodyDirections::Right]);
-        assert_eq!(flip_body_direction(BodyDirections::Down), [BodyDirections::Down, BodyDirections::Down, BodyDirections::Up]);
-        assert_eq!(flip_body_direction(BodyDirections::Left), [BodyDirections::Left, BodyDirections::Right, BodyDirections::Left]);
-        assert_eq!(flip_body_direction(BodyDirections::Still), [BodyDirections::Still, BodyDirections::Still, BodyDirections::Still]);
-    }
-
-    #[test]
-    fn test_rotate_body_direction() {
-        assert_eq!(rotate_body_direction(BodyDirections::Up), [BodyDirections::Up, BodyDirections::Right, BodyDirections::Down, BodyDirections::Left]);
-        assert_eq!(rotate_body_direction(BodyDirections::Right), [BodyDirections::Right, BodyDirections::Down, BodyDirections::Left, BodyDirections::Up]);
-        assert_eq!(rotate_body_direction(BodyDirections::Down), [BodyDirections::Down, BodyDirections::Left, BodyDirections::Up, BodyDirections::Right]);
-        assert_eq!(rotate_body_direction(BodyDirections::Left), [BodyDirections::Left, BodyDirections::Up, BodyDirections::Right, BodyDirections::Down]);
-        assert_eq!(rotate_body_direction(BodyDirections::Still), [BodyDirections::Still, BodyDirections::Still, BodyDirections::Still, BodyDirections::Still]);    
-    }
+    use crate::features::composite::CompositeExamples;
+    use crate::ruleset::RulesetKind;
 
     #[test]
     fn test_permutations() {
-        assert_eq!(get_permutations(1), vec![
+        assert_eq!(get_permutations(1, 4), vec![
             vec![0],
             vec![1],
             vec![2],
             vec![3],
         ]);
-        assert_eq!(get_permutations(2), vec![
+        assert_eq!(get_permutations(2, 4), vec![
             vec![0, 1],
             vec![0, 2],
             vec![0, 3],
@@ -378,7 +312,7 @@ mod tests {
             vec![3, 1],
             vec![3, 2],
         ]);
-        assert_eq!(get_permutations(4), vec![
+        assert_eq!(get_permutations(4, 4), vec![
             vec![0, 1, 2, 3], vec![0, 1, 3, 2], vec![0, 2, 1, 3], vec![0, 2, 3, 1],
             vec![0, 3, 1, 2], vec![0, 3, 2, 1], vec![1, 0, 2, 3], vec![1, 0, 3, 2],
             vec![1, 2, 0, 3], vec![1, 2, 3, 0], vec![1, 3, 0, 2], vec![1, 3, 2, 0],
@@ -386,5 +320,58 @@ mod tests {
             vec![2, 3, 0, 1], vec![2, 3, 1, 0], vec![3, 0, 1, 2], vec![3, 0, 2, 1],
             vec![3, 1, 0, 2], vec![3, 1, 2, 0], vec![3, 2, 0, 1], vec![3, 2, 1, 0],
         ]);
+        assert_eq!(get_permutations(3, 8).len(), 8 * 7 * 6);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_examples_of_four_snakes() {
+        let rng = &mut new_rng(Some(0));
+        let mut board = generate_board(rng, RulesetKind::Standard, 11, 11, 4);
+
+        let feature_tags = ["base", "global_metrics", "snakes_metrics", "flood_fill"].map(String::from).to_vec();
+        let mut composite_examples = CompositeExamples::new(feature_tags, ExamplesContext::new());
+        {
+            let mut examples_collector = composite_examples.examples_collector.borrow_mut();
+            examples_collector.context.set_actual_rewards(&board);
+            examples_collector.context.set_board(&board);
+            examples_collector.refresh_collectors();
+        }
+        collect_examples(&board, &mut composite_examples);
+        let examples = composite_examples.pop_examples();
+
+        // 8 symmetries * 4! permutations
+        assert_eq!(examples.len(), 8 * 24);
+        for (_, rewards) in examples {
+            assert!(rewards[4..].iter().all(|reward| *reward == 0.0));
+        }
+
+        board.snakes[1].health = 0;
+        let mut context = ExamplesContext::new();
+        context.set_board(&board);
+        assert_eq!(context.alive_snakes.as_slice(), [0, 2, 3]);
+        assert_eq!(context.alive_permutations.len(), 4 * 3 * 2);
+        assert!(context.alive_permutations.iter().flatten().all(|owner| *owner < 4));
+    }
+
+    #[test]
+    fn test_examples_of_non_square_board() {
+        let rng = &mut new_rng(Some(0));
+        let board = generate_board(rng, RulesetKind::Standard, 7, 11, 2);
+
+        let mut context = ExamplesContext::new();
+        context.set_board(&board);
+        // Only symmetries keeping the size
+        assert_eq!(context.symmetries.len(), 4);
+
+        let mut examples_collector = ExamplesCollector::new(context);
+        for x in 0..board.width {
+            for y in 0..board.height {
+                examples_collector.grid_point_filler(Point {x, y}, |collector, p| {
+                    assert!(p.x >= 0 && p.x < 7 && p.y >= 0 && p.y < 11, "{:?}", p);
+                    collector.add(get_point_index(p), 1.0);
+                });
+            }
+        }
+        assert_eq!(examples_collector.len(), 4 * 2);
+    }
+}
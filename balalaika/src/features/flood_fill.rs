@@ -48,7 +48,7 @@ impl FeaturesHandler for FloodFillFeatures {
     
     fn food(&mut self, _pos: GridPoint) {}
 
-    fn hazard(&mut self, _pos: GridPoint) {}
+    fn hazard(&mut self, _pos: GridPoint, _stacks: u8) {}
 
     fn post(&mut self, _board: &Board) {}
 
@@ -137,7 +137,7 @@ impl ExamplesHandler for FloodFillExamples {
     
     fn food(&mut self, _pos: GridPoint) {}
 
-    fn hazard(&mut self, _pos: GridPoint) {}
+    fn hazard(&mut self, _pos: GridPoint, _stacks: u8) {}
 
     fn post(&mut self, _board: &Board) {}
 
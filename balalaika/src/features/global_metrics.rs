@@ -13,7 +13,6 @@ use super::{
         SparseCollector,
         FeaturesHandler,
         ExamplesHandler,
-        SIZE,
         ValueType
     },
     examples::ExamplesCollector, Example,
@@ -43,18 +42,17 @@ impl FeaturesHandler for GlobalMetricsFeatures {
     
     fn food(&mut self, _pos: GridPoint) {}
 
-    fn hazard(&mut self, _pos: GridPoint) {}
+    fn hazard(&mut self, _pos: GridPoint, _stacks: u8) {}
 
     fn post(&mut self, board: &Board) {
-        let size = SIZE as ValueType;
+        let size = board.size() as ValueType;
         
         // Food count [0.0; 1.0]
         let food_count = board.foods.len() as ValueType / size;
         self.collector.add(self.offset.get_food_count_index(), food_count);
 
         // Safe zone size [0.0; 1.0]
-        let sz = &board.safe_zone;
-        let safe_zone_size = ((sz.p0.x - sz.p1.x) * (sz.p0.y - sz.p1.y)) as ValueType / size;
+        let safe_zone_size = (board.size() - board.hazards_count()) as ValueType / size;
         self.collector.add(self.offset.get_safe_zone_size_index(), safe_zone_size);
 
         let free_cells_count = board.objects.empties.len() as ValueType / size;
@@ -171,10 +169,10 @@ impl ExamplesHandler for GlobalMetricsExamples {
 
     fn food(&mut self, _pos: GridPoint) {}
 
-    fn hazard(&mut self, _pos: GridPoint) {}
+    fn hazard(&mut self, _pos: GridPoint, _stacks: u8) {}
 
     fn post(&mut self, board: &Board) {
-        let size = SIZE as ValueType;
+        let size = board.size() as ValueType;
         
         // Food count [0.0; 1.0]
         let food_count = board.foods.len() as ValueType / size;
@@ -184,8 +182,7 @@ impl ExamplesHandler for GlobalMetricsExamples {
         );
 
         // Safe zone size [0.0; 1.0]
-        let sz = &board.safe_zone;
-        let safe_zone_size = ((sz.p0.x - sz.p1.x) * (sz.p0.y - sz.p1.y)) as ValueType / size;
+        let safe_zone_size = (board.size() - board.hazards_count()) as ValueType / size;
         self.examples_collector.borrow_mut().parameter(
             self.offset.get_safe_zone_size_index(),
             safe_zone_size,
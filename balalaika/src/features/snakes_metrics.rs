@@ -45,22 +45,22 @@ impl FeaturesHandler for SnakeMetricsFeatures {
         self.healthes[snake_index] = snake.health;
     }
 
-    fn alive_snake(&mut self, snake: &Snake, _alive_index: usize, snake_index: usize, _board: &Board) {
+    fn alive_snake(&mut self, snake: &Snake, _alive_index: usize, snake_index: usize, board: &Board) {
         let health = snake.health as ValueType / 100.0;
         self.collector.add(self.offset.get_linear_health_index(snake_index), health);
 
-        let length = snake.body.len() as ValueType / SIZE;
+        let length = snake.body.len() as ValueType / board.size() as ValueType;
         self.collector.add(self.offset.get_length_index(snake_index), length);
     }
     
     fn food(&mut self, _pos: GridPoint) {}
 
-    fn hazard(&mut self, _pos: GridPoint) {}
+    fn hazard(&mut self, _pos: GridPoint, _stacks: u8) {}
 
-    fn post(&mut self, _board: &Board) {
+    fn post(&mut self, board: &Board) {
         for i in 0..game::MAX_SNAKE_COUNT - 1 {
             for j in i + 1..game::MAX_SNAKE_COUNT {
-                let diff_len = (self.lengthes[i] - self.lengthes[j]) as f32 / SIZE;
+                let diff_len = (self.lengthes[i] - self.lengthes[j]) as f32 / board.size() as f32;
                 self.collector.add(self.offset.get_length_difference_index(i, j - 1), diff_len);
                 self.collector.add(self.offset.get_length_difference_index(j, i), -diff_len);
                 
@@ -84,7 +84,6 @@ impl FeaturesHandler for SnakeMetricsFeatures {
 
 
 // Helper data
-const SIZE: ValueType = collector::SIZE as ValueType;
 const OTHER_SNAKES_COUNT: IndexType = collector::MAX_SNAKE_COUNT - 1;
 
 // Indexes
@@ -186,7 +185,7 @@ impl ExamplesHandler for SnakeMetricsExamples {
 
     fn snake(&mut self, _snake: &Snake, _snake_index: usize, _board: &Board) {}
 
-    fn alive_snake(&mut self, snake: &Snake, alive_index: usize, _snake_index: usize, _board: &Board) {
+    fn alive_snake(&mut self, snake: &Snake, alive_index: usize, _snake_index: usize, board: &Board) {
         let mut examples_collector = self.examples_collector.borrow_mut();
 
         let health = snake.health as ValueType / 100.0;
@@ -197,7 +196,7 @@ impl ExamplesHandler for SnakeMetricsExamples {
             },
         );
 
-        let length = snake.body.len() as ValueType / SIZE;
+        let length = snake.body.len() as ValueType / board.size() as ValueType;
         examples_collector.alive_snake_parameter_filler(
             alive_index,
             |collector, owner| {
@@ -208,7 +207,7 @@ impl ExamplesHandler for SnakeMetricsExamples {
 
     fn food(&mut self, _pos: GridPoint) {}
 
-    fn hazard(&mut self, _pos: GridPoint) {}
+    fn hazard(&mut self, _pos: GridPoint, _stacks: u8) {}
 
     fn post(&mut self, board: &Board) {
         let mut examples_collector = self.examples_collector.borrow_mut();
@@ -226,7 +225,7 @@ impl ExamplesHandler for SnakeMetricsExamples {
 
             for i in 0..game::MAX_SNAKE_COUNT - 1 {
                 for j in i + 1..game::MAX_SNAKE_COUNT {
-                    let diff_length = (lengthes[i] - lengthes[j]) as f32 / SIZE;
+                    let diff_length = (lengthes[i] - lengthes[j]) as f32 / board.size() as f32;
                     collector.add(self.offset.get_length_difference_index(i, j - 1), diff_length);
                     collector.add(self.offset.get_length_difference_index(j, i), -diff_length);
 
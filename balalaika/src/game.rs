@@ -1,10 +1,9 @@
-use std::collections::VecDeque;
 use std::fmt::{Debug, self};
 use std::ops::{Add, AddAssign};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use arrayvec::ArrayVec;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 use rand::Rng;
 use serde::{Serialize, Deserialize};
 use colored::*;
@@ -12,7 +11,13 @@ use colored::*;
 use crate::api;
 use crate::api::objects::Movement;
 use crate::array2d::Array2D;
+use crate::engine::{Elimination, new_rng};
+use crate::ruleset::{Ruleset, RulesetKind};
+use crate::settings::{GameMap, GameSettings};
 use crate::zobrist::{ZobristHash, body_direction};
+use crate::snake_body::{SnakeBody, SNAKE_BODY_CAPACITY};
+use crate::bitboard::Bitboard;
+use crate::symmetry::Symmetry;
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Copy, Clone)]
 pub struct Point<T> {
@@ -25,27 +30,55 @@ pub type PointUsize = Point<usize>;
 pub type CoordType = i32;
 pub type GridPoint = Point<CoordType>;
 
-pub const MAX_SNAKE_COUNT: usize = 4;
-pub const WIDTH: CoordType = 11;
-pub const HEIGHT: CoordType = 11;
-pub const CENTER: GridPoint = Point {x: WIDTH / 2, y: HEIGHT / 2};
-pub const SIZE: usize = (WIDTH * HEIGHT) as usize;
+// Upper bound of snakes in one game. Arrays indexed by snake are allocated with this size.
+pub const MAX_SNAKE_COUNT: usize = 8;
+// Board dimensions are set per board. Fixed size arrays are allocated for the largest supported board.
+pub const MAX_WIDTH: CoordType = 19;
+pub const MAX_HEIGHT: CoordType = 21;
+pub const MAX_SIZE: usize = (MAX_WIDTH * MAX_HEIGHT) as usize;
+pub const DEFAULT_WIDTH: CoordType = 11;
+pub const DEFAULT_HEIGHT: CoordType = 11;
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
+    pub ruleset: RulesetKind,
+    pub settings: GameSettings,
+    pub width: CoordType,
+    pub height: CoordType,
     pub foods: Vec<GridPoint>,
     pub snakes: ArrayVec<Snake, MAX_SNAKE_COUNT>,
     pub turn: i32,
+    // Hazards are everything outside of `safe_zone` (royale) plus stacks from `hazards` grid (maps).
     pub safe_zone: Rectangle,
+    pub hazards: Array2D<u8>,
     pub objects: Objects,
+    // Bodies of alive snakes and cells with hazards, kept by the engine for bit operations.
+    pub snake_bitboards: [Bitboard; MAX_SNAKE_COUNT],
+    pub hazard_bitboard: Bitboard,
     pub zobrist_hash: ZobristHash,
     pub is_terminal: bool,
+    // Filled by the engine. Snakes which were dead when the board was created have no record.
+    #[serde(default)]
+    pub eliminations: [Option<Elimination>; MAX_SNAKE_COUNT],
+    // Same order as `snakes`, empty for boards which don't come from the API.
+    // Shared between copies, so cloning the board stays cheap.
+    #[serde(default)]
+    pub identities: Arc<[SnakeIdentity]>,
+}
+
+/// Who the snake is in the game. Stays with the snake index after the snake is eliminated.
+#[derive(PartialEq, Eq, Debug, Clone, Hash, Default, Serialize, Deserialize)]
+pub struct SnakeIdentity {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub squad: String,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Snake {
     pub health: i32,
-    pub body: VecDeque<GridPoint>,
+    pub body: SnakeBody,
 }
 
 /// Represents [p0.x, p1.x) × [p0.y, p1.y)
@@ -56,33 +89,115 @@ pub struct Rectangle {
 }
 
 impl Board {
-    pub fn from_api(state_api: &api::objects::State) -> Board {        
+    /// Panics on invalid states, use `try_from_api` for input from outside.
+    pub fn from_api(state_api: &api::objects::State) -> Board {
+        Self::try_from_api(state_api).unwrap_or_else(|error| panic!("Invalid state: {}", error))
+    }
+
+    pub fn try_from_api(state_api: &api::objects::State) -> Result<Board, BoardError> {
+        let snakes = Self::snake_api_to_snake_game(&state_api.board.snakes)?;
+        let identities = state_api.board.snakes.iter().map(SnakeIdentity::from_api).collect();
+        Self::try_from_api_with_snakes(state_api, snakes, identities)
+    }
+
+    /// Board for a later turn of the game of `previous`. The API doesn't send eliminated snakes,
+    /// but here they stay dead on their indices, so snake indices and hashes don't change during the game.
+    pub fn try_from_api_after(state_api: &api::objects::State, previous: &Board) -> Result<Board, BoardError> {
+        let snakes_api = &state_api.board.snakes;
+        if let Some(i) = snakes_api.iter().position(|snake_api| previous.snake_index(&snake_api.id).is_none()) {
+            return Err(BoardError::UnknownSnake {snake: i});
+        }
+
+        let mut snakes = ArrayVec::new();
+        for (i, identity) in previous.identities.iter().enumerate() {
+            let snake = match snakes_api.iter().find(|snake_api| snake_api.id == identity.id) {
+                Some(snake_api) => Snake::try_from_api(i, snake_api)?,
+                None => Snake {health: 0, body: previous.snakes[i].body.clone()},
+            };
+            snakes.push(snake);
+        }
+
+        let mut board = Self::try_from_api_with_snakes(state_api, snakes, previous.identities.clone())?;
+        for (i, snake) in board.snakes.iter().enumerate() {
+            if !snake.is_alive() {
+                board.eliminations[i] = previous.eliminations[i];
+            }
+        }
+        Ok(board)
+    }
+
+    fn try_from_api_with_snakes(
+        state_api: &api::objects::State,
+        snakes: ArrayVec<Snake, MAX_SNAKE_COUNT>,
+        identities: Arc<[SnakeIdentity]>,
+    ) -> Result<Board, BoardError> {
         let board_api = &state_api.board;
-        debug_assert!(board_api.width == WIDTH && board_api.height == HEIGHT);
+        check_size(board_api.width, board_api.height)?;
+        if let Some(&hazard) = board_api.hazards.iter().find(|&&p| !is_inside(p, board_api.width, board_api.height)) {
+            return Err(BoardError::OutOfBounds(hazard));
+        }
+
+        let (safe_zone, hazards) = Self::calculate_hazards(board_api.width, board_api.height, &board_api.hazards);
 
-        Board::new(
+        Board::try_new(
+            RulesetKind::from_api_name(&state_api.game.ruleset.name),
+            GameSettings::from_api(&state_api.game),
+            board_api.width,
+            board_api.height,
             state_api.turn as i32,
-            Some(state_api.board.food.clone()),
-            Some(Self::calculate_safe_zone(&state_api.board.hazards)),
-            Self::snake_api_to_snake_game(&state_api.board.snakes),
-        )
+            Some(board_api.food.clone()),
+            Some(safe_zone),
+            Some(hazards),
+            snakes,
+        ).map(|board| Board {identities, ..board})
     }
 
+    /// Same as `new`, but checks the parts first, so it doesn't panic on bad input.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        ruleset: RulesetKind,
+        settings: GameSettings,
+        width: CoordType,
+        height: CoordType,
+        turn: i32,
+        foods: Option<Vec<GridPoint>>,
+        safe_zone: Option<Rectangle>,
+        hazards: Option<Array2D<u8>>,
+        snakes: ArrayVec<Snake, MAX_SNAKE_COUNT>,
+    ) -> Result<Board, BoardError> {
+        check_size(width, height)?;
+        check_snakes(ruleset, width, height, &snakes)?;
+        check_foods(width, height, &snakes, foods.as_deref().unwrap_or_default())?;
+
+        let board = Board::new(ruleset, settings, width, height, turn, foods, safe_zone, hazards, snakes);
+        board.validate()?;
+        Ok(board)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        ruleset: RulesetKind,
+        settings: GameSettings,
+        width: CoordType,
+        height: CoordType,
         turn: i32,
         foods: Option<Vec<GridPoint>>,
         safe_zone: Option<Rectangle>,
+        hazards: Option<Array2D<u8>>,
         snakes: ArrayVec<Snake, MAX_SNAKE_COUNT>,
     ) -> Board {
-        // There always must be food.
+        // There always must be food (except constrictor, where it's removed, and maps with fixed food spots).
         let mut foods = if let Some(foods) = foods {
             foods
         } else {
             Vec::new()
         };
-        if foods.is_empty() {
+        let has_free_cell = || (0..width).any(|x| (0..height).any(|y| snakes.iter().all(|snake| !snake.body.contains(&Point {x, y}))));
+        if foods.is_empty() && !ruleset.is_constrictor() && !settings.map.has_fixed_food() && has_free_cell() {
+            // Seeded by the turn, so the same position always gets the same food.
+            let rng = &mut new_rng(Some(turn as u64));
             loop {
-                let food = random_point_inside_borders();
+                let food = random_point_inside_borders(rng, width, height);
                 if snakes.iter().all(|snake| !snake.body.contains(&food)) {
                     foods.push(food);
                     break;
@@ -90,46 +205,175 @@ impl Board {
             }
         }
 
-        debug_assert!(snakes.iter().all(|snake| foods.iter().all(|food| !snake.body.contains(&food))));
-
-        let zobrist_hash = Self::initial_zobrist_hash(
-            turn,
-            &snakes,
-            &foods,
-        );
-
         let safe_zone = if safe_zone.is_some() {
             safe_zone.unwrap()
         } else {
             Rectangle {
                 p0: Point {x: 0, y: 0},
-                p1: Point {x: WIDTH, y: HEIGHT},
+                p1: Point {x: width, y: height},
             }
         };
 
-        let objects = Self::calculate_objects(&snakes, &foods);
+        let hazards = hazards.unwrap_or_else(|| Array2D::init_same(0));
+
+        Self::from_parts(ruleset, settings, width, height, turn, foods, safe_zone, hazards, snakes)
+    }
+
+    // Board exactly from the parts, the derived fields are calculated from scratch.
+    #[allow(clippy::too_many_arguments)]
+    fn from_parts(
+        ruleset: RulesetKind,
+        settings: GameSettings,
+        width: CoordType,
+        height: CoordType,
+        turn: i32,
+        foods: Vec<GridPoint>,
+        safe_zone: Rectangle,
+        hazards: Array2D<u8>,
+        snakes: ArrayVec<Snake, MAX_SNAKE_COUNT>,
+    ) -> Board {
+        debug_assert!(snakes.iter().filter(|snake| snake.is_alive()).all(|snake| foods.iter().all(|food| !snake.body.contains(&food))));
+
+        let objects = Self::calculate_objects(width, height, &snakes, &foods);
 
-        Board {
+        let mut board = Board {
+            ruleset,
+            settings,
+            width,
+            height,
             foods,
             snakes,
             turn,
             safe_zone,
+            hazards,
             objects,
-            zobrist_hash,
+            snake_bitboards: [Bitboard::EMPTY; MAX_SNAKE_COUNT],
+            hazard_bitboard: Bitboard::EMPTY,
+            zobrist_hash: ZobristHash::new(),
             is_terminal: false,
+            eliminations: [None; MAX_SNAKE_COUNT],
+            identities: Arc::from([]),
+        };
+        board.snake_bitboards = board.calculate_snake_bitboards();
+        board.hazard_bitboard = board.calculate_hazard_bitboard();
+        board.zobrist_hash = board.calculate_zobrist_hash();
+
+        board
+    }
+
+    /// Same position rotated or flipped by the symmetry.
+    /// The transformed board must fit into `MAX_WIDTH` × `MAX_HEIGHT`, so a 19x21 board can't swap its sides.
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let (width, height) = symmetry.size(self.width, self.height);
+        let point = |p: GridPoint| symmetry.point(p, self.width, self.height);
+
+        let foods = self.foods.iter().map(|&food| point(food)).collect();
+
+        let mut hazards = Array2D::init_same(0);
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let p = point(Point {x, y});
+                hazards[(p.x as usize, p.y as usize)] = self.hazards[(x as usize, y as usize)];
+            }
         }
+
+        let safe_zone = if self.safe_zone.p0.x >= self.safe_zone.p1.x || self.safe_zone.p0.y >= self.safe_zone.p1.y {
+            self.safe_zone
+        } else {
+            // Corners are cells, so the last cell is p1 - (1, 1)
+            let a = point(self.safe_zone.p0);
+            let b = point(Point {x: self.safe_zone.p1.x - 1, y: self.safe_zone.p1.y - 1});
+            Rectangle {
+                p0: Point {x: a.x.min(b.x), y: a.y.min(b.y)},
+                p1: Point {x: a.x.max(b.x) + 1, y: a.y.max(b.y) + 1},
+            }
+        };
+
+        let snakes = self.snakes.iter().map(|snake| Snake {
+            health: snake.health,
+            body: snake.body.iter().map(|&part| point(part)).collect(),
+        }).collect();
+
+        let mut board = Self::from_parts(self.ruleset, self.settings, width, height, self.turn, foods, safe_zone, hazards, snakes);
+        board.is_terminal = self.is_terminal;
+        board.eliminations = self.eliminations;
+        board.identities = self.identities.clone();
+        board
     }
 
     pub fn contains(&self, p: GridPoint) -> bool {
-        (p.x < WIDTH) && (p.y < HEIGHT) && (p.x >= 0) && (p.y >= 0)
+        is_inside(p, self.width, self.height)
+    }
+
+    /// Head position after the movement by the board's ruleset. Can be outside of the board.
+    pub fn movement_position(&self, p: GridPoint, movement: Movement) -> GridPoint {
+        self.ruleset.movement_position(p, movement, self.width, self.height)
+    }
+
+    pub fn movement_positions(&self, p: GridPoint) -> [GridPoint; 4] {
+        self.ruleset.movement_positions(p, self.width, self.height)
+    }
+
+    pub fn size(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    pub fn center(&self) -> GridPoint {
+        Point {x: self.width / 2, y: self.height / 2}
+    }
+
+    /// Index of the snake with this API id.
+    pub fn snake_index(&self, id: &str) -> Option<usize> {
+        self.identities.iter().position(|identity| identity.id == id)
     }
 
     pub fn is_hazard(&self, p: GridPoint) -> bool {
-        !self.safe_zone.contains(p)
+        self.hazard_stacks(p) > 0
+    }
+
+    /// How many hazards are stacked on the cell. Each stack deals damage separately.
+    pub fn hazard_stacks(&self, p: GridPoint) -> u8 {
+        self.hazards[(p.x as usize, p.y as usize)] + !self.safe_zone.contains(p) as u8
+    }
+
+    pub fn hazards_count(&self) -> usize {
+        let mut count = 0;
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if self.is_hazard(Point {x, y}) {
+                    count += 1;
+                }
+            }
+        }
+        count
     }
 
     pub fn is_terminal(&self) -> bool {
-        (0..self.snakes.len()).filter(|&i| self.snakes[i].is_alive()).count() <= 1
+        let alive_count = (0..self.snakes.len()).filter(|&i| self.snakes[i].is_alive()).count();
+        if self.ruleset.is_solo() {
+            alive_count == 0
+        } else {
+            alive_count <= 1
+        }
+    }
+
+    /// Place of each snake, 0 is the best. Alive snakes share the first place, eliminated ones are
+    /// ordered by elimination turn. Snakes eliminated on the same turn share the worst of their places.
+    pub fn placements(&self) -> [usize; MAX_SNAKE_COUNT] {
+        let survived_until = |i: usize| {
+            if self.snakes[i].is_alive() {
+                i32::MAX
+            } else {
+                self.eliminations[i].map_or(i32::MIN, |elimination| elimination.turn)
+            }
+        };
+
+        let mut placements = [0; MAX_SNAKE_COUNT];
+        for i in 0..self.snakes.len() {
+            let turn = survived_until(i);
+            placements[i] = (0..self.snakes.len()).filter(|&j| survived_until(j) >= turn).count() - 1;
+        }
+        placements
     }
 
     pub fn put_food(&mut self, pos: GridPoint) {
@@ -138,10 +382,22 @@ impl Board {
         self.zobrist_hash.xor_food(pos.into());
     }
 
-    fn calculate_objects(snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>, foods: &Vec<GridPoint>) -> Objects {
+    pub fn remove_food(&mut self, pos: GridPoint) {
+        self.objects.set_empty_on_food(pos.into());
+        self.foods.retain(|&food| food != pos);
+        self.zobrist_hash.xor_food(pos.into());
+    }
+
+    fn calculate_objects(
+        width: CoordType,
+        height: CoordType,
+        snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>,
+        foods: &Vec<GridPoint>,
+    ) -> Objects {
         let mut objects = Objects::new();
 
-        for snake in snakes {
+        // Bodies of dead snakes are removed from the board.
+        for snake in snakes.iter().filter(|snake| snake.is_alive()) {
             for body_part in snake.body.iter().copied() {
                 if !objects.is_body(body_part.into()) {
                     objects.init_body(body_part.into());
@@ -153,8 +409,8 @@ impl Board {
             objects.init_food(food.into());
         }
 
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
+        for x in 0..width {
+            for y in 0..height {
                 let pos = Point {x, y};
                 if objects.is_default(pos.into()) {
                     objects.init_empty(pos.into());
@@ -165,7 +421,35 @@ impl Board {
         objects
     }
 
-    pub fn calculate_safe_zone(hazards: &Vec<GridPoint>) -> Rectangle {
+    /// Splits API hazards into a safe zone rectangle and a grid of hazard stacks.
+    /// Royale hazards (single stack everywhere outside of a rectangle) are kept in the rectangle only,
+    /// any other hazards go to the grid and the rectangle covers the whole board.
+    pub fn calculate_hazards(width: CoordType, height: CoordType, hazards: &Vec<GridPoint>) -> (Rectangle, Array2D<u8>) {
+        let mut stacks = Array2D::init_same(0u8);
+        for &p in hazards {
+            let p: PointUsize = p.into();
+            stacks[(p.x, p.y)] = stacks[(p.x, p.y)].saturating_add(1);
+        }
+
+        let safe_zone = Self::calculate_safe_zone(width, height, hazards);
+
+        let is_royale = (0..width).all(|x| (0..height).all(|y| {
+            let p = Point {x, y};
+            stacks[(x as usize, y as usize)] == !safe_zone.contains(p) as u8
+        }));
+
+        if is_royale {
+            (safe_zone, Array2D::init_same(0))
+        } else {
+            let whole_board = Rectangle {
+                p0: Point {x: 0, y: 0},
+                p1: Point {x: width, y: height},
+            };
+            (whole_board, stacks)
+        }
+    }
+
+    pub fn calculate_safe_zone(width: CoordType, height: CoordType, hazards: &Vec<GridPoint>) -> Rectangle {
         let is_safe = {
             let mut mask = Array2D::init_same(true);
 
@@ -178,12 +462,12 @@ impl Board {
         };
 
         let mut safe_zone = Rectangle {
-            p0: GridPoint { x: WIDTH, y: HEIGHT },
+            p0: GridPoint { x: width, y: height },
             p1: GridPoint { x: -1, y: -1 },
         };
 
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
+        for x in 0..width {
+            for y in 0..height {
                 if is_safe[(x as usize, y as usize)] {
                     safe_zone.p0.x = safe_zone.p0.x.min(x);
                     safe_zone.p1.x = safe_zone.p1.x.max(x);
@@ -204,39 +488,249 @@ impl Board {
         }
     }
 
-    fn initial_zobrist_hash(
-        turn: i32,
-        snakes: &ArrayVec<Snake, MAX_SNAKE_COUNT>,
-        foods: &Vec<GridPoint>,
-    ) -> ZobristHash {
+    /// Hash of the position from scratch. The engine updates `zobrist_hash` incrementally,
+    /// and it must always be equal to this one.
+    pub fn calculate_zobrist_hash(&self) -> ZobristHash {
         let mut zobrist_hash = ZobristHash::new();
 
-        zobrist_hash.xor_turn(turn);
-        for (snake_index, snake) in snakes.iter().enumerate() {
+        zobrist_hash.xor_turn(self.turn);
+        for (snake_index, snake) in self.snakes.iter().enumerate() {
+            zobrist_hash.xor_health(snake_index, snake.health);
+            // Bodies of dead snakes are removed from the board.
+            if !snake.is_alive() {
+                continue;
+            }
             for i in 1..snake.body.len() {
                 let prev = snake.body[i - 1];
                 let cur = snake.body[i];
                 let direction = body_direction(cur, prev);
                 zobrist_hash.xor_body_direction(cur.into(), snake_index, direction);
-                // TODO: Prevent unxor of Still on first turn
-                // if direction == BodyDirections::Still {
-                //     break
-                // }
             }
         }
 
-        for food in foods.iter().copied() {
+        for food in self.foods.iter().copied() {
             zobrist_hash.xor_food(food.into());
         }
 
+        zobrist_hash.xor_safe_zone(&self.safe_zone);
+        for x in 0..self.width as usize {
+            for y in 0..self.height as usize {
+                zobrist_hash.xor_hazard(PointUsize {x, y}, self.hazards[(x, y)]);
+            }
+        }
+
         zobrist_hash
     }
 
-    pub fn snake_api_to_snake_game(snakes_api: &Vec<api::objects::Snake>) -> ArrayVec<Snake, MAX_SNAKE_COUNT> {
-        snakes_api.iter().map(Snake::from_api).collect()
+    /// Engine keeps bitboards up to date and they must always be equal to these ones.
+    pub fn calculate_snake_bitboards(&self) -> [Bitboard; MAX_SNAKE_COUNT] {
+        let mut bitboards = [Bitboard::EMPTY; MAX_SNAKE_COUNT];
+        for (bitboard, snake) in bitboards.iter_mut().zip(&self.snakes) {
+            if snake.is_alive() {
+                snake.body.iter().for_each(|&p| bitboard.set(p.into()));
+            }
+        }
+        bitboards
+    }
+
+    pub fn calculate_hazard_bitboard(&self) -> Bitboard {
+        let mut bitboard = Bitboard::EMPTY;
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let p = Point {x, y};
+                if self.is_hazard(p) {
+                    bitboard.set(p.into());
+                }
+            }
+        }
+        bitboard
+    }
+
+    /// Cells of the board.
+    pub fn cells(&self) -> Bitboard {
+        Bitboard::full(self.width, self.height)
+    }
+
+    /// Checks everything the engine relies on: the parts of the position are valid
+    /// and the cached objects, bitboards and hash match them.
+    /// The engine checks it after each step in debug builds.
+    pub fn validate(&self) -> Result<(), BoardError> {
+        check_size(self.width, self.height)?;
+        check_snakes(self.ruleset, self.width, self.height, &self.snakes)?;
+        check_foods(self.width, self.height, &self.snakes, &self.foods)?;
+        if !self.identities.is_empty() && self.identities.len() != self.snakes.len() {
+            return Err(BoardError::IdentitiesMismatch);
+        }
+
+        if self.snake_bitboards != self.calculate_snake_bitboards() || self.hazard_bitboard != self.calculate_hazard_bitboard() {
+            return Err(BoardError::InconsistentBitboards);
+        }
+
+        let bodies = self.snake_bitboards.iter().fold(Bitboard::EMPTY, |bodies, &bitboard| bodies | bitboard);
+        let empties_count = self.size() - self.foods.len() - bodies.count();
+        if self.objects.empties.len() != empties_count {
+            return Err(BoardError::InconsistentObjects(None));
+        }
+        for x in 0..MAX_WIDTH {
+            for y in 0..MAX_HEIGHT {
+                let p = Point {x, y};
+                let pos: PointUsize = p.into();
+                let object = self.objects.get(pos);
+                let expected = if !self.contains(p) {
+                    object == DEFAULT
+                } else if self.foods.contains(&p) {
+                    object == FOOD
+                } else if bodies.get(pos) {
+                    object == BODY
+                } else {
+                    is_empty(object) && self.objects.empties.get(object as usize) == Some(&pos)
+                };
+                if !expected || self.objects.occupied.get(pos) != (object == BODY) || self.objects.food.get(pos) != (object == FOOD) {
+                    return Err(BoardError::InconsistentObjects(Some(p)));
+                }
+            }
+        }
+
+        if self.zobrist_hash != self.calculate_zobrist_hash() {
+            return Err(BoardError::InconsistentZobristHash);
+        }
+
+        Ok(())
+    }
+
+    pub fn snake_api_to_snake_game(snakes_api: &[api::objects::Snake]) -> Result<ArrayVec<Snake, MAX_SNAKE_COUNT>, BoardError> {
+        if snakes_api.len() > MAX_SNAKE_COUNT {
+            return Err(BoardError::TooManySnakes(snakes_api.len()));
+        }
+        snakes_api.iter().enumerate().map(|(i, snake_api)| Snake::try_from_api(i, snake_api)).collect()
+    }
+}
+
+fn is_inside(p: GridPoint, width: CoordType, height: CoordType) -> bool {
+    (p.x < width) && (p.y < height) && (p.x >= 0) && (p.y >= 0)
+}
+
+fn check_size(width: CoordType, height: CoordType) -> Result<(), BoardError> {
+    if 0 < width && width <= MAX_WIDTH && 0 < height && height <= MAX_HEIGHT {
+        Ok(())
+    } else {
+        Err(BoardError::UnsupportedSize {width, height})
+    }
+}
+
+// Bodies of alive snakes go by adjacent cells, only the tail can be stacked, and they don't overlap.
+// Bodies of dead snakes are not on the board and are not checked.
+fn check_snakes(ruleset: RulesetKind, width: CoordType, height: CoordType, snakes: &[Snake]) -> Result<(), BoardError> {
+    if snakes.len() > MAX_SNAKE_COUNT {
+        return Err(BoardError::TooManySnakes(snakes.len()));
+    }
+
+    let mut occupied = Bitboard::EMPTY;
+    for (snake_index, snake) in snakes.iter().enumerate() {
+        if snake.body.is_empty() {
+            return Err(BoardError::EmptyBody {snake: snake_index});
+        }
+        if !snake.is_alive() {
+            continue;
+        }
+
+        let mut cells = Bitboard::EMPTY;
+        let mut stacked = false;
+        for (part, &p) in snake.body.iter().enumerate() {
+            if !is_inside(p, width, height) {
+                return Err(BoardError::OutOfBounds(p));
+            }
+            if part > 0 {
+                let next = snake.body[part - 1];
+                if p == next {
+                    stacked = true;
+                    continue;
+                }
+                if stacked || !ruleset.movement_positions(p, width, height).contains(&next) {
+                    return Err(BoardError::BodyNotConnected {snake: snake_index, part});
+                }
+            }
+
+            let pos: PointUsize = p.into();
+            if cells.get(pos) || occupied.get(pos) {
+                return Err(BoardError::BodiesOverlap(p));
+            }
+            cells.set(pos);
+        }
+        occupied |= cells;
     }
+
+    Ok(())
 }
 
+fn check_foods(width: CoordType, height: CoordType, snakes: &[Snake], foods: &[GridPoint]) -> Result<(), BoardError> {
+    for (i, &food) in foods.iter().enumerate() {
+        if !is_inside(food, width, height) {
+            return Err(BoardError::OutOfBounds(food));
+        }
+        if foods[..i].contains(&food) {
+            return Err(BoardError::DuplicateFood(food));
+        }
+        if snakes.iter().any(|snake| snake.is_alive() && snake.body.contains(&food)) {
+            return Err(BoardError::FoodOnBody(food));
+        }
+    }
+    Ok(())
+}
+
+/// Why a position can't be played by the engine.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BoardError {
+    UnsupportedSize { width: CoordType, height: CoordType },
+    TooManySnakes(usize),
+    // Official maps with fixed hazards and start positions are made for one size and number of snakes.
+    UnsupportedMapSize { map: GameMap, width: CoordType, height: CoordType },
+    TooManySnakesOnMap { map: GameMap, count: usize },
+    EmptyBody { snake: usize },
+    HeadIsNotFirstPart { snake: usize },
+    // Snake of the state is not in the previous board of the game.
+    UnknownSnake { snake: usize },
+    BodyTooLong { snake: usize },
+    OutOfBounds(GridPoint),
+    // Part and the next one towards the head are not neighbours, or the body continues after a stacked part.
+    BodyNotConnected { snake: usize, part: usize },
+    BodiesOverlap(GridPoint),
+    FoodOnBody(GridPoint),
+    DuplicateFood(GridPoint),
+    // Map, empties or bitboards of `Objects` don't match the position. The point is the first wrong cell, if any.
+    InconsistentObjects(Option<GridPoint>),
+    IdentitiesMismatch,
+    InconsistentBitboards,
+    InconsistentZobristHash,
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::UnsupportedSize {width, height} => write!(f, "Board {}x{} is not supported", width, height),
+            BoardError::TooManySnakes(count) => write!(f, "{} snakes on board, but only {} are supported", count, MAX_SNAKE_COUNT),
+            BoardError::UnsupportedMapSize {map, width, height} => write!(f, "Map {} doesn't support board {}x{}", map, width, height),
+            BoardError::TooManySnakesOnMap {map, count} => write!(f, "Map {} doesn't support {} snakes", map, count),
+            BoardError::EmptyBody {snake} => write!(f, "Snake {} has no body", snake),
+            BoardError::HeadIsNotFirstPart {snake} => write!(f, "Head of snake {} is not the first part of the body", snake),
+            BoardError::UnknownSnake {snake} => write!(f, "Snake {} was not in the game before", snake),
+            BoardError::BodyTooLong {snake} => write!(f, "Body of snake {} is longer than {}", snake, SNAKE_BODY_CAPACITY),
+            BoardError::OutOfBounds(p) => write!(f, "{:?} is out of the board", p),
+            BoardError::BodyNotConnected {snake, part} => write!(f, "Part {} of snake {} is not next to the previous one", part, snake),
+            BoardError::BodiesOverlap(p) => write!(f, "Bodies overlap at {:?}", p),
+            BoardError::FoodOnBody(p) => write!(f, "Food at {:?} is under a body", p),
+            BoardError::DuplicateFood(p) => write!(f, "Food at {:?} is listed twice", p),
+            BoardError::InconsistentObjects(Some(p)) => write!(f, "Objects don't match the position at {:?}", p),
+            BoardError::InconsistentObjects(None) => write!(f, "Number of empty cells doesn't match the position"),
+            BoardError::IdentitiesMismatch => write!(f, "Number of snake identities doesn't match the number of snakes"),
+            BoardError::InconsistentBitboards => write!(f, "Bitboards don't match the position"),
+            BoardError::InconsistentZobristHash => write!(f, "Zobrist hash doesn't match the position"),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
 impl Hash for Board {
     fn hash<H>(&self, state: &mut H) where H: Hasher {
         state.write_u64(self.zobrist_hash.get_value());
@@ -244,31 +738,113 @@ impl Hash for Board {
 }
 
 
-pub type Object = u8;
-pub const FOOD: u8 = u8::MAX;
-pub const BODY: u8 = u8::MAX - 1;
-pub const DEFAULT: u8 = u8::MAX - 2;
-// EMPTY is dynamic and indexes to Objects.empties from 0 to 120.
+pub type Object = u16;
+pub const FOOD: Object = Object::MAX;
+pub const BODY: Object = Object::MAX - 1;
+pub const DEFAULT: Object = Object::MAX - 2;
+// EMPTY is dynamic and indexes to Objects.empties from 0 to MAX_SIZE - 1.
+// Cells outside of the board stay DEFAULT.
 
 pub fn is_empty(obj: Object) -> bool {
     obj < DEFAULT
 }
 
-#[derive(PartialEq, Clone, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Objects {
-    pub map: [[Object; HEIGHT as usize]; WIDTH as usize],
-    pub empties: ArrayVec<PointUsize, SIZE>,
-    // TODO: foods from 121 to 241
+    pub map: [[Object; MAX_HEIGHT as usize]; MAX_WIDTH as usize],
+    pub empties: ArrayVec<PointUsize, MAX_SIZE>,
+    // Same cells as in `map`, kept in sync on every change.
+    pub occupied: Bitboard,
+    pub food: Bitboard,
+    // Changes are recorded only while the engine makes an undoable step.
+    #[serde(skip)]
+    journal: Option<Vec<ObjectsChange>>,
 }
 
+/// Change of `Objects`, enough to revert it exactly, including order of `empties`.
+#[derive(Clone, Copy, Debug)]
+pub enum ObjectsChange {
+    Set { pos: PointUsize, old: Object },
+    PushEmpty,
+    SwapRemoveEmpty { index: usize, pos: PointUsize },
+}
+
+impl PartialEq for Objects {
+    fn eq(&self, other: &Self) -> bool {
+        // Bitboards follow the map
+        self.map == other.map && self.empties == other.empties
+    }
+}
+
+impl Eq for Objects {}
+
 impl Objects {
     fn new() -> Objects {
         Objects {
-            map: [[DEFAULT; HEIGHT as usize]; WIDTH as usize],
+            map: [[DEFAULT; MAX_HEIGHT as usize]; MAX_WIDTH as usize],
             empties: ArrayVec::new(),
+            occupied: Bitboard::EMPTY,
+            food: Bitboard::EMPTY,
+            journal: None,
+        }
+    }
+
+    /// New changes are appended to the journal.
+    pub fn start_journal(&mut self, journal: Vec<ObjectsChange>) {
+        self.journal = Some(journal);
+    }
+
+    pub fn take_journal(&mut self) -> Vec<ObjectsChange> {
+        self.journal.take().expect("Journal isn't started")
+    }
+
+    /// Reverts the changes recorded after the first `len` ones and removes them from the journal.
+    pub fn revert(&mut self, journal: &mut Vec<ObjectsChange>, len: usize) {
+        while journal.len() > len {
+            let change = journal.pop().unwrap();
+            match change {
+                ObjectsChange::Set {pos, old} => self.write(pos, old),
+                ObjectsChange::PushEmpty => {
+                    self.empties.pop();
+                },
+                ObjectsChange::SwapRemoveEmpty {index, pos} => {
+                    if index == self.empties.len() {
+                        self.empties.push(pos);
+                    } else {
+                        let moved = self.empties[index];
+                        self.empties[index] = pos;
+                        self.empties.push(moved);
+                    }
+                },
+            }
         }
     }
 
+    fn record(&mut self, change: ObjectsChange) {
+        if let Some(journal) = &mut self.journal {
+            journal.push(change);
+        }
+    }
+
+    fn _set(&mut self, pos: PointUsize, object: Object) {
+        self.record(ObjectsChange::Set {pos, old: self.map[pos.x][pos.y]});
+        self.write(pos, object);
+    }
+
+    fn write(&mut self, pos: PointUsize, object: Object) {
+        match self.map[pos.x][pos.y] {
+            BODY => self.occupied.unset(pos),
+            FOOD => self.food.unset(pos),
+            _ => {},
+        }
+        match object {
+            BODY => self.occupied.set(pos),
+            FOOD => self.food.set(pos),
+            _ => {},
+        }
+        self.map[pos.x][pos.y] = object;
+    }
+
     pub fn get(&self, pos: PointUsize) -> Object {
         self.map[pos.x][pos.y]
     }
@@ -281,17 +857,17 @@ impl Objects {
 
     pub fn set_food_on_empty(&mut self, pos: PointUsize) {
         self._remove_empty(pos);
-        self.map[pos.x][pos.y] = FOOD;
+        self._set(pos, FOOD);
     }
 
     pub fn set_food_on_body(&mut self, pos: PointUsize) {
         debug_assert!(self.is_body(pos));
-        self.map[pos.x][pos.y] = FOOD;
+        self._set(pos, FOOD);
     }
 
     pub fn init_food(&mut self, pos: PointUsize) {
         debug_assert!(self.is_default(pos));
-        self.map[pos.x][pos.y] = FOOD;
+        self._set(pos, FOOD);
     }
 
     pub fn is_food(&self, pos: PointUsize) -> bool {
@@ -302,17 +878,17 @@ impl Objects {
 
     pub fn set_body_on_empty(&mut self, pos: PointUsize) {
         self._remove_empty(pos);
-        self.map[pos.x][pos.y] = BODY;
+        self._set(pos, BODY);
     }
 
     pub fn set_body_on_food(&mut self, pos: PointUsize) {
         debug_assert!(self.is_food(pos));
-        self.map[pos.x][pos.y] = BODY;
+        self._set(pos, BODY);
     }
 
     pub fn init_body(&mut self, pos: PointUsize) {
         debug_assert!(self.is_default(pos));
-        self.map[pos.x][pos.y] = BODY;
+        self._set(pos, BODY);
     }
 
     pub fn is_body(&self, pos: PointUsize) -> bool {
@@ -341,21 +917,23 @@ impl Objects {
     }
 
     fn _set_empty(&mut self, pos: PointUsize) {
-        let empty = self.empties.len() as u8;
+        let empty = self.empties.len() as Object;
         self.empties.push(pos);
-        self.map[pos.x][pos.y] = empty;
+        self.record(ObjectsChange::PushEmpty);
+        self._set(pos, empty);
     }
 
     fn _remove_empty(&mut self, pos: PointUsize) {
         debug_assert!(self.is_empty(pos), "{:?} is not empty, it's {}", pos, self.get(pos));
         let i = self.map[pos.x][pos.y] as usize;
         self.empties.swap_remove(i);
+        self.record(ObjectsChange::SwapRemoveEmpty {index: i, pos});
         
         if i != self.empties.len() {
             // Now when where is new empty on this position
             // we must change map pointer
             let last_empty_pos = self.empties[i];
-            self.map[last_empty_pos.x][last_empty_pos.y] = i as u8;
+            self._set(last_empty_pos, i as Object);
         }
     }
 
@@ -371,11 +949,10 @@ impl Objects {
 
 }
 
-pub fn random_point_inside_borders() -> GridPoint {
-    let mut rnd = thread_rng();
+pub fn random_point_inside_borders(rng: &mut impl Rng, width: CoordType, height: CoordType) -> GridPoint {
     Point {
-        x: rnd.gen_range(0..WIDTH),
-        y: rnd.gen_range(0..HEIGHT),
+        x: rng.gen_range(0..width),
+        y: rng.gen_range(0..height),
     }
 }
 
@@ -384,6 +961,10 @@ const SNAKE_COLORS: [(u8, u8, u8); MAX_SNAKE_COUNT] = [
     (50, 90, 252),
     (255, 250, 160),
     (105, 0, 198),
+    (60, 200, 80),
+    (240, 120, 200),
+    (80, 220, 230),
+    (200, 160, 90),
 ];
 const HAZARD_COLOR: (u8, u8, u8) = (64, 64, 64);
 const FOOD_CHAR: &str = "*";
@@ -391,11 +972,14 @@ const FOOD_CHAR: &str = "*";
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut view: [[ColoredString; HEIGHT as usize]; WIDTH as usize] = Default::default();
+        let mut view = vec![vec![ColoredString::default(); self.height as usize]; self.width as usize];
         let mut output = String::new();
 
         for (i, snake) in self.snakes.iter().enumerate() {
             if !snake.is_alive() {
+                if let Some(elimination) = self.eliminations[i] {
+                    output += &format!("{}: eliminated on turn {} by {:?}\n", i, elimination.turn, elimination.cause);
+                }
                 continue;
             }
             let color = SNAKE_COLORS[i];
@@ -423,8 +1007,8 @@ impl fmt::Display for Board {
             view[food.x as usize][food.y as usize] = FOOD_CHAR.bright_green();
         }
 
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
+        for x in 0..self.width {
+            for y in 0..self.height {
                 let mut fill = view[x as usize][y as usize].clone();
                 if fill.is_empty() {
                     fill = ColoredString::from(".");
@@ -440,8 +1024,8 @@ impl fmt::Display for Board {
 
         output += "\n";
 
-        for y in (0..HEIGHT as usize).rev() {
-            for x in 0..WIDTH as usize {
+        for y in (0..self.height as usize).rev() {
+            for x in 0..self.width as usize {
                 output += view[x][y].to_string().as_str();
             }
             output += "\n";
@@ -451,15 +1035,32 @@ impl fmt::Display for Board {
     }
 }
 
+impl SnakeIdentity {
+    pub fn from_api(snake_api: &api::objects::Snake) -> SnakeIdentity {
+        SnakeIdentity {
+            id: snake_api.id.clone(),
+            name: snake_api.name.clone(),
+            squad: snake_api.squad.clone(),
+        }
+    }
+}
+
 impl Snake {
-    pub fn from_api(snake_api: &api::objects::Snake) -> Snake {
-        assert!(snake_api.body.len() > 0);
-        assert_eq!(snake_api.head, snake_api.body[0]);
+    pub fn try_from_api(snake_index: usize, snake_api: &api::objects::Snake) -> Result<Snake, BoardError> {
+        if snake_api.body.is_empty() {
+            return Err(BoardError::EmptyBody {snake: snake_index});
+        }
+        if snake_api.head != snake_api.body[0] {
+            return Err(BoardError::HeadIsNotFirstPart {snake: snake_index});
+        }
+        if snake_api.body.len() > SNAKE_BODY_CAPACITY {
+            return Err(BoardError::BodyTooLong {snake: snake_index});
+        }
 
-        Snake {
+        Ok(Snake {
             health: snake_api.health,
-            body: snake_api.body.iter().copied().collect(), // TODO: reserve some capacity
-        }
+            body: snake_api.body.iter().copied().collect(),
+        })
     }
 
     pub fn is_alive(&self) -> bool {
@@ -525,3 +1126,163 @@ impl Rectangle {
         self.p0.y >= self.p1.y
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayVec;
+
+    use super::{Board, BoardError, GridPoint, Point, Rectangle, Snake};
+    use rocket::serde::json::serde_json;
+
+    use crate::api::objects::State;
+    use crate::engine::{Elimination, EliminationCause};
+    use crate::features::collector::get_placement_rewards;
+    use crate::ruleset::RulesetKind;
+    use crate::settings::GameSettings;
+    use crate::test_data as data;
+
+    #[test]
+    fn test_calculate_hazards() {
+        // Royale: single stack around the safe zone goes to rectangle
+        let mut hazards = Vec::new();
+        for y in 0..7 {
+            hazards.push(Point {x: 0, y});
+        }
+        let (safe_zone, stacks) = Board::calculate_hazards(7, 7, &hazards);
+        assert_eq!(safe_zone, Rectangle {p0: Point {x: 1, y: 0}, p1: Point {x: 7, y: 7}});
+        assert!(stacks.data.iter().flatten().all(|&s| s == 0));
+
+        // Stacked hazards go to the grid
+        hazards.push(Point {x: 0, y: 3});
+        let (safe_zone, stacks) = Board::calculate_hazards(7, 7, &hazards);
+        assert_eq!(safe_zone, Rectangle {p0: Point {x: 0, y: 0}, p1: Point {x: 7, y: 7}});
+        assert_eq!(stacks[(0, 3)], 2);
+        assert_eq!(stacks[(0, 4)], 1);
+
+        // Hazards in the middle of the board go to the grid
+        let (safe_zone, stacks) = Board::calculate_hazards(7, 7, &vec![Point {x: 3, y: 3}]);
+        assert_eq!(safe_zone, Rectangle {p0: Point {x: 0, y: 0}, p1: Point {x: 7, y: 7}});
+        assert_eq!(stacks[(3, 3)], 1);
+
+        // No hazards
+        let (safe_zone, _) = Board::calculate_hazards(7, 7, &vec![]);
+        assert_eq!(safe_zone, Rectangle {p0: Point {x: 0, y: 0}, p1: Point {x: 7, y: 7}});
+    }
+
+    #[test]
+    fn test_zobrist_hash_covers_health_and_hazards() {
+        let make_board = |health: i32, turn: i32| {
+            let mut snakes = ArrayVec::new();
+            let p = Point {x: 3, y: 3};
+            snakes.push(Snake {health, body: [p, p, p].into()});
+            Board::new(RulesetKind::Solo, GameSettings::default(), 11, 11, turn, Some(vec![Point {x: 5, y: 5}]), None, None, snakes)
+        };
+
+        let board = make_board(100, 0);
+        assert_ne!(board.zobrist_hash, make_board(99, 0).zobrist_hash);
+        assert_ne!(board.zobrist_hash, make_board(100, 1).zobrist_hash);
+
+        let mut hazard_board = board.clone();
+        hazard_board.hazards[(1, 1)] = 1;
+        assert_ne!(board.zobrist_hash, hazard_board.calculate_zobrist_hash());
+
+        let mut shrunk_board = board.clone();
+        shrunk_board.safe_zone.p0.x += 1;
+        assert_ne!(board.zobrist_hash, shrunk_board.calculate_zobrist_hash());
+    }
+
+    #[test]
+    fn test_placements() {
+        let mut snakes = ArrayVec::new();
+        for x in [1, 3, 5, 7] {
+            let p = Point {x, y: 1};
+            snakes.push(Snake {health: 100, body: [p, p, p].into()});
+        }
+        let mut board = Board::new(RulesetKind::Standard, GameSettings::default(), 11, 11, 30, Some(vec![Point {x: 5, y: 5}]), None, None, snakes);
+
+        let mut eliminate = |i: usize, turn: i32| {
+            board.snakes[i].health = 0;
+            board.eliminations[i] = Some(Elimination {turn, cause: EliminationCause::Starvation});
+        };
+        eliminate(0, 20);
+        eliminate(2, 10);
+        eliminate(3, 20);
+
+        assert_eq!(board.placements()[..4], [2, 0, 3, 2]);
+        assert_eq!(get_placement_rewards(&board)[..4], [1.0 - 2.0 / 3.0, 1.0, 0.0, 1.0 - 2.0 / 3.0]);
+
+        // Everybody is eliminated on the same turn
+        board.snakes[1].health = 0;
+        board.eliminations[1] = board.eliminations[0];
+        board.eliminations[2] = board.eliminations[0];
+        assert_eq!(board.placements()[..4], [3, 3, 3, 3]);
+        assert_eq!(get_placement_rewards(&board)[..4], [0.0; 4]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let try_new = |ruleset: RulesetKind, width: i32, foods: Vec<GridPoint>, bodies: &[&[(i32, i32)]]| {
+            let snakes = bodies.iter().map(|body| Snake {
+                health: 100,
+                body: body.iter().map(|&(x, y)| Point {x, y}).collect(),
+            }).collect();
+            Board::try_new(ruleset, GameSettings::default(), width, 5, 0, Some(foods), None, None, snakes)
+        };
+        let standard = RulesetKind::Standard;
+        let food = vec![Point {x: 4, y: 4}];
+
+        assert!(try_new(standard, 5, food.clone(), &[&[(0, 0), (0, 1), (1, 1), (1, 1)], &[(2, 2), (2, 2), (2, 2)]]).is_ok());
+        assert_eq!(try_new(standard, 20, food.clone(), &[]).unwrap_err(), BoardError::UnsupportedSize {width: 20, height: 5});
+        assert_eq!(try_new(standard, 5, vec![Point {x: 0, y: 1}], &[&[(0, 0), (0, 1)]]).unwrap_err(), BoardError::FoodOnBody(Point {x: 0, y: 1}));
+        assert_eq!(try_new(standard, 5, vec![Point {x: 5, y: 0}], &[]).unwrap_err(), BoardError::OutOfBounds(Point {x: 5, y: 0}));
+        assert_eq!(try_new(standard, 5, food.clone(), &[&[(0, 0), (0, 2)]]).unwrap_err(), BoardError::BodyNotConnected {snake: 0, part: 1});
+        assert_eq!(try_new(standard, 5, food.clone(), &[&[(0, 0), (0, 0), (0, 1)]]).unwrap_err(), BoardError::BodyNotConnected {snake: 0, part: 2});
+        assert_eq!(
+            try_new(standard, 5, food.clone(), &[&[(0, 0), (0, 1), (1, 1), (1, 0), (0, 0)]]).unwrap_err(),
+            BoardError::BodiesOverlap(Point {x: 0, y: 0}),
+        );
+        assert_eq!(try_new(standard, 5, food.clone(), &[&[(0, 0), (0, 1)], &[(1, 1), (0, 1)]]).unwrap_err(), BoardError::BodiesOverlap(Point {x: 0, y: 1}));
+
+        // Body goes across the border only in wrapped games
+        let across_border: &[&[(i32, i32)]] = &[&[(0, 0), (0, 4), (1, 4)]];
+        assert!(try_new(RulesetKind::Wrapped, 5, food.clone(), across_border).is_ok());
+        assert_eq!(try_new(standard, 5, food.clone(), across_border).unwrap_err(), BoardError::BodyNotConnected {snake: 0, part: 1});
+
+        // No free cell for the food on a full board
+        let board = try_new(standard, 1, vec![], &[&[(0, 0), (0, 1), (0, 2), (0, 3), (0, 4)]]).unwrap();
+        assert!(board.foods.is_empty());
+
+        let board = try_new(standard, 5, food, &[&[(0, 0), (0, 1)]]).unwrap();
+        let mut corrupted = board.clone();
+        corrupted.objects.set_food_on_empty(Point {x: 3, y: 3}.into());
+        assert_eq!(corrupted.validate(), Err(BoardError::InconsistentObjects(None)));
+        let mut corrupted = board.clone();
+        corrupted.zobrist_hash.xor_turn(1);
+        assert_eq!(corrupted.validate(), Err(BoardError::InconsistentZobristHash));
+        let mut corrupted = board;
+        corrupted.snake_bitboards[0].set(Point {x: 3, y: 3}.into());
+        assert_eq!(corrupted.validate(), Err(BoardError::InconsistentBitboards));
+    }
+
+    #[test]
+    fn test_snake_indices_survive_eliminations() {
+        let mut state: State = serde_json::from_str(data::HEAD_TO_HEAD_OUT_OF_HEALTH).unwrap();
+        let first = Board::try_from_api(&state).unwrap();
+        let our_id = state.you.id.clone();
+        assert_eq!(first.snake_index(&our_id), Some(1));
+
+        // The API doesn't send eliminated snakes
+        let eliminated = state.board.snakes.remove(0);
+        state.turn += 1;
+        let board = Board::try_from_api_after(&state, &first).unwrap();
+        assert_eq!(board.snakes.len(), 2);
+        assert!(!board.snakes[0].is_alive());
+        assert_eq!(board.snakes[1], first.snakes[1]);
+        assert_eq!(board.identities[0].id, eliminated.id);
+        assert_eq!(board.snake_index(&our_id), Some(1));
+        assert_eq!(Board::try_from_api(&state).unwrap().snake_index(&our_id), Some(0));
+
+        state.board.snakes[0].id = String::from("stranger");
+        assert_eq!(Board::try_from_api_after(&state, &first).unwrap_err(), BoardError::UnknownSnake {snake: 0});
+    }
+}
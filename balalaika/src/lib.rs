@@ -1,7 +1,14 @@
 pub mod api;
 pub mod game;
 pub mod engine;
+pub mod ruleset;
+pub mod settings;
 pub mod array2d;
+pub mod snake_body;
+pub mod bitboard;
+pub mod maps;
+pub mod notation;
+pub mod symmetry;
 pub mod mcts;
 pub mod zobrist;
 pub mod game_log;
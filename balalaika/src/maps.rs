@@ -0,0 +1,1096 @@
+//! Hazards of the official maps (`arena/rules/maps`). Maps with random hazards are not supported,
+//! their hazards depend on the seed of the game.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::array2d::Array2D;
+use crate::engine::VacatedTail;
+use crate::game::{Board, BoardError, CoordType, GridPoint, Point};
+use crate::settings::{GameMap, GameSettings};
+
+/// Snakes start at the corners of hazard pits map, pits are not dug there.
+const HAZARD_PIT_START_POSITIONS: [GridPoint; 4] = [
+    Point {x: 1, y: 1},
+    Point {x: 9, y: 1},
+    Point {x: 1, y: 9},
+    Point {x: 9, y: 9},
+];
+
+const HAZARD_PITS_MAX_LAYERS: u8 = 4;
+
+const SPIRAL_SPAWN_EVERY: i32 = 3;
+// Spiral starts in the middle of the board, at least this part of the side away from the borders.
+const SPIRAL_SPAWN_AREA: f32 = 0.3;
+
+/// Castle wall for medium board. Cells next to the passages are stacked twice.
+const CASTLE_WALL_HAZARDS: [GridPoint; 28] = [
+    Point {x: 2, y: 2},
+    Point {x: 2, y: 3},
+    Point {x: 2, y: 4},
+    Point {x: 2, y: 6},
+    Point {x: 2, y: 7},
+    Point {x: 2, y: 8},
+    Point {x: 3, y: 2},
+    Point {x: 3, y: 8},
+    Point {x: 4, y: 2},
+    Point {x: 4, y: 8},
+    Point {x: 6, y: 2},
+    Point {x: 6, y: 8},
+    Point {x: 7, y: 2},
+    Point {x: 7, y: 8},
+    Point {x: 8, y: 2},
+    Point {x: 8, y: 3},
+    Point {x: 8, y: 4},
+    Point {x: 8, y: 6},
+    Point {x: 8, y: 7},
+    Point {x: 8, y: 8},
+    Point {x: 2, y: 4},
+    Point {x: 2, y: 6},
+    Point {x: 4, y: 2},
+    Point {x: 4, y: 8},
+    Point {x: 6, y: 2},
+    Point {x: 6, y: 8},
+    Point {x: 8, y: 4},
+    Point {x: 8, y: 6},
+];
+
+/// Food spawns only in the passages of the castle wall.
+const CASTLE_WALL_FOOD: [GridPoint; 4] = [
+    Point {x: 2, y: 5},
+    Point {x: 5, y: 2},
+    Point {x: 5, y: 8},
+    Point {x: 8, y: 5},
+];
+
+const CASTLE_WALL_MAX_FOOD: usize = 2;
+
+/// Corners are taken first, sides are added for 5 or more snakes.
+const CASTLE_WALL_START_POSITIONS: [[GridPoint; 4]; 2] = [
+    [Point {x: 1, y: 1}, Point {x: 1, y: 9}, Point {x: 9, y: 1}, Point {x: 9, y: 9}],
+    [Point {x: 1, y: 5}, Point {x: 5, y: 1}, Point {x: 5, y: 9}, Point {x: 9, y: 5}],
+];
+
+/// Lake in the center with rivers to the sides, each river has bridges.
+const RIVERS_AND_BRIDGES_HAZARDS: [GridPoint; 17] = [
+    Point {x: 5, y: 10},
+    Point {x: 5, y: 9},
+    Point {x: 5, y: 7},
+    Point {x: 5, y: 6},
+    Point {x: 5, y: 5},
+    Point {x: 5, y: 4},
+    Point {x: 5, y: 3},
+    Point {x: 5, y: 0},
+    Point {x: 5, y: 1},
+    Point {x: 6, y: 5},
+    Point {x: 7, y: 5},
+    Point {x: 9, y: 5},
+    Point {x: 10, y: 5},
+    Point {x: 4, y: 5},
+    Point {x: 3, y: 5},
+    Point {x: 1, y: 5},
+    Point {x: 0, y: 5},
+];
+
+/// Start positions by quadrants. Snakes are spread over the quadrants in turn.
+const RIVERS_AND_BRIDGES_START_POSITIONS: [[GridPoint; 2]; 4] = [
+    [Point {x: 1, y: 1}, Point {x: 3, y: 3}],
+    [Point {x: 9, y: 9}, Point {x: 7, y: 7}],
+    [Point {x: 1, y: 9}, Point {x: 3, y: 9}],
+    [Point {x: 9, y: 1}, Point {x: 7, y: 3}],
+];
+
+/// Maze of the arcade maze map, the only map of 19x21 board.
+const ARCADE_MAZE_SIZE: (CoordType, CoordType) = (19, 21);
+
+const ARCADE_MAZE_HAZARDS: [GridPoint; 187] = [
+    Point {x: 0, y: 20},
+    Point {x: 2, y: 20},
+    Point {x: 3, y: 20},
+    Point {x: 4, y: 20},
+    Point {x: 5, y: 20},
+    Point {x: 6, y: 20},
+    Point {x: 7, y: 20},
+    Point {x: 8, y: 20},
+    Point {x: 9, y: 20},
+    Point {x: 10, y: 20},
+    Point {x: 11, y: 20},
+    Point {x: 12, y: 20},
+    Point {x: 13, y: 20},
+    Point {x: 14, y: 20},
+    Point {x: 15, y: 20},
+    Point {x: 16, y: 20},
+    Point {x: 18, y: 20},
+    Point {x: 0, y: 19},
+    Point {x: 9, y: 19},
+    Point {x: 18, y: 19},
+    Point {x: 0, y: 18},
+    Point {x: 2, y: 18},
+    Point {x: 3, y: 18},
+    Point {x: 5, y: 18},
+    Point {x: 6, y: 18},
+    Point {x: 7, y: 18},
+    Point {x: 9, y: 18},
+    Point {x: 11, y: 18},
+    Point {x: 12, y: 18},
+    Point {x: 13, y: 18},
+    Point {x: 15, y: 18},
+    Point {x: 16, y: 18},
+    Point {x: 18, y: 18},
+    Point {x: 0, y: 17},
+    Point {x: 18, y: 17},
+    Point {x: 0, y: 16},
+    Point {x: 2, y: 16},
+    Point {x: 3, y: 16},
+    Point {x: 5, y: 16},
+    Point {x: 7, y: 16},
+    Point {x: 8, y: 16},
+    Point {x: 9, y: 16},
+    Point {x: 10, y: 16},
+    Point {x: 11, y: 16},
+    Point {x: 13, y: 16},
+    Point {x: 15, y: 16},
+    Point {x: 16, y: 16},
+    Point {x: 18, y: 16},
+    Point {x: 0, y: 15},
+    Point {x: 5, y: 15},
+    Point {x: 9, y: 15},
+    Point {x: 13, y: 15},
+    Point {x: 18, y: 15},
+    Point {x: 0, y: 14},
+    Point {x: 3, y: 14},
+    Point {x: 5, y: 14},
+    Point {x: 6, y: 14},
+    Point {x: 7, y: 14},
+    Point {x: 9, y: 14},
+    Point {x: 11, y: 14},
+    Point {x: 12, y: 14},
+    Point {x: 13, y: 14},
+    Point {x: 15, y: 14},
+    Point {x: 18, y: 14},
+    Point {x: 0, y: 13},
+    Point {x: 3, y: 13},
+    Point {x: 5, y: 13},
+    Point {x: 13, y: 13},
+    Point {x: 15, y: 13},
+    Point {x: 18, y: 13},
+    Point {x: 0, y: 12},
+    Point {x: 1, y: 12},
+    Point {x: 2, y: 12},
+    Point {x: 3, y: 12},
+    Point {x: 5, y: 12},
+    Point {x: 7, y: 12},
+    Point {x: 9, y: 12},
+    Point {x: 11, y: 12},
+    Point {x: 13, y: 12},
+    Point {x: 15, y: 12},
+    Point {x: 16, y: 12},
+    Point {x: 17, y: 12},
+    Point {x: 18, y: 12},
+    Point {x: 7, y: 11},
+    Point {x: 11, y: 11},
+    Point {x: 0, y: 10},
+    Point {x: 1, y: 10},
+    Point {x: 2, y: 10},
+    Point {x: 3, y: 10},
+    Point {x: 5, y: 10},
+    Point {x: 7, y: 10},
+    Point {x: 9, y: 10},
+    Point {x: 11, y: 10},
+    Point {x: 13, y: 10},
+    Point {x: 15, y: 10},
+    Point {x: 16, y: 10},
+    Point {x: 17, y: 10},
+    Point {x: 18, y: 10},
+    Point {x: 0, y: 9},
+    Point {x: 3, y: 9},
+    Point {x: 5, y: 9},
+    Point {x: 13, y: 9},
+    Point {x: 15, y: 9},
+    Point {x: 18, y: 9},
+    Point {x: 0, y: 8},
+    Point {x: 3, y: 8},
+    Point {x: 5, y: 8},
+    Point {x: 7, y: 8},
+    Point {x: 8, y: 8},
+    Point {x: 9, y: 8},
+    Point {x: 10, y: 8},
+    Point {x: 11, y: 8},
+    Point {x: 13, y: 8},
+    Point {x: 15, y: 8},
+    Point {x: 18, y: 8},
+    Point {x: 0, y: 7},
+    Point {x: 9, y: 7},
+    Point {x: 18, y: 7},
+    Point {x: 0, y: 6},
+    Point {x: 2, y: 6},
+    Point {x: 3, y: 6},
+    Point {x: 5, y: 6},
+    Point {x: 6, y: 6},
+    Point {x: 7, y: 6},
+    Point {x: 9, y: 6},
+    Point {x: 11, y: 6},
+    Point {x: 12, y: 6},
+    Point {x: 13, y: 6},
+    Point {x: 15, y: 6},
+    Point {x: 16, y: 6},
+    Point {x: 18, y: 6},
+    Point {x: 0, y: 5},
+    Point {x: 3, y: 5},
+    Point {x: 15, y: 5},
+    Point {x: 18, y: 5},
+    Point {x: 0, y: 4},
+    Point {x: 1, y: 4},
+    Point {x: 3, y: 4},
+    Point {x: 5, y: 4},
+    Point {x: 7, y: 4},
+    Point {x: 8, y: 4},
+    Point {x: 9, y: 4},
+    Point {x: 10, y: 4},
+    Point {x: 11, y: 4},
+    Point {x: 13, y: 4},
+    Point {x: 15, y: 4},
+    Point {x: 17, y: 4},
+    Point {x: 18, y: 4},
+    Point {x: 0, y: 3},
+    Point {x: 5, y: 3},
+    Point {x: 9, y: 3},
+    Point {x: 13, y: 3},
+    Point {x: 18, y: 3},
+    Point {x: 0, y: 2},
+    Point {x: 2, y: 2},
+    Point {x: 3, y: 2},
+    Point {x: 4, y: 2},
+    Point {x: 5, y: 2},
+    Point {x: 6, y: 2},
+    Point {x: 7, y: 2},
+    Point {x: 9, y: 2},
+    Point {x: 11, y: 2},
+    Point {x: 12, y: 2},
+    Point {x: 13, y: 2},
+    Point {x: 14, y: 2},
+    Point {x: 15, y: 2},
+    Point {x: 16, y: 2},
+    Point {x: 18, y: 2},
+    Point {x: 0, y: 1},
+    Point {x: 18, y: 1},
+    Point {x: 0, y: 0},
+    Point {x: 2, y: 0},
+    Point {x: 3, y: 0},
+    Point {x: 4, y: 0},
+    Point {x: 5, y: 0},
+    Point {x: 6, y: 0},
+    Point {x: 7, y: 0},
+    Point {x: 8, y: 0},
+    Point {x: 9, y: 0},
+    Point {x: 10, y: 0},
+    Point {x: 11, y: 0},
+    Point {x: 12, y: 0},
+    Point {x: 13, y: 0},
+    Point {x: 14, y: 0},
+    Point {x: 15, y: 0},
+    Point {x: 16, y: 0},
+    Point {x: 18, y: 0},
+];
+
+/// Food spawns only at these spots of the maze, one at a time.
+const ARCADE_MAZE_FOOD: [GridPoint; 12] = [
+    Point {x: 1, y: 1},
+    Point {x: 3, y: 11},
+    Point {x: 4, y: 7},
+    Point {x: 4, y: 17},
+    Point {x: 9, y: 1},
+    Point {x: 9, y: 5},
+    Point {x: 9, y: 11},
+    Point {x: 9, y: 17},
+    Point {x: 14, y: 7},
+    Point {x: 14, y: 17},
+    Point {x: 15, y: 11},
+    Point {x: 17, y: 1},
+];
+
+/// Food placed in the center before the first turn, if the minimum food is set.
+const ARCADE_MAZE_CENTER_FOOD: GridPoint = Point {x: 9, y: 11};
+
+/// First four snakes start at shuffled positions, the fifth and the sixth in the middle.
+const ARCADE_MAZE_START_POSITIONS: [GridPoint; 4] = [
+    Point {x: 4, y: 7},
+    Point {x: 14, y: 7},
+    Point {x: 4, y: 17},
+    Point {x: 14, y: 17},
+];
+
+const ARCADE_MAZE_LAST_START_POSITIONS: [GridPoint; 2] = [
+    Point {x: 9, y: 9},
+    Point {x: 9, y: 13},
+];
+
+/// Sets of healing pools for each board size, one of them is picked at the start.
+fn healing_pool_options(width: CoordType, height: CoordType) -> &'static [&'static [GridPoint]] {
+    match (width, height) {
+        (7, 7) => &[
+            &[Point {x: 3, y: 3}],
+        ],
+        (11, 11) => &[
+            &[Point {x: 3, y: 3}, Point {x: 7, y: 7}],
+            &[Point {x: 3, y: 7}, Point {x: 7, y: 3}],
+            &[Point {x: 3, y: 5}, Point {x: 7, y: 5}],
+            &[Point {x: 5, y: 7}, Point {x: 5, y: 3}],
+        ],
+        (19, 19) => &[
+            &[Point {x: 5, y: 5}, Point {x: 13, y: 13}, Point {x: 5, y: 13}, Point {x: 13, y: 5}],
+            &[Point {x: 5, y: 10}, Point {x: 13, y: 10}, Point {x: 10, y: 13}, Point {x: 10, y: 5}],
+        ],
+        _ => &[],
+    }
+}
+
+// Hazards which never change. Wall maps are defined only for the size of their official version.
+fn static_hazards(map: GameMap, width: CoordType, height: CoordType) -> &'static [GridPoint] {
+    match (map, width, height) {
+        (GameMap::CastleWall, 11, 11) => &CASTLE_WALL_HAZARDS,
+        (GameMap::RiversAndBridges, 11, 11) => &RIVERS_AND_BRIDGES_HAZARDS,
+        (GameMap::ArcadeMaze, 19, 21) => &ARCADE_MAZE_HAZARDS,
+        _ => &[],
+    }
+}
+
+/// Hazards placed by the map before the first turn.
+pub fn initial_hazards(rng: &mut impl Rng, map: GameMap, width: CoordType, height: CoordType) -> Array2D<u8> {
+    let mut hazards = Array2D::init_same(0);
+    for p in static_hazards(map, width, height) {
+        hazards[(p.x as usize, p.y as usize)] += 1;
+    }
+    match map {
+        GameMap::HealingPools => {
+            if let Some(pools) = healing_pool_options(width, height).choose(rng) {
+                for p in pools.iter() {
+                    hazards[(p.x as usize, p.y as usize)] += 1;
+                }
+            }
+        },
+        GameMap::InnerWall => add_ring(&mut hazards, width, height, 2),
+        GameMap::Rings => {
+            for offset in (2..width / 2).step_by(2) {
+                add_ring(&mut hazards, width, height, offset);
+            }
+        },
+        GameMap::Columns => {
+            for x in (1..width).step_by(2) {
+                for y in (1..height).step_by(2) {
+                    hazards[(x as usize, y as usize)] += 1;
+                }
+            }
+        },
+        _ => {},
+    }
+    hazards
+}
+
+/// Checks that the map can set up the board. Maps with fixed hazards and start positions only
+/// support the size and number of snakes of their official version, other versions are separate maps.
+pub fn check_map_size(map: GameMap, width: CoordType, height: CoordType, snake_count: usize) -> Result<(), BoardError> {
+    let (size, max_snakes) = match map {
+        GameMap::HazardPits => ((11, 11), HAZARD_PIT_START_POSITIONS.len()),
+        GameMap::CastleWall => ((11, 11), CASTLE_WALL_START_POSITIONS.len() * CASTLE_WALL_START_POSITIONS[0].len()),
+        GameMap::RiversAndBridges => ((11, 11), RIVERS_AND_BRIDGES_START_POSITIONS.len() * RIVERS_AND_BRIDGES_START_POSITIONS[0].len()),
+        GameMap::ArcadeMaze => (ARCADE_MAZE_SIZE, ARCADE_MAZE_START_POSITIONS.len() + ARCADE_MAZE_LAST_START_POSITIONS.len()),
+        _ => return Ok(()),
+    };
+    if (width, height) != size {
+        return Err(BoardError::UnsupportedMapSize {map, width, height});
+    }
+    if snake_count > max_snakes {
+        return Err(BoardError::TooManySnakesOnMap {map, count: snake_count});
+    }
+    Ok(())
+}
+
+/// Start positions of the snakes for maps which place snakes on their own, in order of the snakes.
+pub fn start_positions(rng: &mut impl Rng, map: GameMap, width: CoordType, height: CoordType, snake_count: usize) -> Option<Vec<GridPoint>> {
+    match (map, width, height) {
+        (GameMap::HazardPits, 11, 11) => {
+            let mut positions = HAZARD_PIT_START_POSITIONS.to_vec();
+            positions.shuffle(rng);
+            Some(positions)
+        },
+        (GameMap::CastleWall, 11, 11) => {
+            let groups = if snake_count >= 5 { 2 } else { 1 };
+            let mut positions: Vec<_> = CASTLE_WALL_START_POSITIONS[..groups].concat();
+            positions.shuffle(rng);
+            Some(positions)
+        },
+        (GameMap::RiversAndBridges, 11, 11) => {
+            let mut quadrants = RIVERS_AND_BRIDGES_START_POSITIONS.map(Vec::from);
+            let first = rng.gen_range(0..quadrants.len());
+            let positions = (0..snake_count)
+                .map_while(|i| {
+                    let quadrant = &mut quadrants[(first + i) % 4];
+                    (!quadrant.is_empty()).then(|| quadrant.swap_remove(rng.gen_range(0..quadrant.len())))
+                })
+                .collect();
+            Some(positions)
+        },
+        (GameMap::ArcadeMaze, 19, 21) => {
+            let mut positions = ARCADE_MAZE_START_POSITIONS.to_vec();
+            positions.shuffle(rng);
+            positions.extend(ARCADE_MAZE_LAST_START_POSITIONS);
+            Some(positions)
+        },
+        _ => None,
+    }
+}
+
+/// Food placed by the map before the first turn, for maps which don't place it as the standard map.
+pub fn initial_food(settings: &GameSettings, width: CoordType, height: CoordType) -> Option<Vec<GridPoint>> {
+    match (settings.map, width, height) {
+        // Castle starts without food, it appears after turn 10
+        (GameMap::CastleWall, 11, 11) => Some(Vec::new()),
+        (GameMap::ArcadeMaze, 19, 21) => {
+            let foods = if settings.minimum_food > 0 { vec![ARCADE_MAZE_CENTER_FOOD] } else { Vec::new() };
+            Some(foods)
+        },
+        _ => None,
+    }
+}
+
+/// Spawns food after the turn for maps with fixed food spots.
+/// Returns false if the map spawns food as the standard map.
+pub fn spawn_food(board: &mut Board, rng: &mut impl Rng) -> bool {
+    // Official maps are updated before the turn is incremented.
+    let turn = board.turn - 1;
+    match (board.settings.map, board.width, board.height) {
+        (GameMap::CastleWall, 11, 11) => {
+            spawn_castle_wall_food(board, rng, turn);
+            true
+        },
+        (GameMap::ArcadeMaze, 19, 21) => {
+            spawn_arcade_maze_food(board, rng);
+            true
+        },
+        _ => false,
+    }
+}
+
+/// Changes hazards after the turn, as post update of the map in official rules.
+/// Must be called when `board.turn` is already incremented.
+pub fn update_hazards(board: &mut Board, vacated_tails: &[VacatedTail], rng: &mut impl Rng) {
+    // Official maps are updated before the turn is incremented.
+    let turn = board.turn - 1;
+    match board.settings.map {
+        GameMap::SnailMode => update_snail_trails(board, vacated_tails),
+        GameMap::HazardPits => update_hazard_pits(board, turn),
+        GameMap::Sinkholes => update_sinkholes(board, turn),
+        GameMap::HealingPools => update_healing_pools(board, rng, turn),
+        GameMap::Spiral => update_spiral(board, rng),
+        _ => {},
+    }
+}
+
+/// Cell which the spiral covers on the turn, as `hz_spiral` in official rules. It may be outside of the board.
+/// Spiral starts at the center on turn 3 and goes clockwise in rings, a cell every 3 turns.
+pub fn spiral_cell(center: GridPoint, turn: i32) -> Option<GridPoint> {
+    if turn < SPIRAL_SPAWN_EVERY || turn % SPIRAL_SPAWN_EVERY != 0 {
+        return None;
+    }
+    let mut index = turn / SPIRAL_SPAWN_EVERY - 1;
+    if index == 0 {
+        return Some(center);
+    }
+
+    // Ring with `offset` has 8 * offset cells
+    index -= 1;
+    let mut offset = 1;
+    while index >= 8 * offset {
+        index -= 8 * offset;
+        offset += 1;
+    }
+
+    // Ring starts next to its top left corner and ends in it
+    let Point {x: cx, y: cy} = center;
+    let mut p = Point {x: cx - offset + 1, y: cy + offset};
+    for _ in 0..index {
+        if p.y == cy + offset && p.x < cx + offset {
+            p.x += 1;
+        } else if p.x == cx + offset && p.y > cy - offset {
+            p.y -= 1;
+        } else if p.y == cy - offset && p.x > cx - offset {
+            p.x -= 1;
+        } else {
+            p.y += 1;
+        }
+    }
+    Some(p)
+}
+
+/// Adds the spiral cell of the turn, if it's on the board. Must be called when `board.turn` is already incremented.
+pub fn add_spiral_hazard(board: &mut Board, center: GridPoint) {
+    if let Some(p) = spiral_cell(center, board.turn) {
+        if board.contains(p) {
+            board.hazards[(p.x as usize, p.y as usize)] += 1;
+        }
+    }
+}
+
+/// Center of the spiral which left the hazards of the board by the turn, None before the spiral starts.
+pub fn find_spiral_center(board: &Board, turn: i32) -> Option<GridPoint> {
+    let mut stacks = 0;
+    let mut candidates = Vec::new();
+    for x in 0..board.width {
+        for y in 0..board.height {
+            let p = Point {x, y};
+            let p_stacks = board.hazards[(x as usize, y as usize)] as usize;
+            stacks += p_stacks;
+            if p_stacks == 1 && is_in_spiral_spawn_area(p, board.width, board.height) {
+                candidates.push(p);
+            }
+        }
+    }
+
+    candidates.into_iter().find(|&center| {
+        let mut covered = 0;
+        for turn in (SPIRAL_SPAWN_EVERY..=turn).step_by(SPIRAL_SPAWN_EVERY as usize) {
+            let p = spiral_cell(center, turn).unwrap();
+            if board.contains(p) {
+                if board.hazards[(p.x as usize, p.y as usize)] != 1 {
+                    return false;
+                }
+                covered += 1;
+            }
+        }
+        covered == stacks
+    })
+}
+
+/// Center of the spiral on the board of the spiral map, None for other maps and before the spiral starts.
+/// Search finds it once at the root, instead of on every spiral turn.
+pub fn spiral_center(board: &Board) -> Option<GridPoint> {
+    if board.settings.map != GameMap::Spiral {
+        return None;
+    }
+    find_spiral_center(board, board.turn)
+}
+
+fn spiral_spawn_range(side: CoordType) -> (CoordType, CoordType) {
+    let offset = (side as f32 * SPIRAL_SPAWN_AREA).floor() as CoordType;
+    (offset, side - 1 - offset)
+}
+
+fn is_in_spiral_spawn_area(p: GridPoint, width: CoordType, height: CoordType) -> bool {
+    let (x0, x1) = spiral_spawn_range(width);
+    let (y0, y1) = spiral_spawn_range(height);
+    x0 <= p.x && p.x <= x1 && y0 <= p.y && p.y <= y1
+}
+
+/// Hazards decay by one stack, then the trails left by tails are stacked as long as their snakes.
+/// Trails under alive heads are skipped, as in official rules.
+fn update_snail_trails(board: &mut Board, trails: &[VacatedTail]) {
+    for x in 0..board.width as usize {
+        for y in 0..board.height as usize {
+            board.hazards[(x, y)] = board.hazards[(x, y)].saturating_sub(1);
+        }
+    }
+
+    for &(p, len) in trails {
+        if board.snakes.iter().any(|snake| snake.is_alive() && snake.head() == p) {
+            continue;
+        }
+        let stacks = &mut board.hazards[(p.x as usize, p.y as usize)];
+        *stacks = stacks.saturating_add(len.min(u8::MAX as usize) as u8);
+    }
+}
+
+// Border of the rectangle `offset` cells inside the board, same as `drawRing`.
+fn add_ring(hazards: &mut Array2D<u8>, width: CoordType, height: CoordType, offset: CoordType) {
+    if offset < 1 || offset >= width - 1 || offset >= height - 1 {
+        return;
+    }
+    let (x0, x1) = (offset - 1, width - offset);
+    let (y0, y1) = (offset - 1, height - offset);
+    for x in x0..=x1 {
+        for y in y0..=y1 {
+            if x == x0 || x == x1 || y == y0 || y == y1 {
+                hazards[(x as usize, y as usize)] += 1;
+            }
+        }
+    }
+}
+
+// Every `shrink_every_n_turns` turns the pits get another layer, 4 layers stay for 3 cycles,
+// then the pits are drained.
+fn update_hazard_pits(board: &mut Board, turn: i32) {
+    let cycle = board.settings.shrink_every_n_turns;
+    if cycle <= 0 || turn % cycle != 0 {
+        return;
+    }
+    let layers = ((turn / cycle) % 7).min(HAZARD_PITS_MAX_LAYERS as i32) as u8;
+
+    for x in 0..board.width {
+        for y in 0..board.height {
+            let is_pit = x % 2 == 1 && y % 2 == 1 && !HAZARD_PIT_START_POSITIONS.contains(&Point {x, y});
+            board.hazards[(x as usize, y as usize)] = if is_pit { layers } else { 0 };
+        }
+    }
+}
+
+// Sinkhole appears in the center on turn 1 and grows by a ring every `shrink_every_n_turns` turns (10 by default).
+fn update_sinkholes(board: &mut Board, turn: i32) {
+    let start_turn = 1;
+    let spawn_every = if board.settings.shrink_every_n_turns > 0 { board.settings.shrink_every_n_turns } else { 10 };
+    let max_rings = match board.width {
+        7 => 3,
+        19 => 7,
+        _ => 5,
+    };
+    let center: GridPoint = Point {x: board.width / 2, y: board.height / 2};
+
+    if turn == start_turn {
+        board.hazards[(center.x as usize, center.y as usize)] += 1;
+        return;
+    }
+    if turn > spawn_every * max_rings || (turn - start_turn) % spawn_every != 0 {
+        return;
+    }
+
+    let offset = (turn - start_turn) / spawn_every;
+    if offset <= 0 || offset > max_rings {
+        return;
+    }
+    for x in center.x - offset..=center.x + offset {
+        for y in center.y - offset..=center.y + offset {
+            // Corners are skipped, so the sinkhole is rounded
+            let is_corner = (x - center.x).abs() == offset && (y - center.y).abs() == offset;
+            if !is_corner && board.contains(Point {x, y}) {
+                board.hazards[(x as usize, y as usize)] += 1;
+            }
+        }
+    }
+}
+
+// Official center is drawn from the seed of the game, so it's a chance event of the engine.
+// Afterwards it's recovered from the hazards.
+fn update_spiral(board: &mut Board, rng: &mut impl Rng) {
+    let center = if board.turn == SPIRAL_SPAWN_EVERY {
+        let (x0, x1) = spiral_spawn_range(board.width);
+        let (y0, y1) = spiral_spawn_range(board.height);
+        Point {x: rng.gen_range(x0..=x1), y: rng.gen_range(y0..=y1)}
+    } else if board.turn % SPIRAL_SPAWN_EVERY == 0 {
+        match find_spiral_center(board, board.turn - 1) {
+            Some(center) => center,
+            None => return,
+        }
+    } else {
+        return;
+    };
+    add_spiral_hazard(board, center);
+}
+
+// Every `shrink_every_n_turns` turns a random pool dries up. Official rules don't seed the choice,
+// so it's a chance event of the engine.
+fn update_healing_pools(board: &mut Board, rng: &mut impl Rng, turn: i32) {
+    let shrink_every = board.settings.shrink_every_n_turns;
+    if turn <= 0 || shrink_every <= 0 || turn % shrink_every != 0 {
+        return;
+    }
+
+    // Every stack is a hazard in the official list
+    let mut stacks = Vec::new();
+    for x in 0..board.width as usize {
+        for y in 0..board.height as usize {
+            for _ in 0..board.hazards[(x, y)] {
+                stacks.push((x, y));
+            }
+        }
+    }
+    if let Some(&p) = stacks.choose(rng) {
+        board.hazards[p] = 0;
+    }
+}
+
+// After turn 10 one food per turn appears in a passage, up to 2 food. Spots next to heads
+// and food are skipped, so two food never share a passage.
+fn spawn_castle_wall_food(board: &mut Board, rng: &mut impl Rng, turn: i32) {
+    if turn < 10 || board.foods.len() >= CASTLE_WALL_MAX_FOOD {
+        return;
+    }
+
+    let is_near = |p: GridPoint, q: GridPoint| (p.x - q.x).abs() + (p.y - q.y).abs() <= 1;
+    let mut spots = CASTLE_WALL_FOOD;
+    spots.shuffle(rng);
+    let spot = spots.into_iter().find(|&p| {
+        !board.objects.occupied.get(p.into())
+            && !board.snakes.iter().any(|snake| snake.is_alive() && is_near(snake.head(), p))
+            && !board.foods.iter().any(|&food| is_near(food, p))
+    });
+    if let Some(p) = spot {
+        board.put_food(p);
+    }
+}
+
+// With the food spawn chance one food appears at a random free spot. There's no minimum food.
+fn spawn_arcade_maze_food(board: &mut Board, rng: &mut impl Rng) {
+    // Same as `rand.Intn(100) > foodSpawnChance` in official rules
+    let chance = board.settings.food_spawn_chance;
+    if chance == 0 || rng.gen_range(0..100) > chance {
+        return;
+    }
+
+    let mut spots = ARCADE_MAZE_FOOD;
+    spots.shuffle(rng);
+    let spot = spots.into_iter().find(|&p| !board.objects.occupied.get(p.into()) && !board.foods.contains(&p));
+    if let Some(p) = spot {
+        board.put_food(p);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board_generator::generate_board_with_settings;
+    use crate::engine::{EngineSettings, Movement, advance_one_step_with_settings, food_spawner, safe_zone_shrinker, hazard_spawner, new_rng};
+    use crate::game::{Board, BoardError, GridPoint, Point, Snake, MAX_SNAKE_COUNT};
+    use crate::ruleset::RulesetKind;
+    use crate::settings::{GameMap, GameSettings};
+    use super::{initial_hazards, start_positions, spiral_cell, spiral_center, find_spiral_center, ARCADE_MAZE_FOOD, ARCADE_MAZE_HAZARDS, ARCADE_MAZE_LAST_START_POSITIONS, CASTLE_WALL_FOOD, HAZARD_PIT_START_POSITIONS, RIVERS_AND_BRIDGES_START_POSITIONS};
+
+    fn hazards_count(board: &Board) -> usize {
+        let mut count = 0;
+        for x in 0..board.width {
+            for y in 0..board.height {
+                count += board.hazard_stacks(Point {x, y}) as usize;
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_initial_hazards() {
+        let rng = &mut new_rng(Some(0));
+        let hazards = initial_hazards(rng, GameMap::InnerWall, 11, 11);
+        assert_eq!(hazards[(1, 1)], 1);
+        assert_eq!(hazards[(9, 5)], 1);
+        assert_eq!(hazards[(5, 5)], 0);
+        assert_eq!(hazards[(0, 0)], 0);
+
+        // Rings with offsets 2 and 4
+        let hazards = initial_hazards(rng, GameMap::Rings, 11, 11);
+        assert_eq!(hazards[(1, 5)], 1);
+        assert_eq!(hazards[(2, 5)], 0);
+        assert_eq!(hazards[(3, 5)], 1);
+        assert_eq!(hazards[(5, 5)], 0);
+
+        let hazards = initial_hazards(rng, GameMap::Columns, 7, 7);
+        assert_eq!(hazards[(1, 3)], 1);
+        assert_eq!(hazards[(2, 3)], 0);
+
+        let hazards = initial_hazards(rng, GameMap::HealingPools, 7, 7);
+        assert_eq!(hazards[(3, 3)], 1);
+    }
+
+    // Moves the first snake around cells (0, 0), (0, 1), (1, 1) and (1, 0).
+    fn circle_in_corner(board: &Board) -> [usize; MAX_SNAKE_COUNT] {
+        circle_around(board, Point {x: 0, y: 0})
+    }
+
+    // Moves the first snake around the square of 2x2 cells with the bottom left corner.
+    fn circle_around(board: &Board, corner: GridPoint) -> [usize; MAX_SNAKE_COUNT] {
+        let head = board.snakes[0].head();
+        let movement = match (head.x - corner.x, head.y - corner.y) {
+            (0, 0) => Movement::Up,
+            (0, 1) => Movement::Right,
+            (1, 1) => Movement::Down,
+            _ => Movement::Left,
+        };
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = movement as usize;
+        actions
+    }
+
+    // Plays a game with the map and collects the hazards count after each turn.
+    fn play(settings: GameSettings, turns: i32) -> Vec<usize> {
+        let rng = &mut new_rng(Some(0));
+        let snake = Snake {
+            health: 100,
+            body: [Point {x: 0, y: 1}, Point {x: 0, y: 0}, Point {x: 1, y: 0}].into(),
+        };
+        let hazards = initial_hazards(rng, settings.map, 11, 11);
+        let mut board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, None, None, Some(hazards), [snake].into_iter().collect());
+        let safe_zone = board.safe_zone;
+
+        let mut counts = Vec::new();
+        while board.turn < turns {
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::noop,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+            // Circles in the corner, away from the hazards
+            let actions = circle_in_corner(&board);
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+            assert!(board.snakes[0].is_alive());
+            assert_eq!(board.safe_zone, safe_zone);
+            assert_eq!(board.zobrist_hash, board.calculate_zobrist_hash());
+            counts.push(hazards_count(&board));
+        }
+        counts
+    }
+
+    #[test]
+    fn test_hazard_pits() {
+        let settings = GameSettings {shrink_every_n_turns: 3, map: GameMap::HazardPits, ..GameSettings::default()};
+        let counts = play(settings, 24);
+        // 21 pits get a layer on turns 4, 7, 10 and 13, then they are drained on turn 22
+        let expected: Vec<usize> = [0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4, 4, 0, 0, 0]
+            .into_iter()
+            .map(|layers| 21 * layers)
+            .collect();
+        assert_eq!(counts, expected);
+
+        // Snakes start at the corners, where no pit is dug
+        let rng = &mut new_rng(Some(0));
+        for snake_count in 1..=4 {
+            let board = generate_board_with_settings(rng, RulesetKind::Standard, settings, 11, 11, snake_count).unwrap();
+            for snake in &board.snakes {
+                assert!(HAZARD_PIT_START_POSITIONS.contains(&snake.head()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sinkholes() {
+        let settings = GameSettings {shrink_every_n_turns: 2, map: GameMap::Sinkholes, ..GameSettings::default()};
+        let counts = play(settings, 14);
+        // Rings of 3x3 - 4, 5x5 - 4, ... cells are stacked over the center, the 5th ring is never drawn
+        assert_eq!(counts, vec![0, 1, 1, 6, 6, 27, 27, 72, 72, 149, 149, 149, 149, 149]);
+    }
+
+    #[test]
+    fn test_healing_pools() {
+        let settings = GameSettings {shrink_every_n_turns: 3, hazard_damage_per_turn: -10, map: GameMap::HealingPools, ..GameSettings::default()};
+        let counts = play(settings, 9);
+        // Two pools, one dries up on turn 4 and the other one on turn 7
+        assert_eq!(counts, vec![2, 2, 2, 1, 1, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_snail_mode() {
+        let snake = Snake {
+            health: 100,
+            body: [Point {x: 2, y: 0}, Point {x: 1, y: 0}, Point {x: 0, y: 0}].into(),
+        };
+        let settings = GameSettings {map: GameMap::SnailMode, ..GameSettings::default()};
+        let mut board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, Some(vec![Point {x: 5, y: 5}]), None, None, [snake].into_iter().collect());
+
+        let mut actions = [0; MAX_SNAKE_COUNT];
+        actions[0] = Movement::Right as usize;
+        for _ in 0..3 {
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::noop,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng: &mut new_rng(Some(0)),
+            };
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+        }
+
+        // Trail of length 3 behind the snake decays by one each turn
+        let stacks: Vec<_> = (0..6).map(|x| board.hazard_stacks(Point {x, y: 0})).collect();
+        assert_eq!(stacks, vec![1, 2, 3, 0, 0, 0]);
+        assert_eq!(board.snakes[0].health, 97);
+        assert_eq!(board.zobrist_hash, board.calculate_zobrist_hash());
+    }
+
+    #[test]
+    fn test_castle_wall_food() {
+        let rng = &mut new_rng(Some(0));
+        let settings = GameSettings {hazard_damage_per_turn: 100, map: GameMap::CastleWall, ..GameSettings::default()};
+        let snake = Snake {
+            health: 100,
+            body: [Point {x: 1, y: 1}, Point {x: 1, y: 1}, Point {x: 1, y: 1}].into(),
+        };
+        let hazards = initial_hazards(rng, settings.map, 11, 11);
+        assert_eq!(hazards[(2, 3)], 1);
+        assert_eq!(hazards[(2, 4)], 2);
+        assert_eq!(hazards[(2, 5)], 0);
+        let mut board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, Some(vec![Point {x: 2, y: 5}]), None, Some(hazards), [snake].into_iter().collect());
+
+        while board.turn < 20 {
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::create_standard,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+            // Circles in the corner, inside the castle
+            let actions = circle_in_corner(&board);
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+            assert!(board.snakes[0].is_alive());
+            assert!(board.foods.iter().all(|food| CASTLE_WALL_FOOD.contains(food)));
+            // The first food appears after turn 10
+            let expected_foods = if board.turn <= 10 { 1 } else { 2 };
+            assert_eq!(board.foods.len(), expected_foods);
+        }
+    }
+
+    #[test]
+    fn test_wall_maps_setup() {
+        let rng = &mut new_rng(Some(0));
+
+        // Snakes are spread over the quadrants
+        let positions = start_positions(rng, GameMap::RiversAndBridges, 11, 11, 8).unwrap();
+        assert_eq!(positions.len(), 8);
+        for quadrant in RIVERS_AND_BRIDGES_START_POSITIONS {
+            assert!(quadrant.iter().all(|p| positions.contains(p)));
+        }
+        let positions = start_positions(rng, GameMap::RiversAndBridges, 11, 11, 4).unwrap();
+        for quadrant in RIVERS_AND_BRIDGES_START_POSITIONS {
+            assert_eq!(quadrant.iter().filter(|p| positions.contains(p)).count(), 1);
+        }
+        assert_eq!(start_positions(rng, GameMap::RiversAndBridges, 19, 19, 4), None);
+
+        for map in [GameMap::CastleWall, GameMap::RiversAndBridges] {
+            let settings = GameSettings {map, ..GameSettings::default()};
+            for snake_count in 1..=MAX_SNAKE_COUNT {
+                let board = generate_board_with_settings(rng, RulesetKind::Standard, settings, 11, 11, snake_count).unwrap();
+                assert!(board.foods.iter().all(|&food| !board.is_hazard(food)));
+                assert!(board.snakes.iter().all(|snake| !board.is_hazard(snake.head())));
+                // Castle starts without food
+                assert_eq!(board.foods.is_empty(), map == GameMap::CastleWall);
+            }
+
+            // Larger versions are separate maps
+            let error = generate_board_with_settings(rng, RulesetKind::Standard, settings, 19, 19, 4).unwrap_err();
+            assert_eq!(error, BoardError::UnsupportedMapSize {map, width: 19, height: 19});
+        }
+        let settings = GameSettings {map: GameMap::HazardPits, ..GameSettings::default()};
+        let error = generate_board_with_settings(rng, RulesetKind::Standard, settings, 11, 11, 5).unwrap_err();
+        assert_eq!(error, BoardError::TooManySnakesOnMap {map: GameMap::HazardPits, count: 5});
+    }
+
+    #[test]
+    fn test_arcade_maze_setup() {
+        let rng = &mut new_rng(Some(0));
+        assert_eq!(GameMap::from_api_name("arcade_maze"), GameMap::ArcadeMaze);
+
+        let settings = GameSettings {map: GameMap::ArcadeMaze, ..GameSettings::default()};
+        for snake_count in 1..=6 {
+            let board = generate_board_with_settings(rng, RulesetKind::Standard, settings, 19, 21, snake_count).unwrap();
+            assert_eq!(hazards_count(&board), ARCADE_MAZE_HAZARDS.len());
+            assert!(board.snakes.iter().all(|snake| !board.is_hazard(snake.head())));
+            // The fifth and the sixth snakes start in the middle
+            for (snake, p) in board.snakes.iter().skip(4).zip(ARCADE_MAZE_LAST_START_POSITIONS) {
+                assert_eq!(snake.head(), p);
+            }
+            assert_eq!(board.foods, vec![Point {x: 9, y: 11}]);
+        }
+
+        let settings = GameSettings {minimum_food: 0, ..settings};
+        let board = generate_board_with_settings(rng, RulesetKind::Standard, settings, 19, 21, 4).unwrap();
+        assert!(board.foods.is_empty());
+
+        let error = generate_board_with_settings(rng, RulesetKind::Standard, settings, 19, 21, 7).unwrap_err();
+        assert_eq!(error, BoardError::TooManySnakesOnMap {map: GameMap::ArcadeMaze, count: 7});
+        let error = generate_board_with_settings(rng, RulesetKind::Standard, settings, 19, 19, 4).unwrap_err();
+        assert_eq!(error, BoardError::UnsupportedMapSize {map: GameMap::ArcadeMaze, width: 19, height: 19});
+    }
+
+    #[test]
+    fn test_arcade_maze_food() {
+        let rng = &mut new_rng(Some(0));
+        let settings = GameSettings {food_spawn_chance: 50, hazard_damage_per_turn: 100, map: GameMap::ArcadeMaze, ..GameSettings::default()};
+        let snake = Snake {
+            health: 100,
+            body: [Point {x: 1, y: 7}, Point {x: 1, y: 7}, Point {x: 1, y: 7}].into(),
+        };
+        let hazards = initial_hazards(rng, settings.map, 19, 21);
+        let mut board = Board::new(RulesetKind::Solo, settings, 19, 21, 0, Some(Vec::new()), None, Some(hazards), [snake].into_iter().collect());
+
+        let mut foods_count = 0;
+        while board.turn < 30 {
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::create_standard,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+            // Circles in the free square at the left of the maze
+            let actions = circle_around(&board, Point {x: 1, y: 7});
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+            assert!(board.snakes[0].is_alive());
+            assert!(board.foods.iter().all(|food| ARCADE_MAZE_FOOD.contains(food)));
+            // At most one food per turn, it's never spawned on the snake
+            assert!(board.foods.len() <= foods_count + 1);
+            assert!(!board.foods.iter().any(|food| board.snakes[0].body.contains(food)));
+            foods_count = board.foods.len();
+        }
+        assert!(foods_count > 0);
+    }
+
+    #[test]
+    fn test_spiral_cell() {
+        let center = Point {x: 5, y: 5};
+        let cells: Vec<_> = (3..=30).step_by(3).map(|turn| spiral_cell(center, turn).unwrap()).collect();
+        let expected = [(5, 5), (5, 6), (6, 6), (6, 5), (6, 4), (5, 4), (4, 4), (4, 5), (4, 6), (4, 7)];
+        assert_eq!(cells, expected.map(|(x, y)| Point {x, y}));
+
+        assert_eq!(spiral_cell(center, 0), None);
+        assert_eq!(spiral_cell(center, 4), None);
+        // Last cell of the second ring is its top left corner
+        assert_eq!(spiral_cell(center, 3 * 25), Some(Point {x: 3, y: 7}));
+    }
+
+    #[test]
+    fn test_spiral() {
+        let settings = GameSettings {map: GameMap::Spiral, ..GameSettings::default()};
+        let counts = play(settings, 30);
+        let expected: Vec<usize> = (1..=30).map(|turn| turn / 3).collect();
+        assert_eq!(counts, expected);
+
+        // Spiral with the found center is the same as the one with the random center
+        let rng = &mut new_rng(Some(1));
+        let snake = Snake {
+            health: 100,
+            body: [Point {x: 0, y: 1}, Point {x: 0, y: 0}, Point {x: 1, y: 0}].into(),
+        };
+        let mut board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, None, None, None, [snake].into_iter().collect());
+        let mut boards = Vec::new();
+        while board.turn < 30 {
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::noop,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+            let actions = circle_in_corner(&board);
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+            boards.push(board.clone());
+        }
+
+        let center = find_spiral_center(&board, board.turn).unwrap();
+        assert_eq!(board.hazards[(center.x as usize, center.y as usize)], 1);
+        // Search takes the center from the root board
+        assert_eq!(spiral_center(&boards[10]), Some(center));
+        assert_eq!(spiral_center(&boards[0]), None);
+        let mut other_map = board.clone();
+        other_map.settings.map = GameMap::Rings;
+        assert_eq!(spiral_center(&other_map), None);
+
+        let first = &boards[0];
+        let mut board = first.clone();
+        for expected in &boards[1..] {
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::noop,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::spiral(center),
+                rng,
+            };
+            let actions = circle_in_corner(&board);
+            advance_one_step_with_settings(&mut board, &mut settings, actions);
+            assert_eq!(board.hazards, expected.hazards);
+            assert_eq!(board.zobrist_hash, expected.zobrist_hash);
+        }
+    }
+}
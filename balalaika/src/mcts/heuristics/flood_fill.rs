@@ -1,129 +1,98 @@
+use std::cmp::Reverse;
+
+use arrayvec::ArrayVec;
+
 use crate::{
+    bitboard::Bitboard,
     game::{
-        Board, MAX_SNAKE_COUNT, WIDTH, HEIGHT, SIZE, GridPoint
+        Board, MAX_SNAKE_COUNT
     },
-    mcts::utils::movement_positions_wrapped, features::collector::Rewards
+    features::collector::Rewards,
+    ruleset::Ruleset,
 };
 
-#[derive(Clone)]
-struct ContendersInfo {
-    several_largest: bool,
-    winner_index: usize,
-    largest_size: usize,
-    visited: bool,
-    body_part_empty_at: usize,
-}
-
-impl Default for ContendersInfo {
-    fn default() -> Self {
-        ContendersInfo {
-            several_largest: false,
-            winner_index: usize::MAX,
-            largest_size: 0,
-            visited: false,
-            body_part_empty_at: 0,
-        }
-    }
-}
-
+/// Share of the board each snake reaches first. All snakes flood simultaneously,
+/// a cell reached by several snakes on the same turn goes to the longest one, or nobody on a tie.
 pub fn flood_fill(board: &Board) -> Rewards {
-    let mut sizes = [0; MAX_SNAKE_COUNT];
-
-    let mut flood_fronts: [Vec<GridPoint>; MAX_SNAKE_COUNT] = Default::default();
-    // let mut seized_points: [Vec<Point>; MAX_SNAKE_COUNT] = Default::default();
     let mut seized_points = [0f32; MAX_SNAKE_COUNT];
 
-    let mut contenders_at_point: [[ContendersInfo; HEIGHT as usize]; WIDTH as usize] = Default::default();
+    let cells = board.cells();
+    let wrapped = board.ruleset.is_wrapped();
+    // Constrictor snakes grow every turn and keep max health, so tails never move and nobody starves.
+    let is_constrictor = board.ruleset.is_constrictor();
 
+    let mut flood_fronts = [Bitboard::EMPTY; MAX_SNAKE_COUNT];
+    let mut blocked = Bitboard::EMPTY;
+    let mut alive_snakes = ArrayVec::<usize, MAX_SNAKE_COUNT>::new();
     for (i, snake) in board.snakes.iter().enumerate() {
-        if !snake.is_alive() {
-            continue;
+        if snake.is_alive() {
+            flood_fronts[i] = Bitboard::from(snake.head());
+            blocked |= board.snake_bitboards[i];
+            alive_snakes.push(i);
         }
-        for (empty_at, body_part) in snake.body.iter().rev().enumerate() {
-            contenders_at_point[body_part.x as usize][body_part.y as usize].body_part_empty_at = empty_at + 1;
-        }
-        
-        flood_fronts[i].reserve_exact(SIZE);
-        sizes[i] = snake.body.len();
-        flood_fronts[i].push(snake.head());
     }
+    // Longest first, contests are resolved between snakes of the same length.
+    alive_snakes.sort_by_key(|&i| Reverse(board.snakes[i].body.len()));
+
+    let hazard_levels = HazardLevels::new(board);
 
+    let mut visited = Bitboard::EMPTY;
     let mut turn = 1;
-    let mut current_points = Vec::new();
 
     loop {
-        for (i, flood_front) in flood_fronts.iter_mut().enumerate() {
-            while let Some(point) = flood_front.pop() {
-                for movement_position in movement_positions_wrapped(point) {
-                    let contenders_info = &mut contenders_at_point[movement_position.x as usize][movement_position.y as usize];
-
-                    if contenders_info.visited {
-                        // This point already contended
-                        continue;
-                    }
-
-                    if turn < contenders_info.body_part_empty_at {
-                        // This body part is not ready for contest because it's not empty yet
-                        continue;
-                    }
-
-                    if !current_points.contains(&movement_position) {
-                        current_points.push(movement_position);
-                    }
-                    
-                    let size = sizes[i];
-                    if contenders_info.largest_size < size {
-                        contenders_info.largest_size = size;
-                        contenders_info.winner_index = i;
-                        contenders_info.several_largest = false;
-                    } else if contenders_info.largest_size == size && contenders_info.winner_index != i {
-                        contenders_info.several_largest = true;
+        if !is_constrictor {
+            // Body parts which are empty on this turn. Stacked parts leave with the last one.
+            for &i in &alive_snakes {
+                let body = &board.snakes[i].body;
+                if turn <= body.len() {
+                    let part_i = body.len() - turn;
+                    if part_i == 0 || body[part_i - 1] != body[part_i] {
+                        blocked.unset(body[part_i].into());
                     }
                 }
             }
         }
+        let available = cells & !visited & !blocked;
 
+        // Contested by longer snakes or already seized on this turn
+        let mut contested = Bitboard::EMPTY;
         let mut all_fronts_empty = true;
 
-        for &point in &current_points {
-            let contenders_info = &mut contenders_at_point[point.x as usize][point.y as usize];
-            contenders_info.visited = true;
-
-            if !contenders_info.several_largest {
-                let flood_front = &mut flood_fronts[contenders_info.winner_index];
-                seized_points[contenders_info.winner_index] += 1.0;
-                
-                flood_front.push(point);
-                all_fronts_empty = false;
-                // let seized = &mut seized_points[contenders_info.winner_index];
-                // seized.push(point);
+        for same_size in alive_snakes.chunk_by(|&i, &j| board.snakes[i].body.len() == board.snakes[j].body.len()) {
+            let mut reached = ArrayVec::<Bitboard, MAX_SNAKE_COUNT>::new();
+            let mut reached_by_any = Bitboard::EMPTY;
+            let mut tie = Bitboard::EMPTY;
+
+            for &i in same_size {
+                let starvation = if is_constrictor { 1 } else { turn as i32 };
+                let deadly = hazard_levels.deadly(board.snakes[i].health - starvation);
+                let cells = flood_fronts[i].neighbours(board.width, board.height, wrapped) & available & !contested & !deadly;
+
+                tie |= reached_by_any & cells;
+                reached_by_any |= cells;
+                reached.push(cells);
             }
+
+            for (&i, cells) in same_size.iter().zip(reached) {
+                let seized = cells & !tie;
+                seized_points[i] += seized.count() as f32;
+                all_fronts_empty &= seized.is_empty();
+                flood_fronts[i] = seized;
+            }
+
+            contested |= reached_by_any;
         }
 
+        visited |= contested;
+
         if all_fronts_empty {
             break;
         }
 
-        current_points.clear();
-
         turn += 1;
     }
 
-    // info!("{:?}", Instant::now() - start);
-
-    // for y in (0..HEIGHT).rev() {
-    //     let v: Vec<_> = (0..WIDTH).map(|x| {
-    //         let c = &contenders_at_point[x][y];
-    //         if c.visited {
-    //             c.winner_index
-    //         } else {
-    //             123123
-    //         }
-    //     }).collect();
-    //     info!("{:?}", v);
-    // }
-
-    let size = SIZE as f32;
+    let size = board.size() as f32;
 
     for i in 0..MAX_SNAKE_COUNT {
         seized_points[i] /= size;
@@ -132,10 +101,65 @@ pub fn flood_fill(board: &Board) -> Rewards {
     seized_points
 }
 
+// Up to 9 stacks are told apart, more only matter with lower than the official damage.
+const MAX_STACKED_LEVELS: usize = 8;
+
+/// Hazard cells by number of stacks, to find where a snake can't survive.
+/// Food cancels hazard damage, so cells with food are never deadly.
+pub struct HazardLevels {
+    damage: i32,
+    // Cells with at least one stack
+    any: Bitboard,
+    // Cells with at least `k + 2` stacks at index k. Empty unless hazards are stacked.
+    stacked: ArrayVec<Bitboard, MAX_STACKED_LEVELS>,
+}
+
+impl HazardLevels {
+    pub fn new(board: &Board) -> HazardLevels {
+        let hazards = board.hazard_bitboard & !board.objects.food;
+        let mut stacked = ArrayVec::new();
+        for p in hazards.iter() {
+            let stacks = (board.hazard_stacks(p.into()) as usize).min(MAX_STACKED_LEVELS + 1);
+            while stacked.len() < stacks - 1 {
+                stacked.push(Bitboard::EMPTY);
+            }
+            for level in &mut stacked[..stacks - 1] {
+                level.set(p);
+            }
+        }
+
+        HazardLevels {
+            damage: board.settings.hazard_damage_per_turn,
+            any: hazards,
+            stacked,
+        }
+    }
+
+    /// Hazard cells which take all of the health. Each stack deals the damage separately.
+    pub fn deadly(&self, health: i32) -> Bitboard {
+        // Healing hazards never kill, they even save starving snakes
+        if self.damage < 0 {
+            return Bitboard::EMPTY;
+        }
+        if health <= 0 {
+            return self.any;
+        }
+        if self.damage <= 0 {
+            return Bitboard::EMPTY;
+        }
+        let stacks = ((health + self.damage - 1) / self.damage) as usize;
+        if stacks == 1 {
+            self.any
+        } else {
+            self.stacked.get(stacks - 2).copied().unwrap_or(Bitboard::EMPTY)
+        }
+    }
+}
+
 pub fn flavored_flood_fill(board: &Board) -> Rewards {
     let len_sum: f32 = board.snakes.iter().map(|snake| snake.body.len() as f32).sum();
 
-    let mut rewards = flood_fill(&board);
+    let mut rewards = flood_fill(board);
 
     for i in 0..board.snakes.len() {
         rewards[i] *= board.snakes[i].body.len() as f32 / len_sum;
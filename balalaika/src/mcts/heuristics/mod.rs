@@ -1 +1,2 @@
-pub mod flood_fill;
\ No newline at end of file
+pub mod flood_fill;
+pub mod survival;
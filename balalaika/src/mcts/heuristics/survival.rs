@@ -0,0 +1,18 @@
+use crate::{
+    game::{Board, MAX_SNAKE_COUNT},
+    features::collector::Rewards,
+};
+
+/// Solo reward: share of the rollout turns the snake has survived.
+/// `board` is the last board of the rollout started at `start_turn`.
+pub fn survival(board: &Board, start_turn: i32, rollout_cutoff: i32) -> Rewards {
+    let mut rewards = [0.0; MAX_SNAKE_COUNT];
+
+    for (i, snake) in board.snakes.iter().enumerate() {
+        // Dead snake hasn't survived the last turn of the rollout.
+        let survived_turns = (board.turn - start_turn - !snake.is_alive() as i32).max(0);
+        rewards[i] = survived_turns as f32 / (rollout_cutoff + 1) as f32;
+    }
+
+    rewards
+}
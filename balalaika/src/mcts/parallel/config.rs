@@ -1,3 +1,5 @@
+use std::env;
+
 use num_cpus::get as num_cpus;
 
 use crate::mcts::utils::parse_env;
@@ -9,6 +11,10 @@ pub struct ParallelMCTSConfig {
     pub draw_reward: f32,
     pub workers: usize,
     pub max_select_depth: usize,
+    // Seeds workers. Search is still not reproducible with several workers, they share the tree.
+    pub seed: Option<u64>,
+    // Rollouts average the rewards over the sides of their first shrink instead of sampling one.
+    pub shrink_expectation: bool,
 }
 
 impl ParallelMCTSConfig {
@@ -19,6 +25,8 @@ impl ParallelMCTSConfig {
             draw_reward:            parse_env("MCTS_DRAW_REWARD").unwrap_or(0.01),
             workers:                parse_env("MCTS_WORKERS").unwrap_or(num_cpus()),
             max_select_depth:       parse_env("MCTS_SELECT_DEPTH").unwrap_or(50),
+            seed:                   parse_env("MCTS_SEED"),
+            shrink_expectation:     env::var("MCTS_SHRINK_EXPECTATION").is_ok(),
         };
 
         config
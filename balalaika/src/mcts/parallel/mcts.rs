@@ -5,13 +5,17 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
+use rand::Rng;
 use spin::mutex::Mutex;
 
 use crate::api::objects::Movement;
-use crate::engine::{EngineSettings, advance_one_step_with_settings, food_spawner, safe_zone_shrinker};
-use crate::features::collector::Rewards;
-use crate::game::{Board, MAX_SNAKE_COUNT};
+use crate::engine::{EngineSettings, GameRng, MOVEMENTS, StepJournal, StepUndo, advance_one_step_undoable, undo_step, food_spawner, safe_zone_shrinker, hazard_spawner, new_rng};
+use crate::features::collector::{Rewards, get_placement_rewards};
+use crate::game::{Board, GridPoint, MAX_SNAKE_COUNT};
+use crate::maps;
 use crate::mcts::heuristics::flood_fill::flavored_flood_fill;
+use crate::mcts::heuristics::survival::survival;
+use crate::ruleset::Ruleset;
 use crate::mcts::search::Search;
 use crate::zobrist::ZobristHasher;
 use crate::mcts::utils::{get_masks, get_random_actions_from_masks};
@@ -50,6 +54,8 @@ pub struct ParallelMCTS {
     pub config: Arc<ParallelMCTSConfig>,
     nodes: Nodes,
     max_depth_reached: Arc<AtomicUsize>,
+    // Seeds workers of each search.
+    rng: GameRng,
 
     // selection_time: Duration,
     // expansion_time: Duration,
@@ -60,9 +66,10 @@ impl Search for ParallelMCTS {
     fn search(&mut self, board: &Board, iterations_count: usize, verbose: bool) {
         let mut join_handles = Vec::with_capacity(self.config.workers);
         let iterations_per_worker = iterations_count / self.config.workers;
+        let spiral_center = maps::spiral_center(board);
         
         for i in 0..self.config.workers {
-            let mut worker = self.create_worker(i);
+            let mut worker = self.create_worker(i, spiral_center);
             let board_clone = board.clone();
 
             let join_handle = thread::spawn(move || {
@@ -85,9 +92,10 @@ impl Search for ParallelMCTS {
     fn search_with_time(&mut self, board: &Board, target_duration: Duration, verbose: bool) -> usize {
         let time_start = Instant::now();
         
+        let spiral_center = maps::spiral_center(board);
         let mut join_handles = Vec::with_capacity(self.config.workers);
         for i in 0..self.config.workers {
-            let mut worker = self.create_worker(i);
+            let mut worker = self.create_worker(i, spiral_center);
             let board_clone = board.clone();
 
             let join_handle = thread::spawn(move || {
@@ -111,10 +119,10 @@ impl Search for ParallelMCTS {
         iterations
     }
 
-    fn get_final_movement(&self, board: &Board, agent_index: usize, _verbose: bool) -> Movement {
+    fn get_final_movement(&self, board: &Board, snake_index: usize, _verbose: bool) -> Movement {
         let node_ref = self.nodes.get(&board.zobrist_hash.get_value()).unwrap();
         let node = node_ref.lock();
-        let agent = &node.agents[agent_index];
+        let agent = node.agents.iter().find(|agent| agent.id == snake_index).expect("Snake is not alive");
         
         agent.strategy.get_final_movement()
     }
@@ -130,6 +138,7 @@ impl ParallelMCTS {
             config: Arc::new(config),
             nodes,
             max_depth_reached: Arc::new(AtomicUsize::new(0)),
+            rng: new_rng(config.seed),
         }
     }
 
@@ -155,12 +164,14 @@ impl ParallelMCTS {
     }
 
     
-    fn create_worker(&self, id: usize) -> ParallelMCTSWorker {
+    fn create_worker(&mut self, id: usize, spiral_center: Option<GridPoint>) -> ParallelMCTSWorker {
         ParallelMCTSWorker::new(
             id,
             self.config.clone(),
             self.nodes.clone(),
             self.max_depth_reached.clone(),
+            new_rng(Some(self.rng.gen())),
+            spiral_center,
         )
     }
 }
@@ -171,21 +182,29 @@ struct ParallelMCTSWorker {
     nodes: Nodes,
     iterations: usize,
     max_depth_reached: Arc<AtomicUsize>,
+    rng: GameRng,
+    // Found once per search from the root board
+    spiral_center: Option<GridPoint>,
+    // Shared by all steps of a rollout, so they don't allocate
+    journal: StepJournal,
 }
 
 
 impl ParallelMCTSWorker {
-    pub fn new(id: usize, config: Arc<ParallelMCTSConfig>, nodes: Nodes, max_depth_reached: Arc<AtomicUsize>) -> ParallelMCTSWorker {
+    pub fn new(id: usize, config: Arc<ParallelMCTSConfig>, nodes: Nodes, max_depth_reached: Arc<AtomicUsize>, rng: GameRng, spiral_center: Option<GridPoint>) -> ParallelMCTSWorker {
         ParallelMCTSWorker {
             id,
             nodes,
             config,
             max_depth_reached,
+            rng,
+            spiral_center,
+            journal: StepJournal::default(),
             iterations: 0,
         }
     }
 
-    fn search_with_time(&mut self, board: Board, duration: Duration) {
+    fn search_with_time(&mut self, mut board: Board, duration: Duration) {
         if self.nodes.len() == 0 {
             let masks = get_masks(&board);
             let node_key = board.zobrist_hash.get_value();
@@ -197,12 +216,12 @@ impl ParallelMCTSWorker {
         let time_end = time_start + duration;
 
         while Instant::now() < time_end {
-            self.rollout(&board);
+            self.rollout(&mut board);
             self.iterations += 1;
         }
     }
 
-    pub fn search(&mut self, board: Board, iterations_count: usize) {
+    pub fn search(&mut self, mut board: Board, iterations_count: usize) {
         if self.nodes.len() == 0 {
             let masks = get_masks(&board);
             let node_key = board.zobrist_hash.get_value();
@@ -212,16 +231,17 @@ impl ParallelMCTSWorker {
 
         for _i in 0..iterations_count {
             // info!("iteration {}", i);
-            self.rollout(&board);
+            self.rollout(&mut board);
             self.iterations += 1;
         }
     }
 
-    fn rollout(&mut self, board: &Board) {
+    // Walks down and back up the same board, so it's left unchanged.
+    fn rollout(&mut self, board: &mut Board) {
         // let start = Instant::now();
-        let mut board = board.clone();
+        let mut undos = Vec::new();
 
-        let path = self.selection(&mut board);
+        let path = self.selection(board, &mut undos);
         let depth = path.len();
         
         self.max_depth_reached.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |max_depth| {
@@ -232,18 +252,22 @@ impl ParallelMCTSWorker {
             }
         });
 
-        let masks = get_masks(&board);
+        let masks = get_masks(board);
         let node_key = board.zobrist_hash.get_value();
 
         if !board.is_terminal() {
-            self.expansion(&board, masks, node_key);
+            self.expansion(board, masks, node_key);
         }
 
-        let rewards = self.simulation(&board);
+        let rewards = self.simulation(board);
         self.backpropagate(path, rewards);
+
+        while let Some(undo) = undos.pop() {
+            undo_step(board, undo, &mut self.journal);
+        }
     }
 
-    fn selection(&self, board: &mut Board) -> Vec<(u64, [usize; MAX_SNAKE_COUNT])> {
+    fn selection(&mut self, board: &mut Board, undos: &mut Vec<StepUndo>) -> Vec<(u64, [usize; MAX_SNAKE_COUNT])> {
         // let start = Instant::now();
 
         let mut path = Vec::new();
@@ -251,6 +275,8 @@ impl ParallelMCTSWorker {
         let mut engine_settings = EngineSettings {
             food_spawner: &mut food_spawner::create_standard,
             safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+            hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+            rng: &mut self.rng,
         };
 
         let iteration = self.iterations;
@@ -292,11 +318,13 @@ impl ParallelMCTSWorker {
                 }
             }
             
-            advance_one_step_with_settings(
+            let (_, undo) = advance_one_step_undoable(
                 board,
                 &mut engine_settings,
                 joint_action,
+                &mut self.journal,
             );
+            undos.push(undo);
             
             path.push((node_key, joint_action));
         }
@@ -320,34 +348,90 @@ impl ParallelMCTSWorker {
         self.nodes.insert(node_key, Mutex::new(node));
     }
 
-    fn simulation(&self, board: &Board) -> Rewards {
-        let mut board = board.clone();
-        let rollout_cutoff = self.config.rollout_cutoff;
-
-        let random = &mut rand::thread_rng();
+    fn simulation(&mut self, board: &mut Board) -> Rewards {
+        let start_turn = board.turn;
+        self.random_rollout(board, start_turn, self.config.shrink_expectation)
+    }
 
-        let mut engine_settings = EngineSettings {
-            food_spawner: &mut food_spawner::create_standard,
-            safe_zone_shrinker: &mut safe_zone_shrinker::standard,
-        };
+    // With `expect_shrink` the first shrink of the rollout is a chance event: the rollout goes on
+    // from every side with the same actions, and their rewards are averaged. Later shrinks are
+    // sampled, so it costs at most as much as four rollouts.
+    fn random_rollout(&mut self, board: &mut Board, start_turn: i32, expect_shrink: bool) -> Rewards {
+        let mut undos = Vec::new();
+        let end_turn = start_turn + self.config.rollout_cutoff;
 
-        let end_turn = board.turn + rollout_cutoff;
+        let mut rewards = None;
         while board.turn <= end_turn && !board.is_terminal() {
-            let actions = get_random_actions_from_masks(random, &board);
-            
-            advance_one_step_with_settings(
-                &mut board,
-                &mut engine_settings,
+            let actions = get_random_actions_from_masks(&mut self.rng, board);
+
+            if expect_shrink && safe_zone_shrinker::is_shrink_turn(board, board.turn + 1) {
+                rewards = Some(self.expected_shrink(board, start_turn, actions));
+                break;
+            }
+
+            let (_, undo) = advance_one_step_undoable(
+                board,
+                &mut EngineSettings {
+                    food_spawner: &mut food_spawner::create_standard,
+                    safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                    hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+                    rng: &mut self.rng,
+                },
+                actions,
+                &mut self.journal,
+            );
+            undos.push(undo);
+        }
+
+        let rewards = rewards.unwrap_or_else(|| self.get_rewards(board, start_turn));
+        // info!("Started at {} turn and rolled out with {} turns and rewards {:?}", start_turn, board.turn - start_turn, rewards);
+
+        while let Some(undo) = undos.pop() {
+            undo_step(board, undo, &mut self.journal);
+        }
+
+        rewards
+    }
+
+    // Average rewards of the rollouts after the shrink to every side.
+    fn expected_shrink(&mut self, board: &mut Board, start_turn: i32, actions: [usize; MAX_SNAKE_COUNT]) -> Rewards {
+        let mut rewards = [0.0; MAX_SNAKE_COUNT];
+        for side in MOVEMENTS {
+            let (_, undo) = advance_one_step_undoable(
+                board,
+                &mut EngineSettings {
+                    food_spawner: &mut food_spawner::create_standard,
+                    safe_zone_shrinker: &mut safe_zone_shrinker::fixed(side),
+                    hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+                    rng: &mut self.rng,
+                },
                 actions,
+                &mut self.journal,
             );
+            let side_rewards = self.random_rollout(board, start_turn, false);
+            undo_step(board, undo, &mut self.journal);
+
+            for (reward, side_reward) in rewards.iter_mut().zip(side_rewards) {
+                *reward += side_reward / MOVEMENTS.len() as f32;
+            }
         }
+        rewards
+    }
+
+    fn get_rewards(&self, board: &Board, start_turn: i32) -> Rewards {
+        if board.ruleset.is_solo() {
+            return survival(board, start_turn, self.config.rollout_cutoff);
+        }
+
         if board.snakes.iter().all(|snake| !snake.is_alive()) {
             return [self.config.draw_reward; MAX_SNAKE_COUNT];
         }
 
-        let rewards = flavored_flood_fill(&board);
-        // info!("Started at {} turn and rolled out with {} turns and rewards {:?}", start_turn, board.turn - start_turn, rewards);
-        rewards
+        if board.is_terminal() {
+            return get_placement_rewards(board);
+        }
+
+        flavored_flood_fill(board)
     }
 
     fn backpropagate(&self, path: Vec<(u64, [usize; MAX_SNAKE_COUNT])>, rewards: Rewards) {
@@ -7,7 +7,9 @@ pub trait Search {
  
     fn search_with_time(&mut self, board: &Board, duration: Duration, verbose: bool) -> usize;
 
-    fn get_final_movement(&self, board: &Board, agent_index: usize, verbose: bool) -> Movement;
+    /// Movement of the snake `board.snakes[snake_index]`, which must be alive.
+    /// Indices don't shift when other snakes are eliminated.
+    fn get_final_movement(&self, board: &Board, snake_index: usize, verbose: bool) -> Movement;
 
     fn shutdown(&self);
 }
@@ -6,13 +6,16 @@ use std::hash::BuildHasherDefault;
 use std::time::{Duration, Instant};
 
 use crate::api::objects::Movement;
-use crate::engine::{EngineSettings, advance_one_step_with_settings, food_spawner, safe_zone_shrinker};
-use crate::features::collector::Rewards;
-use crate::game::{Board, MAX_SNAKE_COUNT};
+use crate::engine::{EngineSettings, GameRng, MOVEMENTS, StepJournal, StepUndo, advance_one_step_undoable, undo_step, food_spawner, safe_zone_shrinker, hazard_spawner, new_rng};
+use crate::features::collector::{Rewards, get_placement_rewards};
+use crate::game::{Board, GridPoint, MAX_SNAKE_COUNT};
+use crate::maps;
 use crate::mcts::search::Search;
 use crate::zobrist::ZobristHasher;
 use crate::mcts::utils::{get_masks, get_random_actions_from_masks};
 use crate::mcts::heuristics::flood_fill::flavored_flood_fill;
+use crate::mcts::heuristics::survival::survival;
+use crate::ruleset::Ruleset;
 
 use super::config::SequentialMCTSConfig;
 
@@ -42,13 +45,20 @@ impl Node {
 pub struct SequentialMCTS {
     config: SequentialMCTSConfig,
     nodes: HashMap<u64, RefCell<Node>, BuildHasherDefault<ZobristHasher>>,
+    rng: RefCell<GameRng>,
+    // Found once per search from the root board
+    spiral_center: Option<GridPoint>,
+    // Shared by all steps of a rollout, so they don't allocate
+    journal: RefCell<StepJournal>,
 }
 
 impl Search for SequentialMCTS {
     fn search(&mut self, board: &Board, iterations_count: usize, _verbose: bool) {
+        self.spiral_center = maps::spiral_center(board);
+        let mut board = board.clone();
         for _i in 0..iterations_count {
             // info!("iteration {}", i);
-            self.rollout(board);
+            self.rollout(&mut board);
         }
     }
 
@@ -56,9 +66,11 @@ impl Search for SequentialMCTS {
         let time_start = Instant::now();
         let time_end = time_start + target_duration;
 
+        self.spiral_center = maps::spiral_center(board);
+        let mut search_board = board.clone();
         let mut i = 0;
         while Instant::now() < time_end {
-            self.rollout(board);
+            self.rollout(&mut search_board);
             i += 1;
         }
 
@@ -71,10 +83,10 @@ impl Search for SequentialMCTS {
         i
     }
 
-    fn get_final_movement(&self, board: &Board, agent_index: usize, verbose: bool) -> Movement {
+    fn get_final_movement(&self, board: &Board, snake_index: usize, verbose: bool) -> Movement {
         let node = self.nodes[&board.zobrist_hash.get_value()].borrow();
-        let strategy = &node.agents[agent_index].strategy;
-        strategy.get_final_movement()
+        let agent = node.agents.iter().find(|agent| agent.id == snake_index).expect("Snake is not alive");
+        agent.strategy.get_final_movement()
     }
 
     fn shutdown(&self) {}
@@ -84,6 +96,9 @@ impl SequentialMCTS {
     pub fn new(config: SequentialMCTSConfig) -> SequentialMCTS {
         SequentialMCTS {
             nodes: HashMap::with_capacity_and_hasher(config.table_capacity, BuildHasherDefault::<ZobristHasher>::default()),
+            rng: RefCell::new(new_rng(config.seed)),
+            spiral_center: None,
+            journal: RefCell::new(StepJournal::default()),
             config,
         }
     }
@@ -98,20 +113,25 @@ impl SequentialMCTS {
         }
     }
 
-    fn rollout(&mut self, board: &Board) {
-        let mut board = board.clone();
-        let path = self.selection(&mut board);
+    // Walks down and back up the same board, so it's left unchanged.
+    fn rollout(&mut self, board: &mut Board) {
+        let mut undos = Vec::new();
+        let path = self.selection(board, &mut undos);
 
-        let masks = get_masks(&board);
+        let masks = get_masks(board);
 
-        let rewards = self.simulation(&board);
+        let rewards = self.simulation(board);
         self.backpropagate(path, rewards);
         if !board.is_terminal() {
-            self.expansion(&board, masks);
+            self.expansion(board, masks);
+        }
+
+        while let Some(undo) = undos.pop() {
+            undo_step(board, undo, &mut self.journal.borrow_mut());
         }
     }
 
-    fn selection(&self, board: &mut Board) -> Vec<(RefMut<Node>, [usize; MAX_SNAKE_COUNT])> {
+    fn selection(&self, board: &mut Board, undos: &mut Vec<StepUndo>) -> Vec<(RefMut<Node>, [usize; MAX_SNAKE_COUNT])> {
         // let start = Instant::now();
 
         let mut path = Vec::new();
@@ -119,6 +139,8 @@ impl SequentialMCTS {
         let mut engine_settings = EngineSettings {
             food_spawner: &mut food_spawner::create_standard,
             safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+            hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+            rng: &mut self.rng.borrow_mut(),
         };
 
         while path.len() < self.config.max_select_depth {
@@ -147,11 +169,13 @@ impl SequentialMCTS {
                 alive_i += 1;
             }
 
-            advance_one_step_with_settings(
+            let (_, undo) = advance_one_step_undoable(
                 board,
                 &mut engine_settings,
                 joint_action,
+                &mut self.journal.borrow_mut(),
             );
+            undos.push(undo);
 
             path.push((node, joint_action));
         }
@@ -174,35 +198,90 @@ impl SequentialMCTS {
         self.nodes.insert(board.zobrist_hash.get_value(), RefCell::new(node));
     }
 
-    fn simulation(&self, board: &Board) -> Rewards {
-        let mut board = board.clone();
-        let rollout_cutoff = self.config.rollout_cutoff;
-
-        let random = &mut rand::thread_rng();
+    fn simulation(&self, board: &mut Board) -> Rewards {
+        let start_turn = board.turn;
+        self.random_rollout(board, start_turn, self.config.shrink_expectation)
+    }
 
-        let mut engine_settings = EngineSettings {
-            food_spawner: &mut food_spawner::create_standard,
-            safe_zone_shrinker: &mut safe_zone_shrinker::standard,
-        };
+    // With `expect_shrink` the first shrink of the rollout is a chance event: the rollout goes on
+    // from every side with the same actions, and their rewards are averaged. Later shrinks are
+    // sampled, so it costs at most as much as four rollouts.
+    fn random_rollout(&self, board: &mut Board, start_turn: i32, expect_shrink: bool) -> Rewards {
+        let mut undos = Vec::new();
+        let end_turn = start_turn + self.config.rollout_cutoff;
 
-        let end_turn = board.turn + rollout_cutoff;
+        let mut rewards = None;
         while board.turn <= end_turn && !board.is_terminal() {
-            let actions = get_random_actions_from_masks(random, &board);
+            let actions = get_random_actions_from_masks(&mut *self.rng.borrow_mut(), board);
 
-            advance_one_step_with_settings(
-                &mut board,
-                &mut engine_settings,
+            if expect_shrink && safe_zone_shrinker::is_shrink_turn(board, board.turn + 1) {
+                rewards = Some(self.expected_shrink(board, start_turn, actions));
+                break;
+            }
+
+            let (_, undo) = advance_one_step_undoable(
+                board,
+                &mut EngineSettings {
+                    food_spawner: &mut food_spawner::create_standard,
+                    safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                    hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+                    rng: &mut self.rng.borrow_mut(),
+                },
+                actions,
+                &mut self.journal.borrow_mut(),
+            );
+            undos.push(undo);
+        }
+
+        let rewards = rewards.unwrap_or_else(|| self.get_rewards(board, start_turn));
+        // info!("Started at {} turn and rolled out with {} turns and rewards {:?}", start_turn, board.turn - start_turn, rewards);
+
+        while let Some(undo) = undos.pop() {
+            undo_step(board, undo, &mut self.journal.borrow_mut());
+        }
+
+        rewards
+    }
+
+    // Average rewards of the rollouts after the shrink to every side.
+    fn expected_shrink(&self, board: &mut Board, start_turn: i32, actions: [usize; MAX_SNAKE_COUNT]) -> Rewards {
+        let mut rewards = [0.0; MAX_SNAKE_COUNT];
+        for side in MOVEMENTS {
+            let (_, undo) = advance_one_step_undoable(
+                board,
+                &mut EngineSettings {
+                    food_spawner: &mut food_spawner::create_standard,
+                    safe_zone_shrinker: &mut safe_zone_shrinker::fixed(side),
+                    hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+                    rng: &mut self.rng.borrow_mut(),
+                },
                 actions,
+                &mut self.journal.borrow_mut(),
             );
+            let side_rewards = self.random_rollout(board, start_turn, false);
+            undo_step(board, undo, &mut self.journal.borrow_mut());
+
+            for (reward, side_reward) in rewards.iter_mut().zip(side_rewards) {
+                *reward += side_reward / MOVEMENTS.len() as f32;
+            }
+        }
+        rewards
+    }
+
+    fn get_rewards(&self, board: &Board, start_turn: i32) -> Rewards {
+        if board.ruleset.is_solo() {
+            return survival(board, start_turn, self.config.rollout_cutoff);
         }
 
         if board.snakes.iter().all(|snake| !snake.is_alive()) {
             return [self.config.draw_reward; MAX_SNAKE_COUNT];
         }
 
-        let rewards = flavored_flood_fill(&board);
-        // info!("Started at {} turn and rolled out with {} turns and rewards {:?}", start_turn, board.turn - start_turn, rewards);
-        rewards
+        if board.is_terminal() {
+            return get_placement_rewards(board);
+        }
+
+        flavored_flood_fill(board)
     }
 
     fn backpropagate(&self, path: Vec<(RefMut<Node>, [usize; MAX_SNAKE_COUNT])>, rewards: Rewards) {
@@ -222,3 +301,150 @@ impl SequentialMCTS {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::objects::Movement;
+    use crate::mcts::search::Search;
+    use crate::test_data as data;
+    use crate::notation;
+    use crate::test_utils::create_board;
+
+    use super::{SequentialMCTS, SequentialMCTSConfig};
+
+    #[test]
+    fn test_solo_search() {
+        let board = create_board(data::SOLO_TOP_RIGHT_CORNER);
+        assert!(!board.is_terminal());
+
+        let mut mcts = SequentialMCTS::new(SequentialMCTSConfig {
+            table_capacity: 10000,
+            rollout_cutoff: 10,
+            draw_reward: 0.01,
+            max_select_depth: 50,
+            seed: Some(0),
+            shrink_expectation: false,
+        });
+        mcts.search(&board, 1000, false);
+
+        // Up and Right are walls, Left is the neck
+        assert_eq!(mcts.get_final_movement(&board, 0, false), Movement::Down);
+    }
+
+    #[test]
+    fn test_final_movement_of_snake_after_eliminated_one() {
+        let board = notation::parse("
+            ruleset=solo
+            A: health=0 body=0,0;0,1;0,2
+            B: health=90 length=3
+            . . b> b> B
+            . . .  .  .
+            * . .  .  .
+            . . .  .  .
+            . . .  .  .
+        ").unwrap();
+
+        let mut mcts = SequentialMCTS::new(SequentialMCTSConfig {
+            table_capacity: 10000,
+            rollout_cutoff: 10,
+            draw_reward: 0.01,
+            max_select_depth: 50,
+            seed: Some(0),
+            shrink_expectation: false,
+        });
+        mcts.search(&board, 1000, false);
+
+        assert_eq!(mcts.get_final_movement(&board, 1, false), Movement::Down);
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_search() {
+        let board = create_board(data::HEAD_TO_HEAD_CORRELATED_MCTS);
+        let config = SequentialMCTSConfig {
+            table_capacity: 10000,
+            rollout_cutoff: 10,
+            draw_reward: 0.01,
+            max_select_depth: 50,
+            seed: Some(42),
+            shrink_expectation: false,
+        };
+
+        let mut mcts = SequentialMCTS::new(config);
+        mcts.search(&board, 500, false);
+        let mut mcts_again = SequentialMCTS::new(config);
+        mcts_again.search(&board, 500, false);
+
+        assert_eq!(mcts.nodes.len(), mcts_again.nodes.len());
+        for (key, node) in &mcts.nodes {
+            let node = node.borrow();
+            let node_again = mcts_again.nodes[key].borrow();
+            assert_eq!(node.visits, node_again.visits);
+            for (agent, agent_again) in node.agents.iter().zip(&node_again.agents) {
+                assert_eq!(format!("{:?}", agent.strategy), format!("{:?}", agent_again.strategy));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shrink_expectation() {
+        use crate::engine::{EngineSettings, MOVEMENTS, advance_one_step_with_settings, food_spawner, safe_zone_shrinker, hazard_spawner};
+        use crate::game::MAX_SNAKE_COUNT;
+        use crate::mcts::utils::get_random_actions_from_masks;
+        use crate::ruleset::RulesetKind;
+
+        let mut board = create_board(data::HEAD_TO_HEAD_CORRELATED_MCTS);
+        board.ruleset = RulesetKind::Royale;
+        board.settings.food_spawn_chance = 0;
+        board.settings.minimum_food = 0;
+        board.turn = 10;
+        board.zobrist_hash = board.calculate_zobrist_hash();
+        let hash = board.zobrist_hash;
+
+        let config = SequentialMCTSConfig {
+            table_capacity: 10000,
+            rollout_cutoff: 5,
+            draw_reward: 0.01,
+            max_select_depth: 50,
+            seed: Some(7),
+            shrink_expectation: false,
+        };
+        let expectation_config = SequentialMCTSConfig {shrink_expectation: true, ..config};
+
+        // No shrinks before the cutoff, so it's the same rollout
+        let rewards = SequentialMCTS::new(config).simulation(&mut board);
+        let expected_rewards = SequentialMCTS::new(expectation_config).simulation(&mut board);
+        assert_eq!(rewards, expected_rewards);
+        assert_eq!(board.zobrist_hash, hash);
+
+        // Shrink on the next turn and two more before the cutoff. Rewards are averaged over
+        // the sides of the first shrink, the rollouts sample the later ones.
+        board.settings.shrink_every_n_turns = 2;
+        board.turn = 11;
+        board.zobrist_hash = board.calculate_zobrist_hash();
+        let hash = board.zobrist_hash;
+        let mcts = SequentialMCTS::new(SequentialMCTSConfig {rollout_cutoff: 4, ..expectation_config});
+        let expected_rewards = mcts.simulation(&mut board);
+        assert_eq!(board.zobrist_hash, hash);
+
+        let mcts = SequentialMCTS::new(SequentialMCTSConfig {rollout_cutoff: 4, ..config});
+        let actions = get_random_actions_from_masks(&mut *mcts.rng.borrow_mut(), &board);
+        let mut rewards = [0.0; MAX_SNAKE_COUNT];
+        for side in MOVEMENTS {
+            let mut side_board = board.clone();
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::create_standard,
+                safe_zone_shrinker: &mut safe_zone_shrinker::fixed(side),
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng: &mut mcts.rng.borrow_mut(),
+            };
+            advance_one_step_with_settings(&mut side_board, &mut settings, actions);
+            assert_ne!(side_board.safe_zone, board.safe_zone);
+            for (reward, side_reward) in rewards.iter_mut().zip(mcts.random_rollout(&mut side_board, board.turn, false)) {
+                *reward += side_reward / 4.0;
+            }
+        }
+        for (reward, expected_reward) in rewards.iter().zip(expected_rewards) {
+            assert!((reward - expected_reward).abs() < 1e-6);
+        }
+    }
+}
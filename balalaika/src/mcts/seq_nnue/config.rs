@@ -1,3 +1,5 @@
+use std::env;
+
 use crate::features::composite::CompositeFeatures;
 use crate::nnue::Model;
 use crate::mcts::utils::parse_env;
@@ -7,6 +9,10 @@ pub struct SequentialNNUEMCTSConfig {
     pub rollout_cutoff: i32,
     pub draw_reward: f32,
     pub max_select_depth: usize,
+    // Seed of rollouts and engine randomness. Random search when not set.
+    pub seed: Option<u64>,
+    // Rollouts average the rewards over the sides of their first shrink instead of sampling one.
+    pub shrink_expectation: bool,
     pub model: Model,
 }
 
@@ -25,6 +31,8 @@ impl SequentialNNUEMCTSConfig {
             rollout_cutoff:         parse_env("MCTS_ROLLOUT_CUTOFF").unwrap_or(0),
             draw_reward:            parse_env("MCTS_DRAW_REWARD").unwrap_or(0.01),
             max_select_depth:       parse_env("MCTS_SELECT_DEPTH").unwrap_or(50),
+            seed:                   parse_env("MCTS_SEED"),
+            shrink_expectation:     env::var("MCTS_SHRINK_EXPECTATION").is_ok(),
             model,
         };
 
@@ -6,13 +6,16 @@ use std::hash::BuildHasherDefault;
 use std::time::{Duration, Instant};
 
 use crate::api::objects::Movement;
-use crate::engine::{EngineSettings, advance_one_step_with_settings, food_spawner, safe_zone_shrinker};
-use crate::features::collector::Rewards;
-use crate::game::{Board, MAX_SNAKE_COUNT};
+use crate::engine::{EngineSettings, GameRng, MOVEMENTS, StepJournal, StepUndo, advance_one_step_undoable, undo_step, food_spawner, safe_zone_shrinker, hazard_spawner, new_rng};
+use crate::features::collector::{Rewards, get_placement_rewards};
+use crate::game::{Board, GridPoint, MAX_SNAKE_COUNT};
+use crate::maps;
 use crate::mcts::search::Search;
 use crate::nnue::rewards_from_tensor;
 use crate::zobrist::ZobristHasher;
 use crate::mcts::utils::{get_masks, get_random_actions_from_masks};
+use crate::mcts::heuristics::survival::survival;
+use crate::ruleset::Ruleset;
 
 use super::config::SequentialNNUEMCTSConfig;
 
@@ -43,13 +46,20 @@ impl Node {
 pub struct SequentialMCTS {
     config: SequentialNNUEMCTSConfig,
     nodes: HashMap<u64, RefCell<Node>, BuildHasherDefault<ZobristHasher>>,
+    rng: RefCell<GameRng>,
+    // Found once per search from the root board
+    spiral_center: Option<GridPoint>,
+    // Shared by all steps of a rollout, so they don't allocate
+    journal: RefCell<StepJournal>,
 }
 
 impl Search for SequentialMCTS {
     fn search(&mut self, board: &Board, iterations_count: usize, _verbose: bool) {
+        self.spiral_center = maps::spiral_center(board);
+        let mut board = board.clone();
         for _i in 0..iterations_count {
             info!("iteration {}", _i);
-            self.rollout(board);
+            self.rollout(&mut board);
         }
     }
 
@@ -57,9 +67,11 @@ impl Search for SequentialMCTS {
         let time_start = Instant::now();
         let time_end = time_start + target_duration;
 
+        self.spiral_center = maps::spiral_center(board);
+        let mut board = board.clone();
         let mut i = 0;
         while Instant::now() < time_end {
-            self.rollout(board);
+            self.rollout(&mut board);
             i += 1;
         }
 
@@ -69,10 +81,10 @@ impl Search for SequentialMCTS {
         i
     }
 
-    fn get_final_movement(&self, board: &Board, agent_index: usize, _verbose: bool) -> Movement {
+    fn get_final_movement(&self, board: &Board, snake_index: usize, _verbose: bool) -> Movement {
         let node = self.nodes[&board.zobrist_hash.get_value()].borrow();
-        let strategy = &node.agents[agent_index].strategy;
-        strategy.get_final_movement()
+        let agent = node.agents.iter().find(|agent| agent.id == snake_index).expect("Snake is not alive");
+        agent.strategy.get_final_movement()
     }
 
     fn shutdown(&self) {}
@@ -82,6 +94,9 @@ impl SequentialMCTS {
     pub fn new(config: SequentialNNUEMCTSConfig) -> SequentialMCTS {
         SequentialMCTS {
             nodes: HashMap::with_capacity_and_hasher(config.table_capacity, BuildHasherDefault::<ZobristHasher>::default()),
+            rng: RefCell::new(new_rng(config.seed)),
+            spiral_center: None,
+            journal: RefCell::new(StepJournal::default()),
             config,
         }
     }
@@ -96,20 +111,25 @@ impl SequentialMCTS {
         }
     }
 
-    fn rollout(&mut self, board: &Board) {
-        let mut board = board.clone();
-        let path = self.selection(&mut board);
+    // Walks down and back up the same board, so it's left unchanged.
+    fn rollout(&mut self, board: &mut Board) {
+        let mut undos = Vec::new();
+        let path = self.selection(board, &mut undos);
 
-        let masks = get_masks(&board);
+        let masks = get_masks(board);
 
-        let rewards = self.simulation(&board);
+        let rewards = self.simulation(board);
         self.backpropagate(path, rewards);
         if !board.is_terminal() {
-            self.expansion(&board, masks);
+            self.expansion(board, masks);
+        }
+
+        while let Some(undo) = undos.pop() {
+            undo_step(board, undo, &mut self.journal.borrow_mut());
         }
     }
 
-    fn selection(&self, board: &mut Board) -> Vec<(RefMut<Node>, [usize; MAX_SNAKE_COUNT])> {
+    fn selection(&self, board: &mut Board, undos: &mut Vec<StepUndo>) -> Vec<(RefMut<Node>, [usize; MAX_SNAKE_COUNT])> {
         // let start = Instant::now();
 
         let mut path = Vec::new();
@@ -117,6 +137,8 @@ impl SequentialMCTS {
         let mut engine_settings = EngineSettings {
             food_spawner: &mut food_spawner::create_standard,
             safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+            hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+            rng: &mut self.rng.borrow_mut(),
         };
 
         while path.len() < self.config.max_select_depth {
@@ -145,11 +167,13 @@ impl SequentialMCTS {
                 alive_i += 1;
             }
 
-            advance_one_step_with_settings(
+            let (_, undo) = advance_one_step_undoable(
                 board,
                 &mut engine_settings,
                 joint_action,
+                &mut self.journal.borrow_mut(),
             );
+            undos.push(undo);
 
             path.push((node, joint_action));
         }
@@ -188,36 +212,90 @@ impl SequentialMCTS {
         }
     }
 
-    fn simulation(&self, board: &Board) -> Rewards {
-        let mut board = board.clone();
-        let rollout_cutoff = self.config.rollout_cutoff;
+    fn simulation(&self, board: &mut Board) -> Rewards {
+        let start_turn = board.turn;
+        self.random_rollout(board, start_turn, self.config.shrink_expectation)
+    }
 
-        let random = &mut rand::thread_rng();
+    // With `expect_shrink` the first shrink of the rollout is a chance event: the rollout goes on
+    // from every side with the same actions, and their rewards are averaged. Later shrinks are
+    // sampled, so it costs at most as much as four rollouts.
+    fn random_rollout(&self, board: &mut Board, start_turn: i32, expect_shrink: bool) -> Rewards {
+        let mut undos = Vec::new();
+        let end_turn = start_turn + self.config.rollout_cutoff;
 
-        let mut engine_settings = EngineSettings {
-            food_spawner: &mut food_spawner::create_standard,
-            safe_zone_shrinker: &mut safe_zone_shrinker::standard,
-        };
-
-        let end_turn = board.turn + rollout_cutoff;
+        let mut rewards = None;
         while board.turn <= end_turn && !board.is_terminal() {
-            let actions = get_random_actions_from_masks(random, &board);
+            let actions = get_random_actions_from_masks(&mut *self.rng.borrow_mut(), board);
 
-            advance_one_step_with_settings(
-                &mut board,
-                &mut engine_settings,
+            if expect_shrink && safe_zone_shrinker::is_shrink_turn(board, board.turn + 1) {
+                rewards = Some(self.expected_shrink(board, start_turn, actions));
+                break;
+            }
+
+            let (_, undo) = advance_one_step_undoable(
+                board,
+                &mut EngineSettings {
+                    food_spawner: &mut food_spawner::create_standard,
+                    safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                    hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+                    rng: &mut self.rng.borrow_mut(),
+                },
                 actions,
+                &mut self.journal.borrow_mut(),
             );
+            undos.push(undo);
+        }
+
+        let rewards = rewards.unwrap_or_else(|| self.get_rewards(board, start_turn));
+        // info!("Started at {} turn and rolled out with {} turns and rewards {:?}", start_turn, board.turn - start_turn, rewards);
+
+        while let Some(undo) = undos.pop() {
+            undo_step(board, undo, &mut self.journal.borrow_mut());
+        }
+
+        rewards
+    }
+
+    // Average rewards of the rollouts after the shrink to every side.
+    fn expected_shrink(&self, board: &mut Board, start_turn: i32, actions: [usize; MAX_SNAKE_COUNT]) -> Rewards {
+        let mut rewards = [0.0; MAX_SNAKE_COUNT];
+        for side in MOVEMENTS {
+            let (_, undo) = advance_one_step_undoable(
+                board,
+                &mut EngineSettings {
+                    food_spawner: &mut food_spawner::create_standard,
+                    safe_zone_shrinker: &mut safe_zone_shrinker::fixed(side),
+                    hazard_spawner: &mut hazard_spawner::search(self.spiral_center),
+                    rng: &mut self.rng.borrow_mut(),
+                },
+                actions,
+                &mut self.journal.borrow_mut(),
+            );
+            let side_rewards = self.random_rollout(board, start_turn, false);
+            undo_step(board, undo, &mut self.journal.borrow_mut());
+
+            for (reward, side_reward) in rewards.iter_mut().zip(side_rewards) {
+                *reward += side_reward / MOVEMENTS.len() as f32;
+            }
+        }
+        rewards
+    }
+
+    fn get_rewards(&self, board: &Board, start_turn: i32) -> Rewards {
+        if board.ruleset.is_solo() {
+            return survival(board, start_turn, self.config.rollout_cutoff);
         }
 
         if board.snakes.iter().all(|snake| !snake.is_alive()) {
             return [self.config.draw_reward; MAX_SNAKE_COUNT];
         }
 
-        let rewards = rewards_from_tensor(self.config.model.predict(&board));
+        if board.is_terminal() {
+            return get_placement_rewards(board);
+        }
 
-        // info!("Started at {} turn and rolled out with {} turns and rewards {:?}", start_turn, board.turn - start_turn, rewards);
-        rewards
+        rewards_from_tensor(self.config.model.predict(board))
     }
 }
 
@@ -227,11 +305,12 @@ mod tests {
     use std::time::Duration;
 
     use crate::board_generator::generate_board;
-    use crate::engine::advance_one_step;
+    use crate::engine::{advance_one_step, new_rng};
     use crate::features::composite::CompositeFeatures;
-    use crate::game::MAX_SNAKE_COUNT;
+    use crate::game::{MAX_SNAKE_COUNT, DEFAULT_WIDTH, DEFAULT_HEIGHT};
     use crate::mcts::search::Search;
     use crate::nnue::Model;
+    use crate::ruleset::RulesetKind;
     
     use super::SequentialNNUEMCTSConfig as NNUEConfig;
     use super::SequentialMCTS as NNUEMCTS;
@@ -251,6 +330,8 @@ mod tests {
             rollout_cutoff:         0,
             draw_reward:            0.00001,
             max_select_depth:       50,
+            seed:                   None,
+            shrink_expectation:     false,
             model,
         };
         let mut seq_nnue = NNUEMCTS::new(config);
@@ -260,10 +341,12 @@ mod tests {
             rollout_cutoff:         0,
             draw_reward:            0.01,
             max_select_depth:       50,
+            seed:                   None,
+            shrink_expectation:     false,
         };
         let mut seq = SequentialMCTS::new(config);
         
-        let mut board = generate_board();
+        let mut board = generate_board(&mut new_rng(None), RulesetKind::Wrapped, DEFAULT_WIDTH, DEFAULT_HEIGHT, 4);
         while !board.is_terminal() {
             println!("NNUE {}", seq_nnue.search_with_time(&board, Duration::from_millis(600), true));
             println!("FLOOD {}", seq.search_with_time(&board, Duration::from_millis(600), true));
@@ -278,9 +361,9 @@ mod tests {
                     continue;
                 }
                 if alive_i == 0 {
-                    actions[0] = seq.get_final_movement(&board, 0, true) as usize;
+                    actions[i] = seq.get_final_movement(&board, i, true) as usize;
                 } else {
-                    actions[i] = seq_nnue.get_final_movement(&board, alive_i, true) as usize;
+                    actions[i] = seq_nnue.get_final_movement(&board, i, true) as usize;
                 }
                 alive_i += 1;
             }
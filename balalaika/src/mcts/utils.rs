@@ -2,13 +2,14 @@ use std::env;
 use std::str::FromStr;
 use std::time::Duration;
 
-use arrayvec::ArrayVec;
 use rand::{seq::SliceRandom, Rng};
 
-use crate::{api::objects::Movement, game::GridPoint};
+use crate::api::objects::Movement;
 use crate::engine::MOVEMENTS;
 use super::search::Search;
-use crate::game::{Board, Point, MAX_SNAKE_COUNT, HEIGHT, WIDTH};
+use super::heuristics::flood_fill::HazardLevels;
+use crate::game::{Board, MAX_SNAKE_COUNT};
+use crate::ruleset::Ruleset;
 
 
 pub fn parse_env<Value>(key: &str) -> Option<Value>
@@ -41,68 +42,49 @@ impl SearchOptions {
 pub fn get_masks(board: &Board) -> [[bool; 4]; MAX_SNAKE_COUNT] {
     // Returns movement masks for alive snakes
 
-    let mut tails = ArrayVec::<_, MAX_SNAKE_COUNT>::new();
-    
-    for snake in board.snakes.iter() {
-        if !snake.is_alive() {
-            continue;
-        }
+    // Tails move away unless they are stacked
+    let mut blocked = board.objects.occupied;
+    for snake in board.snakes.iter().filter(|snake| snake.is_alive()) {
         let tail = snake.body[snake.body.len() - 1];
         let pretail = snake.body[snake.body.len() - 2];
         if tail != pretail {
-            tails.push(tail);
+            blocked.unset(tail.into());
         }
     }
-    
-    let mut masks = [[false; 4]; MAX_SNAKE_COUNT];
-
-    board.snakes
-        .iter()
-        .enumerate()
-        .filter(|(_, snake)| snake.is_alive())
-        .for_each(|(snake_index, snake)| {
-            MOVEMENTS
-                .iter()
-                .for_each(|&movement| {
-                    let movement_position = get_movement_position(snake.head(), movement);
-                    if !board.objects.is_body(movement_position.into()) || tails.contains(&movement_position) {
-                        masks[snake_index][movement as usize] = true;
-                    }
-                });
-        });
-    
-    masks
-}
-
-pub fn get_movement_position(position: GridPoint, movement: Movement) -> GridPoint {
-    match movement {
-        Movement::Right => Point {x: (position.x + 1) % WIDTH, y: position.y},
-        Movement::Left => Point {x: (WIDTH + position.x - 1) % WIDTH, y: position.y},
-        Movement::Up => Point {x: position.x, y: (position.y + 1) % HEIGHT },
-        Movement::Down => Point {x: position.x, y: (HEIGHT + position.y - 1) % HEIGHT},
-    }
-}
+    let free = board.cells() & !blocked;
 
-pub fn movement_positions_wrapped(position: GridPoint) -> [GridPoint; 4] {
-    let positions = [
-        Point {x: position.x, y: (position.y + 1) % HEIGHT },
-        Point {x: (position.x + 1) % WIDTH, y: position.y},
-        Point {x: position.x, y: (HEIGHT + position.y - 1) % HEIGHT},
-        Point {x: (WIDTH + position.x - 1) % WIDTH, y: position.y},
-    ];
+    // Cells from which the movement leads to a free cell
+    let wrapped = board.ruleset.is_wrapped();
+    let can_move = MOVEMENTS.map(|movement| {
+        let opposite = Movement::from_usize((movement as usize + 2) % 4);
+        free.moved(opposite, board.width, board.height, wrapped)
+    });
 
-    positions
-}
+    // Hazards which take the rest of the health are walls (e.g. maps with hazard walls)
+    let hazard_levels = HazardLevels::new(board);
 
-pub fn movement_positions_standard(position: GridPoint) -> [GridPoint; 4] {
-    let positions = [
-        Point {x: position.x, y: position.y + 1 },
-        Point {x: position.x + 1, y: position.y},
-        Point {x: position.x, y: position.y - 1},
-        Point {x: position.x - 1, y: position.y},
-    ];
+    let mut masks = [[false; 4]; MAX_SNAKE_COUNT];
 
-    positions
+    for (snake_index, snake) in board.snakes.iter().enumerate() {
+        if !snake.is_alive() {
+            continue;
+        }
+        let deadly = hazard_levels.deadly(snake.health - 1);
+        let can_move_alive = if deadly.is_empty() {
+            can_move
+        } else {
+            let safe = free & !deadly;
+            MOVEMENTS.map(|movement| {
+                let opposite = Movement::from_usize((movement as usize + 2) % 4);
+                safe.moved(opposite, board.width, board.height, wrapped)
+            })
+        };
+        for movement in MOVEMENTS {
+            masks[snake_index][movement as usize] = can_move_alive[movement as usize].get(snake.head().into());
+        }
+    }
+    
+    masks
 }
 
 pub fn get_random_actions_from_masks(random: &mut impl Rng, board: &Board) -> [usize; MAX_SNAKE_COUNT] {
@@ -159,9 +141,9 @@ pub fn search(searcher: &mut impl Search, board: &Board, options: SearchOptions)
     }
 }
 
-pub fn get_best_movement(searcher: &mut impl Search, board: &Board, agent: usize, options: SearchOptions) -> Movement {
+pub fn get_best_movement(searcher: &mut impl Search, board: &Board, snake_index: usize, options: SearchOptions) -> Movement {
     search(searcher, board, options);
-    searcher.get_final_movement(board, agent, options.verbose)
+    searcher.get_final_movement(board, snake_index, options.verbose)
 }
 
 
@@ -169,7 +151,9 @@ pub fn get_best_movement(searcher: &mut impl Search, board: &Board, agent: usize
 mod tests {
     use arrayvec::ArrayVec;
 
-    use crate::{game::{Board, Snake, Point}, mcts::utils::get_masks};
+    use crate::{game::{Board, Snake, Point}, mcts::utils::get_masks, ruleset::RulesetKind, settings::{GameMap, GameSettings}};
+    use crate::maps::initial_hazards;
+    use crate::engine::new_rng;
 
     #[test]
     fn test_get_masks() {
@@ -187,15 +171,54 @@ mod tests {
             },
         );
 
-        let board = Board::new(
+        let mut board = Board::new(
+            RulesetKind::Wrapped,
+            GameSettings::default(),
+            11,
+            11,
             0,
             Some(vec![Point {x: 0, y: 0}]),
             None,
+            None,
             snakes,
         );
 
         let masks = get_masks(&board);
         assert_eq!(masks[0], [true, false, true, true]);
         assert_eq!(masks[1], [true, true, true, false]);
+
+        // Left is a wall for the first snake
+        board.ruleset = RulesetKind::Standard;
+        let masks = get_masks(&board);
+        assert_eq!(masks[0], [true, false, true, false]);
+        assert_eq!(masks[1], [true, true, true, false]);
+    }
+
+    #[test]
+    fn test_get_masks_hazard_walls() {
+        let snakes = [
+            Snake {
+                health: 100,
+                body: [Point {x: 4, y: 4}, Point {x: 3, y: 4}, Point {x: 2, y: 4}].into()
+            },
+        ];
+        let settings = GameSettings {hazard_damage_per_turn: 100, map: GameMap::RiversAndBridges, ..GameSettings::default()};
+        let hazards = initial_hazards(&mut new_rng(Some(0)), settings.map, 11, 11);
+        let board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, Some(vec![Point {x: 0, y: 0}]), None, Some(hazards.clone()), snakes.clone().into_iter().collect());
+
+        // River is up and right
+        let masks = get_masks(&board);
+        assert_eq!(masks[0], [false, false, true, false]);
+
+        // Food cancels the damage
+        let board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, Some(vec![Point {x: 4, y: 5}]), None, Some(hazards.clone()), snakes.clone().into_iter().collect());
+        let masks = get_masks(&board);
+        assert_eq!(masks[0], [true, false, true, false]);
+
+        // Hazards which are not lethal are passable
+        let settings = GameSettings {hazard_damage_per_turn: 14, ..settings};
+        let board = Board::new(RulesetKind::Solo, settings, 11, 11, 0, Some(vec![Point {x: 0, y: 0}]), None, Some(hazards), snakes.into_iter().collect());
+        let masks = get_masks(&board);
+        assert_eq!(masks[0], [true, true, true, false]);
     }
 }
@@ -1,5 +1,4 @@
 use std::cell::RefCell;
-use std::mem::{MaybeUninit, self};
 
 use tch;
 
@@ -42,16 +41,18 @@ impl Model {
 }
 
 pub fn rewards_from_tensor(tensor: tch::Tensor) -> Rewards {
-    let mut rewards: [MaybeUninit<f32>; MAX_SNAKE_COUNT] = unsafe { MaybeUninit::uninit().assume_init() };
-    for i in 0..MAX_SNAKE_COUNT {
-        rewards[i] = MaybeUninit::new(f32::from(tensor.get(i as i64)));
+    // Model can be trained for fewer snakes than MAX_SNAKE_COUNT. The rest get zero reward.
+    let outputs = (tensor.size()[0] as usize).min(MAX_SNAKE_COUNT);
+    let mut rewards = [0.0; MAX_SNAKE_COUNT];
+    for i in 0..outputs {
+        rewards[i] = f32::from(tensor.get(i as i64));
     }
-    unsafe { mem::transmute(rewards) }
+    rewards
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{board_generator::generate_board, nnue::Model, features::composite::CompositeFeatures};
+    use crate::{board_generator::generate_board, engine::new_rng, nnue::Model, features::composite::CompositeFeatures, game::{DEFAULT_WIDTH, DEFAULT_HEIGHT}, ruleset::RulesetKind};
 
     #[test]
     fn test_predict() {
@@ -59,7 +60,7 @@ mod tests {
             tch::CModule::load("../analysis/weights/main.pt").unwrap(),
             CompositeFeatures::new(vec![String::from("base")]),
         );
-        let board = generate_board();
+        let board = generate_board(&mut new_rng(None), RulesetKind::Wrapped, DEFAULT_WIDTH, DEFAULT_HEIGHT, 4);
         let x = model.predict(&board);
         println!("{:?}", x);
     }
@@ -0,0 +1,459 @@
+//! Plain text board notation, for tests, bug reports and logs.
+//!
+//! ```text
+//! ruleset=standard turn=12
+//! A: health=98 length=4
+//! B: health=0 eliminated=11 cause=head-to-head:A body=5,1;5,2;5,3
+//! . . .  .  .
+//! . * A  .  .!
+//! . . a^ a< .!
+//! . . .  a^ .!!
+//! ```
+//!
+//! Header lines are `key=value` pairs: `ruleset` (API name, `standard` by default), `turn` (0 by default)
+//! and the fields of `GameSettings` (`food_spawn_chance`, `minimum_food`, `hazard_damage_per_turn`,
+//! `shrink_every_n_turns`, `map`), which are default when omitted.
+//!
+//! A snake line starts with the snake letter, `A` is the first snake. Alive snakes have `health`
+//! and `length`, the length counts stacked parts too. Dead snakes have `health` and
+//! `body` as `x,y` points from the head, and optional `eliminated` turn and `cause`
+//! (`out-of-bounds`, `self-collision`, `body-collision:B`, `head-to-head:B`, `starvation`, `hazard`).
+//!
+//! The grid goes from the top row, cells are separated by whitespace:
+//! - `.` is empty, `*` is food;
+//! - `A` is the head of the snake A;
+//! - `a^`, `av`, `a<`, `a>` is a body part of the snake A, the arrow points to the next part towards the head.
+//!   Directions follow the ruleset, so in wrapped games an arrow can point across the border;
+//! - each trailing `!` is a hazard stack on the cell.
+//!
+//! Hazards are split into the safe zone and the grid of stacks the same way as API hazards.
+
+use std::fmt::Write;
+
+use arrayvec::ArrayVec;
+
+use crate::api::objects::Movement;
+use crate::array2d::Array2D;
+use crate::engine::{MOVEMENTS, Elimination, EliminationCause};
+use crate::game::{Board, Snake, GridPoint, Point, CoordType, MAX_SNAKE_COUNT};
+use crate::ruleset::{Ruleset, RulesetKind};
+use crate::settings::{GameMap, GameSettings};
+use crate::snake_body::SnakeBody;
+use crate::zobrist::{BodyDirections, body_direction};
+
+const EMPTY_CHAR: char = '.';
+const FOOD_CHAR: char = '*';
+const HAZARD_CHAR: char = '!';
+const ARROWS: [char; 4] = ['^', '>', 'v', '<'];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Food,
+    Head(usize),
+    // Snake and direction to the next part towards the head
+    Body(usize, Movement),
+}
+
+struct SnakeLine {
+    health: i32,
+    length: Option<usize>,
+    body: Option<Vec<GridPoint>>,
+    elimination_turn: Option<i32>,
+    cause: Option<EliminationCause>,
+}
+
+pub fn parse(text: &str) -> Result<Board, String> {
+    let mut ruleset = RulesetKind::Standard;
+    let mut turn = 0;
+    let mut settings = GameSettings::default();
+    let mut snake_lines: ArrayVec<Option<SnakeLine>, MAX_SNAKE_COUNT> = ArrayVec::new();
+    let mut rows: Vec<Vec<(Cell, u8)>> = Vec::new();
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some((letter, rest)) = line.split_once(':').filter(|(letter, _)| letter.len() == 1) {
+            let i = snake_index(letter.chars().next().unwrap().to_ascii_lowercase())?;
+            while snake_lines.len() <= i {
+                snake_lines.push(None);
+            }
+            snake_lines[i] = Some(parse_snake_line(rest)?);
+        } else if line.contains('=') {
+            for (key, value) in key_values(line)? {
+                match key {
+                    "ruleset" => ruleset = value.parse()?,
+                    "turn" => turn = parse_number(key, value)?,
+                    "food_spawn_chance" => settings.food_spawn_chance = parse_number(key, value)?,
+                    "minimum_food" => settings.minimum_food = parse_number(key, value)?,
+                    "hazard_damage_per_turn" => settings.hazard_damage_per_turn = parse_number(key, value)?,
+                    "shrink_every_n_turns" => settings.shrink_every_n_turns = parse_number(key, value)?,
+                    "map" => settings.map = GameMap::from_api_name(value),
+                    _ => return Err(format!("Unknown key '{}'", key)),
+                }
+            }
+        } else {
+            rows.push(line.split_whitespace().map(parse_cell).collect::<Result<_, _>>()?);
+        }
+    }
+
+    let height = rows.len() as CoordType;
+    let width = rows.first().map_or(0, |row| row.len()) as CoordType;
+    if width == 0 || rows.iter().any(|row| row.len() as CoordType != width) {
+        return Err(String::from("Grid rows must be non-empty and of the same width"));
+    }
+    // Top row goes first
+    let cell_at = |p: GridPoint| rows[(height - 1 - p.y) as usize][p.x as usize];
+
+    let mut foods = Vec::new();
+    let mut hazards = Vec::new();
+    let mut heads = [None; MAX_SNAKE_COUNT];
+    let mut body_parts_count = [0; MAX_SNAKE_COUNT];
+    for y in (0..height).rev() {
+        for x in 0..width {
+            let p = Point {x, y};
+            let (cell, stacks) = cell_at(p);
+            hazards.extend((0..stacks).map(|_| p));
+            match cell {
+                Cell::Empty => {},
+                Cell::Food => foods.push(p),
+                Cell::Head(i) => {
+                    if heads[i].replace(p).is_some() {
+                        return Err(format!("Snake {} has several heads", snake_letter(i)));
+                    }
+                },
+                Cell::Body(i, _) => body_parts_count[i] += 1,
+            }
+        }
+    }
+
+    let mut snakes = ArrayVec::new();
+    let mut eliminations = [None; MAX_SNAKE_COUNT];
+    for (i, snake_line) in snake_lines.into_iter().enumerate() {
+        let snake_line = snake_line.ok_or_else(|| format!("No line for snake {}", snake_letter(i)))?;
+
+        let body: SnakeBody = if snake_line.health > 0 {
+            let head = heads[i].ok_or_else(|| format!("Snake {} is alive but has no head", snake_letter(i)))?;
+            let length = snake_line.length.ok_or_else(|| format!("Snake {} is alive but has no length", snake_letter(i)))?;
+
+            // Follow the arrows from the head to the tail
+            let mut body = vec![head];
+            'parts: while body.len() <= body_parts_count[i] {
+                let front = body[body.len() - 1];
+                for movement in MOVEMENTS {
+                    let back = ruleset.movement_position(front, opposite(movement), width, height);
+                    let inside = back.x >= 0 && back.x < width && back.y >= 0 && back.y < height;
+                    if inside && cell_at(back).0 == Cell::Body(i, movement) && !body.contains(&back) {
+                        body.push(back);
+                        continue 'parts;
+                    }
+                }
+                return Err(format!("Body of snake {} is broken after {:?}", snake_letter(i), front));
+            }
+            if length < body.len() {
+                return Err(format!("Snake {} has {} parts on the grid, but its length is {}", snake_letter(i), body.len(), length));
+            }
+            let tail = body[body.len() - 1];
+            body.resize(length, tail);
+            body.into()
+        } else {
+            if heads[i].is_some() || body_parts_count[i] > 0 {
+                return Err(format!("Snake {} is dead but is on the grid", snake_letter(i)));
+            }
+            snake_line.body.ok_or_else(|| format!("Snake {} is dead but has no body", snake_letter(i)))?.into()
+        };
+
+        if let (Some(turn), Some(cause)) = (snake_line.elimination_turn, snake_line.cause) {
+            eliminations[i] = Some(Elimination {turn, cause});
+        }
+        snakes.push(Snake {health: snake_line.health, body});
+    }
+
+    if let Some(i) = (snakes.len()..MAX_SNAKE_COUNT).find(|&i| heads[i].is_some() || body_parts_count[i] > 0) {
+        return Err(format!("No line for snake {}", snake_letter(i)));
+    }
+
+    let (safe_zone, hazards) = Board::calculate_hazards(width, height, &hazards);
+    let no_food = foods.is_empty();
+    let mut board = Board::try_new(ruleset, settings, width, height, turn, Some(foods), Some(safe_zone), Some(hazards), snakes)
+        .map_err(|error| error.to_string())?;
+    if no_food {
+        // Board adds food to the position without it
+        while let Some(&food) = board.foods.last() {
+            board.remove_food(food);
+        }
+    }
+    board.eliminations = eliminations;
+
+    Ok(board)
+}
+
+pub fn print(board: &Board) -> String {
+    let mut output = String::new();
+
+    writeln!(output, "ruleset={} turn={}", board.ruleset, board.turn).unwrap();
+    if board.settings != GameSettings::default() {
+        let settings = &board.settings;
+        writeln!(
+            output,
+            "food_spawn_chance={} minimum_food={} hazard_damage_per_turn={} shrink_every_n_turns={} map={}",
+            settings.food_spawn_chance,
+            settings.minimum_food,
+            settings.hazard_damage_per_turn,
+            settings.shrink_every_n_turns,
+            settings.map,
+        ).unwrap();
+    }
+
+    let mut cells = Array2D::init_same(Cell::Empty);
+    for (i, snake) in board.snakes.iter().enumerate() {
+        write!(output, "{}: health={}", snake_letter(i).to_ascii_uppercase(), snake.health).unwrap();
+
+        if snake.is_alive() {
+            writeln!(output, " length={}", snake.body.len()).unwrap();
+
+            let head = snake.head();
+            cells[(head.x as usize, head.y as usize)] = Cell::Head(i);
+            for j in 1..snake.body.len() {
+                let (back, front) = (snake.body[j], snake.body[j - 1]);
+                let direction = body_direction(back, front);
+                if direction == BodyDirections::Still {
+                    break;
+                }
+                cells[(back.x as usize, back.y as usize)] = Cell::Body(i, Movement::from_usize(direction as usize));
+            }
+        } else {
+            if let Some(elimination) = board.eliminations[i] {
+                write!(output, " eliminated={} cause={}", elimination.turn, cause_name(elimination.cause)).unwrap();
+            }
+            let body: Vec<_> = snake.body.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+            writeln!(output, " body={}", body.join(";")).unwrap();
+        }
+    }
+    for food in &board.foods {
+        cells[(food.x as usize, food.y as usize)] = Cell::Food;
+    }
+
+    let mut tokens = Vec::new();
+    for y in (0..board.height).rev() {
+        tokens.push((0..board.width).map(|x| {
+            let mut token = match cells[(x as usize, y as usize)] {
+                Cell::Empty => EMPTY_CHAR.to_string(),
+                Cell::Food => FOOD_CHAR.to_string(),
+                Cell::Head(i) => snake_letter(i).to_ascii_uppercase().to_string(),
+                Cell::Body(i, movement) => format!("{}{}", snake_letter(i), ARROWS[movement as usize]),
+            };
+            let stacks = board.hazard_stacks(Point {x, y});
+            token.extend((0..stacks).map(|_| HAZARD_CHAR));
+            token
+        }).collect::<Vec<_>>());
+    }
+
+    // Align columns
+    let cell_width = tokens.iter().flatten().map(|token| token.chars().count()).max().unwrap_or(1);
+    for row in tokens {
+        let line: Vec<_> = row.iter().map(|token| format!("{:width$}", token, width = cell_width)).collect();
+        writeln!(output, "{}", line.join(" ").trim_end()).unwrap();
+    }
+
+    output
+}
+
+fn parse_snake_line(line: &str) -> Result<SnakeLine, String> {
+    let mut snake_line = SnakeLine {
+        health: 0,
+        length: None,
+        body: None,
+        elimination_turn: None,
+        cause: None,
+    };
+    let mut has_health = false;
+
+    for (key, value) in key_values(line)? {
+        match key {
+            "health" => {
+                snake_line.health = parse_number(key, value)?;
+                has_health = true;
+            },
+            "length" => snake_line.length = Some(parse_number(key, value)?),
+            "eliminated" => snake_line.elimination_turn = Some(parse_number(key, value)?),
+            "cause" => snake_line.cause = Some(parse_cause(value)?),
+            "body" => {
+                let body = value.split(';').map(|point| {
+                    let (x, y) = point.split_once(',').ok_or_else(|| format!("Bad point '{}'", point))?;
+                    Ok(Point {x: parse_number("x", x)?, y: parse_number("y", y)?})
+                }).collect::<Result<Vec<_>, String>>()?;
+                snake_line.body = Some(body);
+            },
+            _ => return Err(format!("Unknown snake key '{}'", key)),
+        }
+    }
+
+    if !has_health {
+        return Err(format!("No health in snake line '{}'", line));
+    }
+    Ok(snake_line)
+}
+
+fn parse_cell(token: &str) -> Result<(Cell, u8), String> {
+    let object = token.trim_end_matches(HAZARD_CHAR);
+    let stacks = (token.len() - object.len()) as u8;
+
+    let mut chars = object.chars();
+    let cell = match (chars.next(), chars.next(), chars.next()) {
+        (Some(EMPTY_CHAR), None, _) => Cell::Empty,
+        (Some(FOOD_CHAR), None, _) => Cell::Food,
+        (Some(c), None, _) if c.is_ascii_uppercase() => Cell::Head(snake_index(c.to_ascii_lowercase())?),
+        (Some(c), Some(arrow), None) if c.is_ascii_lowercase() => {
+            let direction = ARROWS.iter().position(|&a| a == arrow).ok_or_else(|| format!("Bad arrow in '{}'", token))?;
+            Cell::Body(snake_index(c)?, Movement::from_usize(direction))
+        },
+        _ => return Err(format!("Bad cell '{}'", token)),
+    };
+
+    Ok((cell, stacks))
+}
+
+fn parse_cause(value: &str) -> Result<EliminationCause, String> {
+    let (name, snake) = match value.split_once(':') {
+        Some((name, letter)) if letter.len() == 1 => (name, Some(snake_index(letter.chars().next().unwrap().to_ascii_lowercase())?)),
+        Some(_) => return Err(format!("Bad cause '{}'", value)),
+        None => (value, None),
+    };
+
+    match (name, snake) {
+        ("out-of-bounds", None) => Ok(EliminationCause::OutOfBounds),
+        ("self-collision", None) => Ok(EliminationCause::SelfCollision),
+        ("body-collision", Some(j)) => Ok(EliminationCause::BodyCollision(j)),
+        ("head-to-head", Some(j)) => Ok(EliminationCause::HeadToHead(j)),
+        ("starvation", None) => Ok(EliminationCause::Starvation),
+        ("hazard", None) => Ok(EliminationCause::Hazard),
+        _ => Err(format!("Bad cause '{}'", value)),
+    }
+}
+
+fn cause_name(cause: EliminationCause) -> String {
+    match cause {
+        EliminationCause::OutOfBounds => String::from("out-of-bounds"),
+        EliminationCause::SelfCollision => String::from("self-collision"),
+        EliminationCause::BodyCollision(j) => format!("body-collision:{}", snake_letter(j).to_ascii_uppercase()),
+        EliminationCause::HeadToHead(j) => format!("head-to-head:{}", snake_letter(j).to_ascii_uppercase()),
+        EliminationCause::Starvation => String::from("starvation"),
+        EliminationCause::Hazard => String::from("hazard"),
+    }
+}
+
+fn key_values(line: &str) -> Result<Vec<(&str, &str)>, String> {
+    line.split_whitespace()
+        .map(|pair| pair.split_once('=').ok_or_else(|| format!("Expected key=value, got '{}'", pair)))
+        .collect()
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Bad number '{}' for {}", value, key))
+}
+
+fn snake_index(letter: char) -> Result<usize, String> {
+    let i = (letter as usize).wrapping_sub('a' as usize);
+    if letter.is_ascii_lowercase() && i < MAX_SNAKE_COUNT {
+        Ok(i)
+    } else {
+        Err(format!("Bad snake letter '{}', only {} snakes are supported", letter, MAX_SNAKE_COUNT))
+    }
+}
+
+fn snake_letter(i: usize) -> char {
+    (b'a' + i as u8) as char
+}
+
+fn opposite(movement: Movement) -> Movement {
+    Movement::from_usize((movement as usize + 2) % 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board_generator::generate_board;
+    use crate::engine::{EngineSettings, advance_one_step_with_settings, food_spawner, safe_zone_shrinker, hazard_spawner, new_rng};
+    use crate::mcts::utils::get_random_actions_from_masks;
+    use crate::game::Point;
+    use crate::ruleset::RulesetKind;
+    use crate::test_data as data;
+    use crate::test_utils::create_board;
+
+    use super::{parse, print};
+
+    const POSITION: &str = "
+        ruleset=wrapped turn=12
+        food_spawn_chance=15 minimum_food=1 hazard_damage_per_turn=14 shrink_every_n_turns=25 map=standard
+        A: health=98 length=5
+        B: health=0 eliminated=11 cause=head-to-head:A body=2,0;2,1;2,2
+        .  .  .  .   A
+        .  *  .  .   a^
+        a< .  .  .!  a^
+        .  .  .  .!! .
+    ";
+
+    #[test]
+    fn test_parse() {
+        let board = parse(POSITION).unwrap();
+
+        assert_eq!(board.ruleset, RulesetKind::Wrapped);
+        assert_eq!((board.width, board.height, board.turn), (5, 4, 12));
+        assert_eq!(board.settings.food_spawn_chance, 15);
+        // The tail is across the right border and stacked
+        assert_eq!(
+            board.snakes[0].body.iter().copied().collect::<Vec<_>>(),
+            vec![Point {x: 4, y: 3}, Point {x: 4, y: 2}, Point {x: 4, y: 1}, Point {x: 0, y: 1}, Point {x: 0, y: 1}],
+        );
+        assert_eq!(board.snakes[0].health, 98);
+        assert!(!board.snakes[1].is_alive());
+        assert_eq!(board.foods, vec![Point {x: 1, y: 2}]);
+        assert_eq!(board.hazard_stacks(Point {x: 3, y: 0}), 2);
+        assert_eq!(board.hazard_stacks(Point {x: 3, y: 1}), 1);
+        assert_eq!(board.hazards_count(), 2);
+        assert_eq!(board.zobrist_hash, board.calculate_zobrist_hash());
+    }
+
+    #[test]
+    fn test_print_parse_round_trip() {
+        let text = print(&parse(POSITION).unwrap());
+        assert_eq!(text, print(&parse(&text).unwrap()));
+
+        for json in [data::BODY_COLLISION, data::FOLLOW_TAIL, data::FOOD_IN_FRONT, data::STEP_ON_TAIL, data::HEAD_TO_HEAD_CORRELATED_MCTS] {
+            let board = create_board(json);
+            let parsed = parse(&print(&board)).unwrap();
+            assert_eq!(parsed.zobrist_hash, board.zobrist_hash, "{}", print(&board));
+            assert_eq!(parsed.snakes, board.snakes);
+        }
+
+        for ruleset in [RulesetKind::Standard, RulesetKind::Wrapped, RulesetKind::Constrictor, RulesetKind::Royale] {
+            let rng = &mut new_rng(Some(5));
+            let mut board = generate_board(rng, ruleset, 11, 11, 4);
+            board.settings.shrink_every_n_turns = 5;
+            let mut settings = EngineSettings {
+                food_spawner: &mut food_spawner::create_standard,
+                safe_zone_shrinker: &mut safe_zone_shrinker::standard,
+                hazard_spawner: &mut hazard_spawner::standard,
+                rng,
+            };
+            while !board.is_terminal() {
+                let actions = get_random_actions_from_masks(settings.rng, &board);
+                advance_one_step_with_settings(&mut board, &mut settings, actions);
+
+                let text = print(&board);
+                let parsed = parse(&text).unwrap();
+                assert_eq!(print(&parsed), text);
+                assert_eq!(parsed.zobrist_hash, board.zobrist_hash, "{}", text);
+                assert_eq!(parsed.snakes, board.snakes);
+                assert_eq!(parsed.eliminations, board.eliminations);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("A: health=10 length=3\n. A\na> .").is_err());
+        assert!(parse("A: health=10 length=3\n. A .\n. a^").is_err());
+        assert!(parse("A: health=10 length=3\n. A\n. b^").is_err());
+        assert!(parse(". A").is_err());
+        assert!(parse("A: health=10 length=1\nA a<").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
 use pyo3::types::PyDict;
 use crate::api::objects::State;
 use crate::features::base;
@@ -9,7 +10,7 @@ use crate::features::composite::feature_set_sizes;
 use crate::mcts::seq::{SequentialMCTS, SequentialMCTSConfig};
 use crate::mcts::search::Search;
 use crate::{features, mcts, dataloader};
-use crate::game::Board;
+use crate::game::{Board, BoardError};
 use crate::game_log as gl;
 use crate::engine;
 use crate::game::{MAX_SNAKE_COUNT};
@@ -17,13 +18,24 @@ use pythonize::{depythonize, pythonize};
 use mcts::heuristics;
 
 
+// Boards from Python are checked, so bad input raises ValueError instead of crashing the interpreter.
+fn board_from_dict(board: &PyDict) -> PyResult<Board> {
+    let board: Board = depythonize(board).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    board.validate().map_err(board_error)?;
+    Ok(board)
+}
+
+fn board_error(error: BoardError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
 #[pyfunction]
 fn rewind(
     py: Python<'_>,
     game_log: &PyDict
 ) -> PyResult<(Vec<[usize; MAX_SNAKE_COUNT]>, PyObject, (Rewards, bool))> {
-    let game_log: gl::GameLog = depythonize(game_log).unwrap();
-    let (actions, boards) = gl::rewind(&game_log);
+    let game_log: gl::GameLog = depythonize(game_log).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    let (actions, boards) = gl::try_rewind(&game_log).map_err(board_error)?;
     let rewards = get_rewards(&boards[game_log.turns]);
     let boards = pythonize(py, &boards).unwrap();
     Ok((actions, boards, rewards))
@@ -32,17 +44,18 @@ fn rewind(
 #[pyfunction]
 fn flood_fill(
     board: &PyDict,
-) -> Rewards {
-    let board: Board = depythonize(board).unwrap();
-    heuristics::flood_fill::flood_fill(&board)
+) -> PyResult<Rewards> {
+    let board = board_from_dict(board)?;
+    Ok(heuristics::flood_fill::flood_fill(&board))
 }
 
 #[pyfunction]
 fn draw_board(
     board: &PyDict,
-) {
-    let board: Board = depythonize(board).unwrap();
+) -> PyResult<()> {
+    let board = board_from_dict(board)?;
     println!("{}", board);
+    Ok(())
 }
 
 #[pyfunction]
@@ -50,7 +63,7 @@ fn get_features(
     board: &PyDict,
     feature_set_tags: Vec<String>,
 ) -> PyResult<(Vec<IndexType>, Vec<ValueType>)> {
-    let board: Board = depythonize(board).unwrap();
+    let board = board_from_dict(board)?;
     let mut features_handler = features::composite::CompositeFeatures::new(feature_set_tags);
     collect_features(&board, &mut features_handler);
     let features = features_handler.pop_features();
@@ -62,8 +75,8 @@ fn get_board_from_state(
     py: Python<'_>,
     state: &PyDict,
 ) -> PyResult<PyObject> {
-    let state: State = depythonize(state).unwrap();
-    let board = Board::from_api(&state);
+    let state: State = depythonize(state).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    let board = Board::try_from_api(&state).map_err(board_error)?;
     let board = pythonize(py, &board).unwrap();
     Ok(board)
 }
@@ -73,15 +86,18 @@ fn search(
     board: &PyDict,
     iterations_count: usize,
 ) -> PyResult<[usize; MAX_SNAKE_COUNT]> {
-    let board: Board = depythonize(board).unwrap();
+    let board = board_from_dict(board)?;
     let config = SequentialMCTSConfig::from_env();
     let mut mcts = SequentialMCTS::new(config);
-    mcts.search(&board, iterations_count);
+    mcts.search(&board, iterations_count, false);
 
     let mut actions = [0; MAX_SNAKE_COUNT];
-    for i in 0..MAX_SNAKE_COUNT {
-        let a = mcts.get_final_movement(&board, i);
-        actions[i] = a as usize;
+    for (snake_i, snake) in board.snakes.iter().enumerate() {
+        if !snake.is_alive() {
+            continue;
+        }
+        let a = mcts.get_final_movement(&board, snake_i, false);
+        actions[snake_i] = a as usize;
     }
     Ok(actions)
 }
@@ -90,7 +106,7 @@ fn search(
 fn get_masks(
     board: &PyDict,
 ) -> PyResult<[[bool; 4]; MAX_SNAKE_COUNT]> {
-    let board: Board = depythonize(board).unwrap();
+    let board = board_from_dict(board)?;
     let masks = mcts::utils::get_masks(&board);
     Ok(masks)
 }
@@ -106,7 +122,7 @@ fn advance_one_step(
     board: &PyDict,
     actions: [usize; MAX_SNAKE_COUNT]
 ) -> PyResult<PyObject> {
-    let mut board: Board = depythonize(board).unwrap();
+    let mut board = board_from_dict(board)?;
     engine::advance_one_step(&mut board, actions);
     let board = pythonize(py, &board).unwrap();
     Ok(board)
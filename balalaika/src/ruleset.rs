@@ -0,0 +1,202 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+use crate::api::objects::Movement;
+use crate::engine::MOVEMENTS;
+use crate::game::{CoordType, GridPoint, Point};
+
+/// Game rules which differ between battlesnake game modes.
+/// Engine, movement masks and heuristics must all ask the ruleset where a snake goes.
+pub trait Ruleset {
+    /// Position of the head after the movement.
+    /// It can be outside of the board, then the snake is eliminated.
+    fn movement_position(&self, position: GridPoint, movement: Movement, width: CoordType, height: CoordType) -> GridPoint;
+
+    /// Positions in `MOVEMENTS` order.
+    fn movement_positions(&self, position: GridPoint, width: CoordType, height: CoordType) -> [GridPoint; 4] {
+        MOVEMENTS.map(|movement| self.movement_position(position, movement, width, height))
+    }
+
+    /// Snakes grow every turn and keep max health. There is no food.
+    fn is_constrictor(&self) -> bool {
+        false
+    }
+
+    /// Single snake game. It's over only when the snake dies, the goal is to survive as long as possible.
+    fn is_solo(&self) -> bool {
+        false
+    }
+
+    /// Snakes going out of the board appear on the opposite side. Bitboards shift by it.
+    fn is_wrapped(&self) -> bool {
+        false
+    }
+
+    /// Safe zone shrinks every `shrink_every_n_turns` turns, cells outside of it are hazards.
+    fn is_royale(&self) -> bool {
+        false
+    }
+}
+
+/// Walls on the borders.
+#[derive(Debug, Copy, Clone)]
+pub struct Standard;
+
+/// Snakes going out of the board appear on the opposite side.
+#[derive(Debug, Copy, Clone)]
+pub struct Wrapped;
+
+/// Constrictor variant of the movement rules.
+#[derive(Debug, Copy, Clone)]
+pub struct Constrictor<R: Ruleset>(pub R);
+
+impl Ruleset for Standard {
+    fn movement_position(&self, position: GridPoint, movement: Movement, _: CoordType, _: CoordType) -> GridPoint {
+        position + movement.to_direction()
+    }
+}
+
+impl Ruleset for Wrapped {
+    fn movement_position(&self, position: GridPoint, movement: Movement, width: CoordType, height: CoordType) -> GridPoint {
+        match movement {
+            Movement::Right => Point {x: (position.x + 1) % width, y: position.y},
+            Movement::Left => Point {x: (width + position.x - 1) % width, y: position.y},
+            Movement::Up => Point {x: position.x, y: (position.y + 1) % height},
+            Movement::Down => Point {x: position.x, y: (height + position.y - 1) % height},
+        }
+    }
+
+    fn is_wrapped(&self) -> bool {
+        true
+    }
+}
+
+impl<R: Ruleset> Ruleset for Constrictor<R> {
+    fn movement_position(&self, position: GridPoint, movement: Movement, width: CoordType, height: CoordType) -> GridPoint {
+        self.0.movement_position(position, movement, width, height)
+    }
+
+    fn is_constrictor(&self) -> bool {
+        true
+    }
+
+    fn is_wrapped(&self) -> bool {
+        self.0.is_wrapped()
+    }
+}
+
+/// Ruleset chosen for a game. Stored in the board, so it's cheap to copy and dispatch.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum RulesetKind {
+    Standard,
+    Wrapped,
+    Constrictor,
+    WrappedConstrictor,
+    Solo,
+    Royale,
+}
+
+impl RulesetKind {
+    /// Unknown game modes are played by standard rules.
+    pub fn from_api_name(name: &str) -> RulesetKind {
+        name.parse().unwrap_or(RulesetKind::Standard)
+    }
+}
+
+impl FromStr for RulesetKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "standard" | "squad" => Ok(RulesetKind::Standard),
+            "royale" => Ok(RulesetKind::Royale),
+            "wrapped" => Ok(RulesetKind::Wrapped),
+            "constrictor" => Ok(RulesetKind::Constrictor),
+            "wrapped_constrictor" => Ok(RulesetKind::WrappedConstrictor),
+            "solo" => Ok(RulesetKind::Solo),
+            _ => Err(format!("Unknown ruleset {}", name)),
+        }
+    }
+}
+
+impl fmt::Display for RulesetKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RulesetKind::Standard => "standard",
+            RulesetKind::Wrapped => "wrapped",
+            RulesetKind::Constrictor => "constrictor",
+            RulesetKind::WrappedConstrictor => "wrapped_constrictor",
+            RulesetKind::Solo => "solo",
+            RulesetKind::Royale => "royale",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Ruleset for RulesetKind {
+    fn movement_position(&self, position: GridPoint, movement: Movement, width: CoordType, height: CoordType) -> GridPoint {
+        match self {
+            RulesetKind::Standard | RulesetKind::Solo | RulesetKind::Royale => Standard.movement_position(position, movement, width, height),
+            RulesetKind::Wrapped => Wrapped.movement_position(position, movement, width, height),
+            RulesetKind::Constrictor => Constrictor(Standard).movement_position(position, movement, width, height),
+            RulesetKind::WrappedConstrictor => Constrictor(Wrapped).movement_position(position, movement, width, height),
+        }
+    }
+
+    fn is_constrictor(&self) -> bool {
+        match self {
+            RulesetKind::Standard | RulesetKind::Wrapped | RulesetKind::Solo | RulesetKind::Royale => false,
+            RulesetKind::Constrictor | RulesetKind::WrappedConstrictor => true,
+        }
+    }
+
+    fn is_solo(&self) -> bool {
+        *self == RulesetKind::Solo
+    }
+
+    fn is_wrapped(&self) -> bool {
+        matches!(self, RulesetKind::Wrapped | RulesetKind::WrappedConstrictor)
+    }
+
+    fn is_royale(&self) -> bool {
+        *self == RulesetKind::Royale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_movement_positions() {
+        let corner = Point {x: 0, y: 10};
+
+        assert_eq!(
+            RulesetKind::Standard.movement_positions(corner, 11, 11),
+            [Point {x: 0, y: 11}, Point {x: 1, y: 10}, Point {x: 0, y: 9}, Point {x: -1, y: 10}],
+        );
+        assert_eq!(
+            RulesetKind::Wrapped.movement_positions(corner, 11, 11),
+            [Point {x: 0, y: 0}, Point {x: 1, y: 10}, Point {x: 0, y: 9}, Point {x: 10, y: 10}],
+        );
+        assert_eq!(
+            RulesetKind::Wrapped.movement_position(Point {x: 6, y: 0}, Movement::Down, 7, 19),
+            Point {x: 6, y: 18},
+        );
+    }
+
+    #[test]
+    fn test_from_api_name() {
+        assert_eq!(RulesetKind::from_api_name("standard"), RulesetKind::Standard);
+        assert_eq!(RulesetKind::from_api_name("royale"), RulesetKind::Royale);
+        assert!(RulesetKind::from_api_name("royale").is_royale());
+        assert_eq!(RulesetKind::from_api_name("wrapped"), RulesetKind::Wrapped);
+        assert_eq!(RulesetKind::from_api_name("wrapped_constrictor"), RulesetKind::WrappedConstrictor);
+        assert!(RulesetKind::from_api_name("constrictor").is_constrictor());
+        assert!(RulesetKind::from_api_name("solo").is_solo());
+        assert_eq!(RulesetKind::from_api_name("something_new"), RulesetKind::Standard);
+        assert!("something_new".parse::<RulesetKind>().is_err());
+    }
+}
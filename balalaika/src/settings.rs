@@ -0,0 +1,204 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+use crate::api;
+
+/// Map of the game. Maps place and spawn food and hazards on their own.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMap {
+    Standard,
+    Empty,
+    Royale,
+    // Hazard stacks fill pits in cycles of `shrink_every_n_turns` turns.
+    HazardPits,
+    // Hazard stacks grow as rounded rings from the center.
+    Sinkholes,
+    // Hazards heal with negative damage. A random pool dries up every `shrink_every_n_turns` turns.
+    HealingPools,
+    // Tails leave hazard stacks as long as their snakes, stacks decay by one every turn.
+    SnailMode,
+    // Static hazards placed at the start
+    InnerWall,
+    Rings,
+    Columns,
+    // Hazard spiral grows clockwise from a random cell in the middle, a cell every 3 turns.
+    Spiral,
+    // Hazard walls with bridges, meant to be played with lethal hazard damage. Food spawns at
+    // fixed spots of the castle and away from the walls of the rivers. Only medium boards.
+    CastleWall,
+    RiversAndBridges,
+    // Maze of hazard walls on 19x21 board, also with lethal hazard damage. Food spawns at fixed spots
+    // of the maze with the food spawn chance.
+    ArcadeMaze,
+    // Map is not supported by the engine. Game is played as on standard map.
+    Unknown,
+}
+
+impl GameMap {
+    pub fn from_api_name(name: &str) -> GameMap {
+        match name {
+            "" | "standard" => GameMap::Standard,
+            "empty" => GameMap::Empty,
+            "royale" => GameMap::Royale,
+            "hz_hazard_pits" => GameMap::HazardPits,
+            "sinkholes" => GameMap::Sinkholes,
+            "healing_pools" => GameMap::HealingPools,
+            "snail_mode" => GameMap::SnailMode,
+            "hz_inner_wall" => GameMap::InnerWall,
+            "hz_rings" => GameMap::Rings,
+            "hz_columns" => GameMap::Columns,
+            "hz_spiral" => GameMap::Spiral,
+            "hz_castle_wall" => GameMap::CastleWall,
+            "hz_rivers_bridges" => GameMap::RiversAndBridges,
+            "arcade_maze" | "hz_arcade_maze" => GameMap::ArcadeMaze,
+            _ => GameMap::Unknown,
+        }
+    }
+
+    /// Royale map shrinks the safe zone on any ruleset, as the royale ruleset does.
+    /// Other maps with their own hazards use `shrink_every_n_turns` for them.
+    pub fn shrinks_safe_zone(self) -> bool {
+        self == GameMap::Royale
+    }
+
+    /// Hazards are walls, snakes are not supposed to survive in them.
+    pub fn has_hazard_walls(self) -> bool {
+        matches!(self, GameMap::CastleWall | GameMap::RiversAndBridges | GameMap::ArcadeMaze)
+    }
+
+    /// Food only appears at the map's spots, so the board may have none.
+    pub fn has_fixed_food(self) -> bool {
+        matches!(self, GameMap::CastleWall | GameMap::ArcadeMaze)
+    }
+}
+
+impl FromStr for GameMap {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match GameMap::from_api_name(name) {
+            GameMap::Unknown => Err(format!("Unknown map {}", name)),
+            map => Ok(map),
+        }
+    }
+}
+
+impl fmt::Display for GameMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GameMap::Standard => "standard",
+            GameMap::Empty => "empty",
+            GameMap::Royale => "royale",
+            GameMap::HazardPits => "hz_hazard_pits",
+            GameMap::Sinkholes => "sinkholes",
+            GameMap::HealingPools => "healing_pools",
+            GameMap::SnailMode => "snail_mode",
+            GameMap::InnerWall => "hz_inner_wall",
+            GameMap::Rings => "hz_rings",
+            GameMap::Columns => "hz_columns",
+            GameMap::Spiral => "hz_spiral",
+            GameMap::CastleWall => "hz_castle_wall",
+            GameMap::RiversAndBridges => "hz_rivers_bridges",
+            GameMap::ArcadeMaze => "arcade_maze",
+            GameMap::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Parameters of the game rules. Carried by the board, so the engine and the features
+/// use the real parameters of each game.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct GameSettings {
+    /// Percent chance to spawn food on each turn.
+    pub food_spawn_chance: u32,
+    pub minimum_food: usize,
+    /// Damage per hazard stack. Starvation damage is applied separately.
+    pub hazard_damage_per_turn: i32,
+    pub shrink_every_n_turns: i32,
+    pub map: GameMap,
+}
+
+impl Default for GameSettings {
+    /// Defaults of the official rules. The API fills missing settings with them,
+    /// and selfplay and game logs written before settings were stored use them too.
+    fn default() -> Self {
+        GameSettings {
+            food_spawn_chance: 15,
+            minimum_food: 1,
+            hazard_damage_per_turn: 14,
+            shrink_every_n_turns: 25,
+            map: GameMap::Standard,
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn from_api(game_api: &api::objects::Game) -> GameSettings {
+        let settings_api = &game_api.ruleset.settings;
+
+        GameSettings {
+            food_spawn_chance: settings_api.food_spawn_chance.clamp(0, 100) as u32,
+            minimum_food: settings_api.minimum_food.max(0) as usize,
+            hazard_damage_per_turn: settings_api.hazard_damage_per_turn,
+            shrink_every_n_turns: settings_api.royale.shrink_every_n_turns,
+            map: GameMap::from_api_name(&game_api.map),
+        }
+    }
+
+    /// Part of the shrink period passed at the given turn, in [0, 1).
+    pub fn shrink_progress(&self, turn: i32) -> f32 {
+        let shrink_every = self.shrink_every_n_turns.max(1);
+        (turn % shrink_every) as f32 / shrink_every as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::serde::json::serde_json;
+
+    use crate::api::objects::Game;
+    use super::{GameSettings, GameMap};
+
+    #[test]
+    fn test_from_api() {
+        let game: Game = serde_json::from_str(r#"{
+            "id": "1",
+            "ruleset": {
+                "name": "royale",
+                "version": "v1.1.20",
+                "settings": {
+                    "foodSpawnChance": 25,
+                    "minimumFood": 3,
+                    "hazardDamagePerTurn": 30,
+                    "royale": {"shrinkEveryNTurns": 10},
+                    "squad": {"allowBodyCollisions": false}
+                }
+            },
+            "map": "royale",
+            "timeout": 500
+        }"#).unwrap();
+
+        let settings = GameSettings::from_api(&game);
+        assert_eq!(settings.food_spawn_chance, 25);
+        assert_eq!(settings.minimum_food, 3);
+        assert_eq!(settings.hazard_damage_per_turn, 30);
+        assert_eq!(settings.shrink_every_n_turns, 10);
+        assert_eq!(settings.map, GameMap::Royale);
+
+        // Old API without settings
+        let game: Game = serde_json::from_str(r#"{
+            "id": "1",
+            "ruleset": {"name": "standard", "version": "v1.2.3"},
+            "timeout": 500
+        }"#).unwrap();
+
+        let settings = GameSettings::from_api(&game);
+        assert_eq!(settings, GameSettings::default());
+        assert_eq!(settings.food_spawn_chance, 15);
+        assert_eq!(settings.shrink_every_n_turns, 25);
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{Chain, FromIterator};
+use std::mem::MaybeUninit;
+use std::ops::Index;
+use std::slice;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error;
+
+use crate::game::{GridPoint, MAX_SIZE};
+
+/// Body parts occupy distinct cells except the tail, which can be stacked up to 3 times.
+pub const SNAKE_BODY_CAPACITY: usize = MAX_SIZE + 2;
+
+pub type Iter<'a> = Chain<slice::Iter<'a, GridPoint>, slice::Iter<'a, GridPoint>>;
+
+/// Ring buffer of body parts from head to tail. Unlike `VecDeque` it never allocates,
+/// so cloning a board is a plain copy. Serialized as a sequence, same as `VecDeque`.
+#[derive(Clone)]
+pub struct SnakeBody {
+    parts: [MaybeUninit<GridPoint>; SNAKE_BODY_CAPACITY],
+    // Index of the head in `parts`
+    start: usize,
+    len: usize,
+}
+
+impl SnakeBody {
+    pub fn new() -> SnakeBody {
+        SnakeBody {
+            parts: [MaybeUninit::uninit(); SNAKE_BODY_CAPACITY],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, part: GridPoint) {
+        assert!(self.len < SNAKE_BODY_CAPACITY, "Snake body is full");
+        self.start = if self.start == 0 { SNAKE_BODY_CAPACITY - 1 } else { self.start - 1 };
+        self.parts[self.start] = MaybeUninit::new(part);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, part: GridPoint) {
+        assert!(self.len < SNAKE_BODY_CAPACITY, "Snake body is full");
+        let i = self.wrap(self.start + self.len);
+        self.parts[i] = MaybeUninit::new(part);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<GridPoint> {
+        if self.len == 0 {
+            return None;
+        }
+        let part = self.get_unchecked(self.start);
+        self.start = self.wrap(self.start + 1);
+        self.len -= 1;
+        Some(part)
+    }
+
+    pub fn pop_back(&mut self) -> Option<GridPoint> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.get_unchecked(self.wrap(self.start + self.len)))
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    pub fn contains(&self, part: &GridPoint) -> bool {
+        let (front, back) = self.as_slices();
+        front.contains(part) || back.contains(part)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        let (front, back) = self.as_slices();
+        front.iter().chain(back.iter())
+    }
+
+    pub fn as_slices(&self) -> (&[GridPoint], &[GridPoint]) {
+        let front_len = self.len.min(SNAKE_BODY_CAPACITY - self.start);
+        // SAFETY: `len` parts starting from `start` (wrapping around) are initialized.
+        unsafe {
+            let ptr = self.parts.as_ptr() as *const GridPoint;
+            (
+                slice::from_raw_parts(ptr.add(self.start), front_len),
+                slice::from_raw_parts(ptr, self.len - front_len),
+            )
+        }
+    }
+
+    fn wrap(&self, i: usize) -> usize {
+        if i >= SNAKE_BODY_CAPACITY { i - SNAKE_BODY_CAPACITY } else { i }
+    }
+
+    fn get_unchecked(&self, i: usize) -> GridPoint {
+        // SAFETY: only called for indices inside the initialized range.
+        unsafe { self.parts[i].assume_init() }
+    }
+}
+
+impl Default for SnakeBody {
+    fn default() -> Self {
+        SnakeBody::new()
+    }
+}
+
+impl Index<usize> for SnakeBody {
+    type Output = GridPoint;
+
+    fn index(&self, i: usize) -> &GridPoint {
+        assert!(i < self.len, "Index {} is out of body of length {}", i, self.len);
+        // SAFETY: checked above that the part is initialized.
+        unsafe { &*self.parts[self.wrap(self.start + i)].as_ptr() }
+    }
+}
+
+impl<'a> IntoIterator for &'a SnakeBody {
+    type Item = &'a GridPoint;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl FromIterator<GridPoint> for SnakeBody {
+    fn from_iter<I: IntoIterator<Item = GridPoint>>(iter: I) -> Self {
+        let mut body = SnakeBody::new();
+        for part in iter {
+            body.push_back(part);
+        }
+        body
+    }
+}
+
+impl<const N: usize> From<[GridPoint; N]> for SnakeBody {
+    fn from(parts: [GridPoint; N]) -> Self {
+        parts.into_iter().collect()
+    }
+}
+
+impl From<Vec<GridPoint>> for SnakeBody {
+    fn from(parts: Vec<GridPoint>) -> Self {
+        parts.into_iter().collect()
+    }
+}
+
+impl PartialEq for SnakeBody {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for SnakeBody {}
+
+impl Hash for SnakeBody {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        self.iter().for_each(|part| part.hash(state));
+    }
+}
+
+impl fmt::Debug for SnakeBody {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Serialize for SnakeBody {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for SnakeBody {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let parts = Vec::<GridPoint>::deserialize(deserializer)?;
+        if parts.len() > SNAKE_BODY_CAPACITY {
+            return Err(D::Error::custom(format!("Snake body of length {} exceeds capacity {}", parts.len(), SNAKE_BODY_CAPACITY)));
+        }
+        Ok(parts.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rocket::serde::json::serde_json;
+
+    use crate::game::{GridPoint, Point};
+    use super::{SnakeBody, SNAKE_BODY_CAPACITY};
+
+    #[test]
+    fn test_wraps_around_like_vec_deque() {
+        let mut body = SnakeBody::new();
+        let mut expected = VecDeque::new();
+        for i in 0..3 * SNAKE_BODY_CAPACITY as i32 {
+            let part = Point {x: i, y: -i};
+            body.push_front(part);
+            expected.push_front(part);
+            if i % 3 != 0 {
+                assert_eq!(body.pop_back(), expected.pop_back());
+            }
+            if body.len() == SNAKE_BODY_CAPACITY {
+                body.truncate(2);
+                expected.truncate(2);
+            }
+
+            assert_eq!(body.len(), expected.len());
+            assert!(body.iter().eq(expected.iter()));
+            assert_eq!(body[body.len() - 1], expected[expected.len() - 1]);
+            assert_eq!(body.clone(), body);
+        }
+
+        while let Some(part) = expected.pop_front() {
+            assert_eq!(body.pop_front(), Some(part));
+        }
+        assert!(body.is_empty());
+        assert_eq!(body.pop_back(), None);
+    }
+
+    #[test]
+    fn test_serialized_as_vec_deque() {
+        let parts: Vec<GridPoint> = (0..5).map(|i| Point {x: i, y: 2 * i}).collect();
+        let mut body = SnakeBody::from(parts.clone());
+        let expected = VecDeque::from(parts);
+        assert_eq!(serde_json::to_string(&body).unwrap(), serde_json::to_string(&expected).unwrap());
+
+        body.pop_back();
+        body.push_front(Point {x: 7, y: 7});
+        let json = serde_json::to_string(&body).unwrap();
+        assert_eq!(serde_json::from_str::<SnakeBody>(&json).unwrap(), body);
+
+        let too_long = vec![Point {x: 0, y: 0}; SNAKE_BODY_CAPACITY + 1];
+        assert!(serde_json::from_str::<SnakeBody>(&serde_json::to_string(&too_long).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use serde::{Serialize, Deserialize};
+
+use crate::api::objects::Movement;
+use crate::engine::MOVEMENTS;
+use crate::game::{CoordType, GridPoint, Point};
+use crate::zobrist::BodyDirections;
+
+/// Symmetries of the board: rotations clockwise around the center and flips.
+/// Rotations by 90 and 270 degrees and diagonal flips swap the width and the height.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    // Mirrors x
+    FlipX,
+    // Mirrors y
+    FlipY,
+    // Swaps x and y
+    FlipDiagonal,
+    // Swaps x and y and mirrors both
+    FlipAntiDiagonal,
+}
+
+pub const SYMMETRIES: [Symmetry; 8] = [
+    Symmetry::Identity,
+    Symmetry::Rotate90,
+    Symmetry::Rotate180,
+    Symmetry::Rotate270,
+    Symmetry::FlipX,
+    Symmetry::FlipY,
+    Symmetry::FlipDiagonal,
+    Symmetry::FlipAntiDiagonal,
+];
+
+impl Symmetry {
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            _ => self,
+        }
+    }
+
+    pub fn swaps_sides(self) -> bool {
+        matches!(self, Symmetry::Rotate90 | Symmetry::Rotate270 | Symmetry::FlipDiagonal | Symmetry::FlipAntiDiagonal)
+    }
+
+    /// Width and height of the transformed board.
+    pub fn size(self, width: CoordType, height: CoordType) -> (CoordType, CoordType) {
+        if self.swaps_sides() { (height, width) } else { (width, height) }
+    }
+
+    // Linear part of the transform, it maps directions.
+    fn linear(self, v: GridPoint) -> GridPoint {
+        let (x, y) = (v.x, v.y);
+        let (x, y) = match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (y, -x),
+            Symmetry::Rotate180 => (-x, -y),
+            Symmetry::Rotate270 => (-y, x),
+            Symmetry::FlipX => (-x, y),
+            Symmetry::FlipY => (x, -y),
+            Symmetry::FlipDiagonal => (y, x),
+            Symmetry::FlipAntiDiagonal => (-y, -x),
+        };
+        Point {x, y}
+    }
+
+    /// Position of the cell of the board `width` × `height` on the transformed board.
+    pub fn point(self, p: GridPoint, width: CoordType, height: CoordType) -> GridPoint {
+        // Shift the image of the board back to the corner at (0, 0)
+        let corner = self.linear(Point {x: width - 1, y: height - 1});
+        let q = self.linear(p);
+        Point {
+            x: q.x - corner.x.min(0),
+            y: q.y - corner.y.min(0),
+        }
+    }
+
+    pub fn movement(self, movement: Movement) -> Movement {
+        let direction = self.linear(movement.to_direction());
+        MOVEMENTS.into_iter().find(|m| m.to_direction() == direction).unwrap()
+    }
+
+    pub fn body_direction(self, direction: BodyDirections) -> BodyDirections {
+        if direction == BodyDirections::Still {
+            return BodyDirections::Still;
+        }
+        BodyDirections::from(self.movement(Movement::from_usize(direction as usize)) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board_generator::generate_board;
+    use crate::engine::{MOVEMENTS, EngineSettings, advance_one_step_with_settings, food_spawner, safe_zone_shrinker, hazard_spawner, new_rng};
+    use crate::game::{Board, GridPoint, Point, MAX_SNAKE_COUNT};
+    use crate::mcts::utils::get_random_actions_from_masks;
+    use crate::notation;
+    use crate::ruleset::{Ruleset, RulesetKind};
+    use super::{Symmetry, SYMMETRIES};
+
+    #[test]
+    fn test_point_and_movement() {
+        assert_eq!(Symmetry::Rotate90.point(Point {x: 9, y: 2}, 11, 11), Point {x: 2, y: 1});
+        assert_eq!(Symmetry::Rotate270.point(Point {x: 0, y: 1}, 4, 4), Point {x: 2, y: 0});
+        assert_eq!(Symmetry::FlipAntiDiagonal.point(Point {x: 0, y: 0}, 7, 5), Point {x: 4, y: 6});
+
+        let (width, height) = (7, 5);
+        for symmetry in SYMMETRIES {
+            let (new_width, new_height) = symmetry.size(width, height);
+            for x in 0..width {
+                for y in 0..height {
+                    let p: GridPoint = Point {x, y};
+                    let q = symmetry.point(p, width, height);
+                    assert!(q.x >= 0 && q.x < new_width && q.y >= 0 && q.y < new_height, "{:?} {:?}", symmetry, q);
+                    assert_eq!(symmetry.inverse().point(q, new_width, new_height), p);
+
+                    for movement in MOVEMENTS {
+                        let moved = RulesetKind::Wrapped.movement_position(p, movement, width, height);
+                        let expected = RulesetKind::Wrapped.movement_position(q, symmetry.movement(movement), new_width, new_height);
+                        assert_eq!(symmetry.point(moved, width, height), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    // Without food spawns and shrinks the step is deterministic
+    fn step(board: &mut Board, actions: [usize; MAX_SNAKE_COUNT]) {
+        let mut settings = EngineSettings {
+            food_spawner: &mut food_spawner::noop,
+            safe_zone_shrinker: &mut safe_zone_shrinker::noop,
+            hazard_spawner: &mut hazard_spawner::noop,
+            rng: &mut new_rng(Some(0)),
+        };
+        advance_one_step_with_settings(board, &mut settings, actions);
+    }
+
+    #[test]
+    fn test_engine_is_equivariant() {
+        for ruleset in [RulesetKind::Standard, RulesetKind::Wrapped] {
+            let rng = &mut new_rng(Some(3));
+            let mut board = generate_board(rng, ruleset, 11, 11, 4);
+            board.hazards[(3, 4)] = 2;
+            board.safe_zone.p1.y -= 2;
+            board.hazard_bitboard = board.calculate_hazard_bitboard();
+            board.zobrist_hash = board.calculate_zobrist_hash();
+
+            while !board.is_terminal() {
+                let actions = get_random_actions_from_masks(rng, &board);
+                for symmetry in SYMMETRIES {
+                    let mut transformed = board.transform(symmetry);
+                    assert_eq!(transformed.transform(symmetry.inverse()).zobrist_hash, board.zobrist_hash);
+
+                    let mut transformed_actions = [0; MAX_SNAKE_COUNT];
+                    for i in 0..board.snakes.len() {
+                        transformed_actions[i] = symmetry.movement(MOVEMENTS[actions[i]]) as usize;
+                    }
+                    let mut stepped = board.clone();
+                    step(&mut stepped, actions);
+                    step(&mut transformed, transformed_actions);
+
+                    let expected = stepped.transform(symmetry);
+                    assert_eq!(notation::print(&transformed), notation::print(&expected), "{:?}", symmetry);
+                    assert_eq!(transformed.zobrist_hash, expected.zobrist_hash);
+                }
+                step(&mut board, actions);
+            }
+        }
+    }
+}
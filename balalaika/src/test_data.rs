@@ -186,6 +186,23 @@ pub const STEP_ON_TAIL: &str = include_str!("test_data/step_on_tail.json");
 */
 pub const TOP_RIGHT_CORNER: &str = include_str!("test_data/top_right_corner.json");
 
+/*
+ turn=200
+ A: health=100
+ . . . . . . . . a a A
+ . . . . . . . . a . .
+ . . . . . . . . a . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+ . . . . . . . . . . .
+*/
+pub const SOLO_TOP_RIGHT_CORNER: &str = include_str!("test_data/solo_top_right_corner.json");
+
 /*
  turn=200
  A: health=100
@@ -4,7 +4,6 @@ use crate::{
     game::{
         Board,
         MAX_SNAKE_COUNT,
-        SIZE,
     },
     api,
     engine::advance_one_step,
@@ -24,5 +23,5 @@ pub fn test_transition(state_before: &str, state_after: &str, actions: [usize; M
 }
 
 pub fn is_empty(board: &Board) -> bool {
-    board.objects.empties_count() == SIZE
+    board.objects.empties_count() == board.size()
 }
\ No newline at end of file
@@ -2,15 +2,124 @@ use std::hash::Hasher;
 
 use serde::{Serialize, Deserialize};
 
-use crate::game::{GridPoint, PointUsize};
+use crate::game::{self, GridPoint, PointUsize, Rectangle, MAX_SNAKE_COUNT};
 
 type ValueInt = u64;
 
-const MAX_SNAKE_COUNT: usize = 4;
-const MAX_WIDTH: usize = 11;
-const MAX_HEIGHT: usize = 11;
-const FOOD: [[u64; MAX_HEIGHT]; MAX_WIDTH] = [[7174793549527663570, 8797867602282236902, 17207478791116866666, 8968147481621258804, 16266839565957427585, 15355299496193394830, 17936051169535133990, 1847041982698147690, 12485338001516684976, 12709200305929269499, 2868392602073035923], [15157511140222913453, 17397167598054603912, 10041382042055016882, 3442305770230510818, 3570511638565196918, 12825867195665898579, 10164160027118422607, 588164808004805988, 12527934551250142162, 17602796369211072966, 13531810660883790424], [12362849612423154457, 4226474190876733703, 6197964982295007672, 13390213799640371685, 16297272168576500320, 18269430619652693567, 3618614806907125750, 10246039777089553970, 10844146040554791061, 10282212375291490941, 13466290224903556520], [871785673817575367, 6136170508442882087, 16767605039486851044, 5097815444167663557, 6304669330221070308, 8098387759048269518, 9113915083248961740, 330800351509845020, 15762998432150652993, 9140456561775184496, 6883779511077472805], [13940539393528445411, 17886869561270293267, 11913912194383150787, 10631004552878881981, 10742120116853008078, 6820768052466264307, 11775799833563000918, 13808557269129227134, 13022729025394872463, 15412161281045093994, 6375732831837513951], [15353753927996707200, 5512895118336563854, 10042974880280378361, 8148994254380118988, 12310224062912367032, 4269041715610634270, 17757270097856007386, 11706415999817619648, 1796072185886615685, 13134463226641993590, 3117153985951293880], [13870588301207911555, 8888899286896381662, 2870828181789742305, 4632288378500605728, 15023873543204156597, 11848779221168301891, 10653240830994127080, 2315469684440086596, 10792751300233534203, 6702038951124821530, 13453566729662318037], [13351749360534572297, 11000407121162585509, 17838290357231554366, 17723915788986305946, 8493328336955712486, 17286100645685783685, 16894737038581317006, 4758042560163215890, 8320654616650330799, 10925726562298062139, 9069384896005239488], [532963788326641871, 5828544049686532146, 4676566382105502018, 7553604104715381462, 16494279448612567724, 9205058325939589259, 5013333370038700145, 10195265457894528094, 17852529336379487208, 12700469256513221058, 9038115205048169130], [10993356222056718097, 1103070374281498641, 14611808852756624104, 16717828986609543332, 2753472588959756041, 9722855083965526762, 14117675537688138001, 16978815834618681876, 9636291125272819832, 3021743028945782717, 15818677494925112550], [2900221192703912931, 15851671885707584803, 17372881970037805761, 6700003798806788070, 5438511802119675241, 1018064476032430618, 6353059762355284721, 17688713042653186482, 7743364198599993453, 815610247301980774, 14361108260735519082]];
-const BODY_DIRECTIONS: [[[[u64; 5]; MAX_SNAKE_COUNT]; MAX_HEIGHT]; MAX_WIDTH] = [[[[5745648488117711334, 14437030672568060337, 15259342641028514415, 13496944983072776389, 15298574306213282683], [13025135573817257172, 14191147264841138501, 3229987813984981065, 17761597880436937863, 13855957945328429603], [16919096715257125754, 7080788686426013991, 15221008125068442140, 9045417594491329431, 874081652740229962], [14794217519548787589, 2078698663121759354, 1635016240722376790, 909370825118900035, 17662740208925625113]], [[16936806873593260193, 573751707828546955, 16177200851068735451, 262721847012354130, 2424516031536758739], [10719086993307067387, 13952488621043909057, 7837235632209963992, 3030175920059118232, 3684601101632174281], [14946105380827975497, 17114721761153120847, 1584718833202141212, 2218954041305066386, 14594946656586895893], [3946369923991962975, 2412836846937672945, 15079521013879837765, 1298591409854629011, 16646412109012678542]], [[10927120071802672135, 5267479924501608629, 13554759198157101610, 6709958118267500902, 8872984064243700618], [14842064772214457242, 11522189368658353277, 8451620073656642545, 5563975532361915196, 15648309351492320458], [13734412589561037433, 15727292276552092911, 17380040022479976587, 14376551080013547019, 5599890141154854641], [16227470128081268688, 8566254302367996325, 16473055119016835080, 10171558210309451253, 2274037426131774926]], [[9018536631202942111, 10421970678261119064, 18411306846977765120, 3191164691961741259, 5541314849503967072], [10819096515215526067, 7695757922241003931, 5884404075658052460, 5858654626046131438, 10009317780702294969], [3264038448252906730, 11212509935777782171, 9353959919515815874, 4741663027359816174, 10465774258958585852], [3091561919524138404, 8725179354222690660, 1895954924934065021, 3085499990645634873, 246054531009770055]], [[5216305502248285409, 8052555675176163817, 975658304063919328, 10266422759873486196, 9435277930268517823], [9896342229697968388, 4186092341659321646, 13909682924045457847, 5142135658963018554, 9638579846296195680], [1212631934043899394, 7558493674580505085, 11764659243075637321, 12258463596787398881, 465656971867674664], [17151874054564773649, 3616946072230167523, 1220713144886489243, 8056159998909444831, 2171974449028216847]], [[9151231904190029727, 10921781944640874689, 6304563163674910436, 10267298505595461964, 2397247018564812537], [5407808864287391292, 10343807982452805441, 18322624502878368942, 13430150402372741142, 1285630999150222068], [13156238828779463430, 7649981698748800301, 14034672103376168578, 8427720675917297507, 399339719318933200], [3155300862282845578, 9475649034139561172, 8476434838592221597, 4484646559641235879, 2923638660213739318]], [[12078110208190799040, 14361905623270516851, 16070246117281695068, 12543079547782909456, 9823493167207024151], [1052474160074621870, 8032071729598132497, 2130403608553458252, 6452754541634625456, 10711471096691202558], [8334184830523968130, 11034142128314220641, 18075465087337344606, 15692547568438063723, 8921826537977622791], [14675474806393429711, 15501125116305048629, 5043768914954278474, 14135269699641345049, 1004659687830012622]], [[751016216885993383, 917783501717893023, 10413858738098169595, 12032443190532414563, 2496455951996007752], [1392383357184784866, 10499649576376656963, 16371042852521390247, 3711362249942613864, 1640034606194567078], [2262502976274672034, 4021718584836979505, 4563043044086352250, 10192673067623979662, 2108497325423022657], [3647689647542568149, 307787152837724417, 11382989074271197627, 5108762729764131615, 2304163746110640866]], [[4514992717982286807, 3943999176700062302, 7184911724741932077, 7183680931576245114, 1129339448951651354], [14269243998690397869, 10665678154554450744, 17723427576067857153, 2651244402932809886, 6018147758646826549], [8215817650977945385, 12286257043898230012, 8075943253630867795, 18123729281954318683, 11430088316569840629], [5349083807426727842, 6675218989870418656, 9123554274025269018, 2416851277018636696, 15535017644536089438]], [[5699945958303726592, 11369885649300391682, 16051979157945484974, 17473092735392354937, 10088904933362172088], [11781380930066419437, 12035661222740791938, 13565362678501468848, 12695481149490092398, 6757929684916270232], [16930129878061227940, 464548279844582922, 16269929149073598500, 15038530230530945904, 11265444348789394341], [15888129775119417995, 13271345028903347887, 3181089198458681280, 9422132880589204149, 28390915962473480]], [[16047341939648437187, 3711825279547965706, 3168388802132619642, 1647754587168068357, 11460234952914472212], [17570490521961015697, 2589659734377759507, 10060726446489637624, 7289785200265701657, 2220856693665247540], [4785793390092670828, 17341106453234337447, 1710003313764024500, 2617164766310136447, 3868053078949985863], [6260976545542554279, 2228350101647648916, 5651742501609261231, 7222161799204644296, 11817851091823291113]]], [[[8530419489753186871, 14864893145806612411, 5203013536575448676, 13909210719897910549, 4417231524306930696], [11555591116199492770, 18275382522989985805, 8294873365457681912, 10395164760893442840, 9061380668062222285], [15143658386745117575, 18418375945771286102, 6550756849429952975, 17424917634416239636, 4651980658480949125], [244913276048293758, 12846543295633998407, 15679523055842337389, 17018208060784518106, 17011124533905250476]], [[9170544368638022964, 6710895962239838637, 6026028331740255671, 11007934531236112583, 12677678300307237044], [17355648626024215512, 9118215215565602086, 6889914859767018315, 5192478721174090641, 7266793108784592457], [14293936227758809709, 7417617037623749761, 556930568584830611, 2643997457432083159, 12112262302958660752], [3996563768031775002, 14729941262878663888, 7979554987111283866, 5531141696179752302, 7046116432124657361]], [[16407655794263126971, 12173462562736385785, 409792066077513007, 5623271214348894655, 6990454959946670334], [8962022549609765390, 10562412139418799786, 7524535967745787973, 15680265352384221835, 11708832012015760615], [8872735835449717006, 949103075530740239, 3295364638808730543, 8382963092413029737, 1319697403767594077], [3933518800504636584, 576911440048748913, 3121252081666754516, 7417132731442100584, 8357545427430517848]], [[8989398181284732439, 8119563934396225365, 15632298388694540031, 14899154645377610565, 428280100648096436], [17279302422153896248, 10064303082233410154, 11472140279189487768, 6152360148409752193, 5028669402354958517], [18362879850299918887, 7795284816065284895, 9271397315787115176, 9548822411648302557, 15915194233706401858], [5254123163508927722, 8036103442774499874, 10109871839561619794, 3493990849502836984, 8202565104216056973]], [[11537096658048105063, 18248838400431359313, 5349282480974319191, 17768772413255587761, 17819763409227829022], [4524936022072506516, 14334122447707144801, 6821756508614236600, 395482720760409079, 11228019731988512018], [4564812538717280975, 5682587490830129858, 1702875967249246995, 2396209325505723453, 8259707285382446983], [11619298410046232312, 1776756035781606980, 9690169047024779612, 12717803135390305022, 3229646378429551797]], [[7057704935217580875, 4755270972212146837, 14584870914650146235, 159993325495252828, 10986998886259664641], [3348091785138849481, 5117790102302311363, 7657310358424629480, 16859144394250931920, 10646317875316351477], [11665537863893368011, 14079297568388076386, 3701610590747487171, 11352886413319464577, 12205793836865590380], [4418989161353457465, 5846148281365705327, 7653761941852458173, 10870271635681139153, 2701877776579925359]], [[13829855746878922662, 8856602746090163393, 11940746922051463549, 18201611309967839144, 14655140407717006557], [6651515475714279996, 2077602395173396393, 8553050512802290398, 1210001768710628196, 12004969165899797889], [6953239976479778036, 9536814159793583301, 11311266781785249247, 10785469181976201426, 14883608072931046818], [11094088649978406957, 2115922555489368019, 6172435365682345788, 16544532693473696038, 15988088595779045116]], [[15269162842411620745, 10089610177974834426, 14453779689236698839, 15382800395790338117, 243826900023627818], [1342388838054262643, 854937036377724854, 12949170355063699547, 2412033079401627163, 5092984884760792506], [7661940805435571121, 13049736652068765284, 8916084216676007028, 16395654410282494031, 16225966353010967467], [10137774439372872740, 2870394370783222152, 4876462934684770368, 8240629059754962674, 7460258283667983689]], [[17676494089591140890, 594278738918288180, 3990086573759567345, 5315025995341434537, 14068424342818053498], [15826899325108882756, 4635285801494235968, 15442023668696972051, 4690165469506898632, 14903171226777314136], [17143185425622058186, 8183088818001604223, 3705577615584482969, 15503578048145856756, 8793790569297639661], [2075303328608838483, 6651512087268108370, 15586235036539841178, 10790110344826948641, 8658843484631126287]], [[12836437783168343379, 8475701626190095640, 11427504351606257859, 12515959627036011296, 6646566974082083214], [5865199642706863203, 12869644283967682336, 5803076119347730804, 12372437359042595897, 18187271062450392002], [12219070368011562316, 5079524348606756956, 17390419835323287547, 7087309461594949908, 5290376882419534074], [13490393214862884777, 14136473139176999, 14107663366433101093, 1179568160416087761, 10099400626464207686]], [[15305955535080793540, 11534215297218659129, 2711960278042690294, 6527932386324072508, 12009432926173541719], [6766268389554163882, 2488443969015584900, 10613717541435544799, 2626944241296588865, 13275219766505044817], [12255777998151165839, 15794472054433042194, 16734498834494136540, 8432447065727752930, 11351834081037364920], [351327850856803473, 1516087817090092574, 14822808339516159516, 3085281991823931887, 2748190456911403797]]], [[[17203608210967325364, 10261627638979898120, 18258945468141371036, 3582556610141749978, 16549305737909439973], [400857074679056752, 13804303964318955071, 4641157286032934410, 3863286188867709564, 8338301924559099246], [11891962463640719582, 15057731460922634376, 3113279976794645841, 7446755683371078799, 1353581219221280239], [9639020382426694734, 17282386241142881265, 3580306843987871282, 861445839220512719, 446784604432150437]], [[14202516262126400985, 8053455478109267894, 8798205375585893583, 13186891488601833471, 6869609602450913643], [12127958969578008610, 6414350145264326374, 17026782457198856723, 9941113089900693014, 17718769767613813580], [15436613129167711560, 4533573703477259625, 8618772397427336307, 10738886144319930021, 12011107672704216974], [10844898658074638721, 356016835072163074, 13889826751893003061, 2605637062080902173, 11655618658072958056]], [[13734816908254464354, 4519481889517885562, 829014167341732818, 14178219357571857803, 3741984955944620864], [8919269543011460709, 1866873554216285004, 7866664478215278, 18154859685135756821, 124094282477029536], [4575554334598699218, 13698164914908093430, 4238886007016322462, 6556475615325106170, 1235531423804989730], [17669662624280725880, 4063340433215382135, 9777171699011320640, 16092271244252406630, 8403807950437298632]], [[2743724705920908776, 1218834152731954873, 6737372749743728673, 12547641466434803019, 12769589614651794650], [6880395792039319578, 13624530479835210105, 18125613151704782593, 13520954412580698721, 8803722175059834672], [11958032622861129794, 17289121073753885098, 10181165622089918814, 14620525053582206332, 3329707214956884671], [8483610638595562701, 14333088545299714085, 14351458900928621611, 862743207876713634, 4173089545760129988]], [[6422576392194000781, 2416230051334668245, 2422503714288021090, 2926779439693543275, 10479930123843370311], [6101838520964728629, 4567148131500582400, 11123173789084250809, 12689589466189879450, 14365322811969949955], [16539320159258957214, 10537120194024802530, 7678164468755300001, 11751578114370291144, 12057265605521425454], [16934826865572423170, 1340700561387074484, 12545839150093233267, 94261247507840302, 6011836564662115137]], [[5890758957673809891, 11060746778067137634, 14987303306300485149, 6771389419838947204, 5271781433788815624], [8702844531099851415, 6004571757829511285, 11055512720471602831, 8046744264655834780, 4778078161629828032], [14137446020865134480, 2572013405924372798, 15370377887241638233, 5748181342337877529, 3731893920141060351], [2188728071062732705, 15824909219545896517, 9239708031413414448, 1125888459013521264, 4102268521243635778]], [[14713872561050564627, 18176426346616650196, 3556496690903043739, 18337513440405952876, 13985362502431827175], [7189021295896457817, 4905263086837631912, 1510642523394308460, 18211426896091622886, 956637116746701284], [8399901817051100502, 8692127979742823102, 7888553560944365715, 7952053132883752961, 8176181882472421443], [5774698690869511634, 3694978588658176650, 17029950238712867073, 14593834076352951267, 5850471391301655297]], [[1873182143920744364, 5137692463971145477, 5559635520165885002, 578850438209268788, 13292978350528320431], [8761745251427260287, 3774089357051879835, 7619170911096559050, 15259128957025178383, 15503825114070922963], [3919231891628544692, 16267919215517333222, 7331097938284093297, 4077556692663063993, 18398725818205107364], [16253608518344911014, 7010669528533383672, 14401993339108308976, 634504391296130866, 11141938809047914838]], [[7662073804542602397, 9929673827361830064, 1601680666621232198, 6162818224464496702, 10835464349161030132], [1957124330292616603, 4072308538086035555, 11807953346157944885, 6605944171363907522, 7807354669948624312], [3645353607906590303, 14227086160711826084, 14570631156708065628, 5420788247089727638, 7566484303199125223], [16908631796155674467, 777626505202299624, 5360608444801626235, 13427559224127583827, 12593671199168883258]], [[4547548115590203290, 17358904305391995269, 8236410734852495167, 4783778628086442013, 3966071747191150956], [2009317397832369, 16709954454113956459, 15974265232865727121, 7679144887675001950, 2891042865963798124], [16709433622324832688, 11642494402090439419, 4687285353657151725, 10401653075143144728, 12820428526939886311], [278587401575684420, 594659705284043694, 7141219430151690758, 4678377682145737763, 7245449289436484048]], [[11751801573519349245, 15170851400296420005, 5310303314249753141, 3116695118147535744, 12580183331322774781], [17653478148679610347, 14347996633432225657, 12803853876873136116, 9730188605824971784, 13828769779009228032], [3884556416153752761, 3572975997878486434, 12302012901867185619, 5997775850219212098, 2797113018040051651], [13844804354905809461, 10610075793037471834, 220028461848933848, 4719110587999039910, 18381207955734338520]]], [[[9630587985130957540, 590042926996288109, 14861908092965402263, 13652306767535587031, 5389297575467624875], [7573453237893276648, 9812685892165827685, 15479709030685091547, 12096041399240302323, 1178798513704061311], [4460627045742710710, 11769739054470999801, 8948622153862341361, 517620442814190291, 7711587495921071924], [10764388371148691774, 13165250214509732383, 4672679589268203839, 1244690398041621100, 15701715495855007515]], [[16790766245673568657, 14138388527231814324, 8174685237770151554, 6724461857172594449, 1756529543633057779], [1568097300096800591, 13778582897357552704, 15639471512348889572, 17746185475312756723, 8342036889772029748], [16384320557135188095, 16580043251273577493, 17274722885147602157, 7676664585951150852, 17206131732074693354], [17847278142149013518, 10461370972036265952, 5335949010739293578, 17200596155848305087, 11404548609915084298]], [[6521078915948060657, 331714957348853573, 16801618722505758648, 15536860021332019146, 10764854860414626325], [16974217171317323460, 4826969740374652189, 8290386396279408255, 3252382176046771549, 12087512422321744539], [15661961265798823000, 6578678324500670560, 15981907910438200199, 8560755428663852668, 16500636612808633241], [3242322195926304056, 1712633040662922448, 1855624194352883825, 3114224164631150462, 5707031507434320813]], [[8492962481193094883, 12830517649559843979, 4911775472622076705, 5434576126963737104, 7668249318771677274], [379344363935125291, 17094145269539025675, 10261264041799565565, 7336263475012224728, 6276383946200458377], [16757051034342287263, 9247424232294689751, 3834476382084954552, 2032944057037807988, 13152721438926920457], [16049789571247588188, 11669580187183099469, 6611720050474683928, 4254281804322608964, 4868949726197119151]], [[10436350970170606321, 8572962259666890728, 1941834875985498625, 4588242001495294814, 5192420429975962680], [8793911527233664312, 1234210697003549945, 16259561727465108788, 6334257957335742489, 11888303877153021115], [12832864081258316376, 6512752853774259300, 14494021829710034475, 827313819209495710, 15831110517374093618], [14398929368833158898, 14660563060539407981, 7588794857554065996, 15055647988331816796, 14551112131551889983]], [[8992590366236191678, 10784013312756057452, 11216571674869116298, 5078783898913306025, 12570245946944593074], [9239144318871389490, 16423189028815445571, 9109441362984730719, 3495887805281452193, 12266725814576008657], [3348556995622506905, 15163975702435441897, 10485222283953775652, 3774068777283338933, 3510461894989456713], [12012942803486155600, 13058318011968733124, 247595962621018770, 1032371560077010649, 1451885675581728316]], [[9218035620938310083, 5440129099976433278, 4129978118999257127, 6953515737116434004, 15431359738680647787], [13851846228595744144, 9752288544162180221, 7681720847270196450, 15282384801858096278, 9955548562140473150], [4826490164496338638, 15135849737040558794, 13636063185414299662, 15186379956065028798, 10908209381248856944], [11020714334457770937, 14785258829434976124, 16289985129112029386, 9280055442685488861, 5507612101420393581]], [[10921147002072198200, 5526262720400915306, 11181216432292932201, 18142992079032668976, 714585685336573949], [14434621179591382776, 12769318921756156589, 4457243707739392486, 12589568006855507069, 4475845237147287386], [12962271582660779623, 13750847442752339389, 15683401383272805165, 14986041628186616824, 17394969318117559496], [7776790299556038520, 13791430705360362552, 8813606203830182772, 9537633975963172272, 18421155299296413979]], [[4801258398344066225, 11206513731833201194, 10256680819909225265, 13401562436450793035, 383148101874497783], [16968368732622806076, 13489354049329740344, 808059755013199474, 5092624896255578397, 6087898100628537836], [3626826076384327723, 5943943634420333861, 16345947021412961033, 13734320104332797576, 18442460105315734546], [18158135900281775207, 7465000550738285813, 4922183001409856004, 15815057749886183202, 10104325609011511887]], [[8856769750996499007, 18022503917836716570, 16102485244994513615, 11837236159713747332, 16720067560392653529], [10330215215272710402, 14896495395177505808, 7146755515771517960, 16679110883362283143, 1418165823419771709], [12239526033654623253, 6097766182386157346, 11834040675111138007, 15451565053675685579, 14294721062257992550], [9806629360735224141, 14230196686120393787, 9076593190318247134, 4432107243058548609, 14896655886171833274]], [[17897320053135617147, 14942697100048058073, 5609799392511341406, 8031869104892668456, 14185081354734140864], [10920933360729988881, 13631745111825849123, 659554205995973166, 9391118178891745132, 3091693698365697397], [3810903684642902526, 15651195512283224574, 6641027650131297400, 11387702677874333427, 13139186806175133169], [18252390544415714690, 13598780226054173780, 18441533179397922109, 1427634499987273472, 17316954609653306675]]], [[[11314795217267737494, 1301842191613745905, 18322222407361173564, 1710798829151480136, 11249313734258586392], [4237479364267349367, 6469230034104135100, 12194877737258752183, 9748372681836128788, 8660491963213817198], [11834543155861147231, 9592893901084060450, 8697997662380893714, 756465122146003181, 15481825222798632654], [9860977076364988423, 2014710618953987571, 8091417938067390663, 494031262242632342, 8488549522981624931]], [[1658679813356678773, 11940485526202099095, 10699886021777139194, 14753225425128684047, 8276821640486628969], [4667046121230451647, 15327886178193898005, 6490406130339443654, 3125299471310015733, 11892025189923445893], [13075258755483543281, 11005268381160473439, 17482805186249680561, 8988142402314959176, 17994974854629303743], [2684337997245440027, 16517139106885565657, 15047806702873854112, 9501355905956700775, 16496429845825819575]], [[13093976635756668859, 14659522111073225442, 14712857276472122696, 1762184046732246040, 3387647574844477520], [7591697867654255479, 975840910502976138, 15603874636087835449, 1299424010645789827, 3934149413302433726], [6415155272547162992, 13387483615248428060, 18090858173228094394, 1549815889637906877, 3024047562242496983], [9179830484173018502, 14919915182342919644, 11551896686377271773, 754464939552070629, 16571960730572951288]], [[13987975523043794703, 15644614856478233826, 16013272678549786784, 3137803077049383442, 447908711959624741], [6784200721945855309, 1956234417167690390, 18045020109794473707, 8332930707818475729, 1499236666494830692], [13587333893446222454, 4546899047663291749, 17490283037924827190, 243812528692848744, 15744964448596651660], [1193135379328557722, 8830301993348689457, 1469344607232239878, 10894702451536305229, 12052554733051214174]], [[5805657564903655242, 686168528130963724, 417336756608642603, 14266625150786898706, 8313845255478460123], [10495826250744242132, 7418934128897605880, 3678753292985184846, 7582500047153413452, 9947929570102707034], [16066419611705214331, 11913096226160875694, 17060589979031490532, 3471489728201377667, 298938262690460144], [12274821810030926985, 15330432045844045156, 6259846218903753309, 4820627667489616735, 5421799094523409640]], [[12908591242391237547, 15721282864732328275, 1892587499178267989, 16648311511276754399, 16306357822718754720], [6748681990553559481, 12504747050545864224, 12761720654885534034, 13272662828097898229, 16002434554995376937], [6550452774743601227, 9517304673663639602, 7348167555176239586, 5576869136883862162, 10802990109806055790], [4163092143333674390, 3320869782249421057, 17544240682897876534, 8403647252094250269, 1933890769150030]], [[8464851732818787760, 3932059072402331137, 2770291596681238306, 8413824372674723806, 12491839363889271549], [2595356872787100991, 8335926914720446770, 163339876485186506, 1537494964285423560, 15242511950827744455], [13373907988316040601, 8095433822966704997, 11691028992467624653, 15313208863610987037, 16275905119292454232], [4487364401245400077, 10165308977301629119, 9998242050400941869, 905336511145300362, 8240399833264094601]], [[460108619857192152, 14829262668178598730, 3304154674296548417, 1968845727083785794, 5425684901046747072], [8093813986280905967, 4081522974532009583, 5222645627146653180, 16247051956703240554, 16518965922136312424], [9815657813747516700, 10164160068591254095, 5708724600828245297, 13239225904419387392, 1453561468833244006], [7481785234907712391, 16342567893197876077, 15224751440716800670, 2112336125063144372, 16187392025726293598]], [[18257360180815929432, 3172686873067299415, 3096387516606554571, 12029419721975623545, 17893458740160405644], [4765812520627211743, 15885119588861731021, 6893484847281058713, 11993232846614645760, 5585071699780607088], [17145189550440363576, 10798294172605966376, 15963677574708523187, 10610401028131509930, 4320323131926583374], [1172991203041827081, 15856560256465142811, 11959569910832091905, 434450608651257763, 17137789979777663253]], [[3197508895166600456, 10005773551511651112, 381062164774948419, 6274332274994659288, 15698712645832395672], [11055365454481539555, 15532813455227678858, 6391760799499417473, 17218693268533820652, 1449357084250729764], [7692103892849053178, 15026633606914668932, 7136317159997530438, 11004146185722843481, 12386392210886733259], [18170201422890446151, 14086174473425560447, 8966672485812660783, 2283841296483153890, 13311894715387331562]], [[8865147932617426522, 8132980828310302982, 17046055020723322462, 14950941894246269151, 5164750123072287557], [16331657806027830874, 2050281819276235887, 11399614771161074432, 15988913044883917275, 15860881397832946582], [783882601363539133, 11400829620437087128, 4971348011914035390, 10104237353597849861, 6043936708894206585], [6734832107008160396, 5051129672029475447, 6953125456968366602, 10728645104125515845, 1833789447744021288]]], [[[1413682989127809853, 3504965724575350707, 3789065193882009446, 13016762678738331495, 7786524852770950391], [8900662404233452483, 7770786176719447521, 9059408702134106290, 8243756046505042205, 8131153753844062379], [18438062317837589352, 6591136516754434835, 11941428159924292512, 7669163831205507802, 15623040536443132580], [9315529908719909346, 10982532127990459392, 11157787500432946902, 10860700505785707707, 4688873678937989259]], [[18443818858359031897, 10661246849018872372, 18089844210468776803, 15180932310999488839, 2535497020161634521], [15911164221927770795, 16457999892833265901, 16104382858077038024, 8883231773500555453, 13736883097436643013], [16907977954847451479, 8234259947520008921, 16155508348544089282, 7265631449667786972, 12692191407767716410], [3811947836580024107, 14019754085970385963, 18194792958494240832, 7492689951232347984, 9851711651210470827]], [[9301677475807540204, 15039774525376502739, 17028541995119341528, 5914705427472153099, 12366878686644303051], [14233056936248336315, 17247055216484605420, 5925242208993935689, 745022907326991735, 12727986935518394170], [18092846561878958274, 9758336820320465684, 16553701163812015642, 13559251295357377506, 15656120729659889373], [16564374634305575859, 16785288831183514366, 9117421261763335773, 11323142716870414496, 1523119614247295624]], [[10105538448453229396, 205315897089587398, 12874699567372626515, 1432208122028986970, 18338950218830932220], [4462418148508036617, 8198259738435750211, 15337999637334718477, 4437279973341845424, 17797331372292066717], [4127064933428455656, 13475666070535973877, 3091152011790068415, 12252760414155506845, 5656624199738800295], [3766811747569570913, 13517868502221582311, 8581376540406867235, 6380781340373776770, 12177656333679877717]], [[15878994745440617016, 3503830879549680982, 16011474695479259327, 11468900494325471384, 15765423948081727357], [4215274267891380798, 17892922232797722634, 8527135196615654348, 7828527142541001637, 15725197475354639305], [11030490186372301835, 3868423033493883924, 395354091906074559, 10706871325376037360, 309912458947508073], [5588559858908615629, 3181604357499589168, 6050805723579821194, 805124292353689501, 1327924732008780024]], [[12888896264093584354, 7722988260679098914, 11524696340463525450, 10759967018426556014, 12518519071421052159], [1128327525134253564, 16149370028801670229, 11116947833494265776, 4480376378270516166, 8841486692931468709], [494185404227949736, 11568759529472399453, 6800329883938306350, 7530839194615396441, 16273193115721532954], [14652628871988635592, 5802984766957483650, 7635655824378630802, 4110018151072310961, 16050316448700524512]], [[6063744826658385196, 17696046503900151182, 6807985121402000709, 10930809174109199264, 16130070431972215144], [6644156389316483946, 776612228616789616, 12886663655708500567, 570069019397991716, 11296097726730899650], [17873736454892545766, 14172361396750565227, 3626354815143636919, 6353097168163423838, 15117279004771523297], [773840727712917279, 3203304146773476355, 13136077170493814052, 13215607231744959414, 1795653133478200257]], [[5250208955467459423, 12502554995405845401, 18309113393360613921, 2960483777294945634, 8813642843358791050], [1875156218319578048, 15666976718268940271, 16279471316187745174, 14696521291782883880, 15700750382667651848], [2924116152675852507, 14552963307811413843, 8524113563746431422, 674320206807760470, 18439520345962053149], [374736875296690187, 12467368535865146587, 18177265158544556604, 11351561791989599754, 3453720267389746913]], [[3745757211848207017, 4423951016714765506, 16843607742273206032, 9288229331046556056, 1858627988042824], [6092078481907023341, 3876598784059476646, 14477622969483821859, 17327419458943161798, 6553780694379492304], [6735456977967231655, 13881786922152237020, 3693619262147139614, 3188858258911807534, 11287599972186428989], [7324727904258854415, 17882026513965302724, 6735478629004898845, 13581135021408607377, 13352939211775259422]], [[17465755553512588862, 15052388031176004277, 3892376104121386139, 7870161385183093116, 4202753326214677089], [15252918562910789061, 13819369510562485880, 6547211522233462984, 10202528608487009468, 10068153435313330505], [557617629375651238, 6176245322152241869, 6563103678012641305, 3890500731347401981, 272188450692936083], [16734193771494998062, 5417681479967823572, 16153077630438868263, 15247439172812484575, 3207776061569876640]], [[10132967459075325197, 4676126240458189940, 45185918658494440, 5025667021274171955, 3498165609553941237], [461171712207465101, 12647536029989527288, 2865335198727680780, 2815986588070717322, 4954069204947921546], [4727868734382226951, 14267055397230795352, 1397405351957264584, 1314532874327614790, 4108279853468906716], [8998550361978369630, 5425584225659033332, 4167048037160989533, 15657852199050516404, 6325106782032141351]]], [[[7560140437146705548, 10424265694326147975, 6394431096485804648, 17539207317986100682, 16740563089338891550], [14517318098103522354, 9170883584630152076, 2459656261608735218, 5988472053027509697, 16945929824517841799], [9563946302335227382, 13235918741448461242, 11100740829506020481, 5340698432972318516, 13680531084717980102], [11966592537107424003, 6840475819179396110, 1437388934726099443, 6394442272296509, 2520237280888998800]], [[10927643810876583348, 5308803433962288242, 9479340283848703827, 6158078636005585989, 92848928299716934], [13753938610995199341, 7583641178133039339, 8113190918747070436, 9424451769484992910, 6702401414832495765], [6749659604472457621, 6715898064660272335, 128108730116810670, 5590491359220626486, 11943268090052416195], [10007783950263042628, 350090445904920915, 7883691547180165223, 3601680861922265108, 1170449365225232771]], [[6852209870155686609, 10099390334188028084, 5981791541354562275, 11391882954409302303, 6186562754800420132], [3255649568730915977, 15494427624732273203, 17533297871517396454, 3834856426082505322, 12556770850583400872], [12609151336837849332, 13819276746354227848, 10027419431183588291, 8301913075743789925, 17319434575302310182], [4031183393718345831, 16095819964323945513, 15058178750874335144, 6093994467851444031, 17029726569594717150]], [[3082434992137873352, 17772764824546108495, 16347609937612026362, 6206262451262724254, 3588851673711998765], [2038149584054710462, 9150492698351376016, 13528813670077918029, 12122247685025672822, 14820507779002073456], [9424469492662556770, 11861665898539175919, 11709858574315986141, 17997637985444401651, 12278074721789505180], [2941415420502706238, 3681608148199511489, 11123048263089705051, 238163795471974461, 14893757599699682760]], [[6512341907872171145, 15871111583004164835, 12640666599544484300, 15030715219484029191, 17020232982426727284], [8546026889959946229, 14036251566381852839, 16888104934390529726, 6869916641444401317, 6687759067445482236], [5257702627926727275, 7062729783919870123, 1423579539232410889, 15727677533216913228, 8852551637062989445], [3645267750697436701, 13071537816044561624, 18405688184012098850, 9490221475597138633, 14690694391013648194]], [[17164759673208949387, 3053181141863610056, 3994466778150980191, 16430486302617706905, 15275094341280818040], [15080510151689599486, 11724605800807604938, 13145995873538654857, 12280638069036923793, 14573767299392064878], [9219649151543775130, 15070108179464006027, 13518217993930859899, 9414977982441752163, 3970236203916562317], [7493473376208586587, 3452842587719722057, 845292395263034385, 15791749319108135028, 6854422840527117176]], [[4853045426010869239, 14453701482857986303, 1007731680234589099, 10933424100874230400, 15175415004614001202], [14550689153034120937, 14437717606481640829, 16820227719408816691, 16895664428354695013, 6923288995598668125], [2352261754304020184, 16038139458255728868, 5047756762295203290, 14105086654161530299, 13091007686984770648], [12796938314835675854, 6904098779620435133, 10947189046814951478, 8121102989057407528, 7253604290510324078]], [[17356997497244488115, 12866191676569044769, 13649883285772350845, 11601679447514747645, 5668513096743188425], [16711557946801855100, 15188485594630382301, 15043366584077092983, 5536474590106933831, 2475326249454018251], [12611569121629184371, 5638278548903899976, 1216755132127460254, 6698812418004056583, 8753291533497892878], [4720532319582432835, 15981644304457206512, 7218896537502818882, 2944942852449858646, 12586174507672350378]], [[16877380416730624294, 10497513550573836215, 4965690847797717789, 632896438669622761, 13254141069003501953], [16240601768561811302, 5652855524624109851, 7265009754337495209, 8781518730239173262, 7625404150505302806], [12680686398234250752, 6984328401292881628, 2042236287396919274, 1660514097396712419, 11917307219688353334], [2518795184099869856, 11626873161808711479, 1818064727599728908, 13365077467943280332, 16889165388336535678]], [[16513155240389834359, 4604554210403394687, 17941201466135646044, 8746395837468699121, 9372582342694205053], [2037790048844342583, 1327513933987900551, 6229533476192246521, 837709935902516340, 2121847977354491906], [14452263748020245983, 2407268780349227123, 12507568206033079484, 12581849372735632427, 10864686103761775863], [9888703895391420075, 9389475243406532707, 3221679144423615639, 11526118757124393481, 14598307813351343387]], [[10994688901909428278, 2449820621221561637, 4205865121958845841, 4567815036992683746, 11431742945642404382], [15733822176490999815, 10765475273043009113, 14887366040026210091, 883657176144834400, 3707850098910992098], [7544265362477729813, 5216349840612288749, 5457485215178924211, 2839210539733944448, 12185915813630687501], [9100698732088487515, 16518212839851040375, 18141485717556790072, 8762296746982066785, 13292729146777971427]]], [[[10433935133080366708, 11572446189039131533, 6260804760157786335, 12801795368014535327, 6524854772784127132], [15031698352418551772, 5050731734250679964, 5312439127434057138, 9186584425015007734, 9380885834391867921], [11386302397271774044, 7078949945001978804, 16103914496430180019, 5979233090241362714, 3026479841229362285], [15390369529048078467, 15037615767324023248, 13503045095540650592, 467681604914133358, 17677636449981487354]], [[12566965030998073697, 18003934903489422513, 16557530136249386612, 6149583325303783615, 2472750995165043756], [1548517240800020471, 16334435826435742032, 15745043270510526142, 14422939029292266708, 16108504840010562630], [2093572397741843928, 11234649109984742661, 9095836990540564235, 70471213821954641, 5508252493098493760], [2123613932774291326, 1835218010028710687, 6344737343355636742, 4785161908027162087, 2724250079157138845]], [[931235590055328067, 18284503583655257123, 11378813448663101453, 2089379698867616773, 8621773672175025446], [17101511443722875721, 14033957061770330362, 1076234995383664534, 9096672074389098748, 4438434175397362424], [11137810453846146365, 6359579591578602673, 18120549147285966231, 8175592498192793046, 14810057795969890681], [11468951537416282700, 6068712681355158361, 8376161496780286755, 726872430053980278, 5754799568035765888]], [[2743449403923316468, 11969880004806163289, 13007405721478317555, 773553212793189013, 8973508021918837096], [6629146795800382877, 7265402304937160878, 4541467899429720731, 8186294585405999878, 4959162969318831688], [6576433156262321997, 4609738197704232644, 11989236462498887150, 9001761872456352026, 16559580162676997446], [8588664953016268324, 10866003431366450444, 10952433729847796483, 17811234035959299559, 14533181810588028526]], [[5235820689011451560, 16412187940873208449, 5375355991486218321, 9285638641398614750, 3220980330709888685], [516620679648713607, 238143082171897291, 10696041623631483731, 11450396074315917553, 561515600858953904], [17898289526021913926, 12681301830487368896, 4087288850401527189, 5783311094049321071, 2109625589907406865], [9404038741769117316, 12555741686628198190, 10038026929998870758, 6275469146164245112, 2042120296335056405]], [[981869669540740069, 17755343120187433099, 10924530442193336156, 13454379983503223492, 2612383287462290516], [1907870978578821195, 3557067854523961078, 3297264449549008862, 2075733302125467218, 13046862480708265780], [5099563833754480924, 9838881327822230033, 13955384399279347588, 11220889585747789316, 7343961429665509909], [125999389942181226, 15912219167790231324, 7403619548394552959, 11599097040694287654, 1389236193053648801]], [[3226071974932990115, 5202855733225438866, 4856530524074382171, 12570797267519104542, 2955900060225514082], [12910652117433071331, 7214304129812499942, 1732621497367537559, 2733645662100469537, 15389966567257339068], [11758021023761974405, 17400248504061748426, 1908785606027571647, 11325706571369080101, 13807666096165999163], [252205903226504926, 5456274461467654249, 8575733170247911309, 12369304712848082916, 15890846724598681330]], [[1361977177559337863, 5743236022876556076, 12454738739148240861, 6240292365090002752, 8733890511997510681], [9986984317337307747, 8769514950956162378, 15955788145074468464, 7399027324507962795, 10101911675016582241], [5865276253010220771, 455282122657337973, 3210884364247978611, 18370398203877128835, 5549899677459954724], [11798508128984669657, 16759498039284110422, 10516339248229915421, 15074875271403855718, 7388023630282921618]], [[4907437866262555422, 17374256898929298042, 9300754166570641922, 12553110237993311080, 11700789221581737619], [2096698469599344292, 15900801027980423421, 16506220212396955130, 2759763302518949730, 10741571084879988340], [18242882757606098012, 5970305634169474054, 11379054094382474709, 12017182849935325302, 17416773655899538284], [1062004765092187843, 6953624411158906147, 18348963567882002850, 3496431900173766507, 6308916982525868727]], [[16558025508288127760, 8577471363612015999, 16426267125176372878, 2802792208754727926, 1311703311800249446], [14312948504759280484, 921903409814751227, 5142522201347185529, 3000813967368693167, 15692192945671932937], [9439394189652428909, 15555790093298868143, 2251632751109803223, 14000626525004141887, 2847270066630232717], [16813025640979705485, 18043135148149120657, 5850465258046302746, 13543925306237054350, 10160784622172362356]], [[2315250358404785650, 6292181443825433007, 13526749703241670399, 10793186731567006421, 17243207292907522776], [13489307764986198108, 10507750945777147524, 18281635708175227605, 11360114803634929445, 16703734371173720651], [5648550763313377577, 17054203451754492176, 3857529267385884815, 13428823411240083294, 17788155519491825120], [10579431975210858521, 348157945765628921, 6510352649126506778, 12683310850473930400, 10510672583525979588]]], [[[16409383621441733468, 2562344462179370474, 6412152365019201360, 8832945805664273, 7478214827504334084], [3535620461926229945, 11018628052380742904, 13483717571015356407, 14863542837184909344, 12460584371147563861], [1415389491523759895, 17868403201547046333, 288753655599583201, 9330411544602142421, 13527008906598936187], [5818777278706520330, 16721424346011678279, 10594794727009442824, 11207366039384642012, 16490504726722044439]], [[15737069373749989491, 7194322544615577452, 9270947809660627275, 10311912221620261061, 1533727618668862038], [5396205664607789509, 11094193275257369514, 11204737035693188971, 11366040059859473058, 17923266929017870680], [1591737106045099012, 10329948185786255492, 2736587074885047816, 12601129722530108642, 901538047395314576], [10732216659977639703, 6561719998051131599, 12958486550846551450, 12621593960730114833, 14071480514954604681]], [[4666155623595903620, 11863756261080202375, 9494384035802046723, 2248068073084172747, 14603491308085515602], [8499605901122191423, 18119905224480097994, 13966739879414052951, 17530768826412839431, 6046447361498147349], [11584130415940787518, 13486594510566226253, 9329857653107641998, 17442932995611697450, 16920529808209200064], [15367280138926526549, 570505382393515888, 2461156893723874, 18067410636826803075, 9532468542211556696]], [[4816471986080548089, 12186655472429044783, 8963947618753789006, 9780494638939597426, 5522305406164637346], [18300651117817928790, 17942671116645193404, 1887650402431899566, 13545119710244933697, 13496627445560170430], [16039492841700133299, 2260876082478577826, 6974954012463944706, 5312431644542212694, 6611335473316179277], [4865456826320041267, 10388528089497829235, 13646759255131798565, 344810908340215039, 14594194261071986690]], [[1961289338256079106, 3778848649682330912, 15199295063695137850, 9639751375728566086, 13174665706681049435], [13490337019306281085, 11338211626303093046, 10079816254586985026, 18005828988299746632, 3951171278362335908], [16760660975375675155, 8902471743608451565, 17754358882577227674, 14470144291785819679, 6094276507905958607], [1890622857529450316, 1980609513960116582, 2269969150970965508, 11213379837967263280, 10814076359124579377]], [[1145776684521043757, 2986193488435918769, 13654394558895954451, 17082589978848497889, 7098176837718421901], [6146318175173523036, 7664284451296991602, 7287617767385211792, 14116038088891650739, 2595090688501234318], [62176832336643988, 10955838486573244427, 18187101303963621161, 15154779431806725343, 14768518943574061510], [13784773302311211462, 17821261286337920260, 14448606796663112565, 5092240465067056752, 5295356250037044717]], [[5782013307094909388, 2243698596676827709, 862021677292433473, 11186648324503189671, 9857170027533134585], [16466859905880867302, 2536425112498196260, 4425322422473639310, 12631701872077249144, 11144098278297508054], [17631869959450663501, 12646913911775557439, 8612320724351365324, 18172325582763959304, 4822868342473542220], [13399061243036049557, 3576929911157848145, 11501981180992872729, 15132967162365196016, 12007797752048639423]], [[5793901918510169588, 15336768688903153141, 10786199627451760595, 12884401119732290203, 6017114053914002294], [14982239924370678589, 3209833753138781159, 13350693810392229490, 1455416510844967324, 9495852543986804709], [10049435275219571413, 13583295171957910569, 10117732875488928179, 3948866361997255060, 4695036367012162044], [3532189135007545856, 5833333139207248964, 7524963183198433558, 4523197608584391042, 230038109172615329]], [[7944339663621924480, 1117756319618340823, 5481961975104458511, 7973995066837178639, 7531832323275492989], [14273934205773170339, 7529935687325969164, 5214647415582391945, 17809906757322120900, 12381806929833753486], [16278536057073260944, 14276187167932274583, 282824262957161940, 15756843633667910008, 6823835010166021884], [9673823828490967934, 9753811192009101306, 10747439403698546084, 16752343801229384523, 1111211407308792208]], [[8041459492807871488, 15752529951107609675, 3641984263553936942, 6954434451944991887, 6583247268461337912], [8847281079118136873, 408870107756546676, 8302625578373616103, 5328205540872716194, 2235189728877552318], [1173700126548900949, 16644619053995647671, 15456717539609343864, 12065316917805964328, 16169222321688671018], [7247896185502035348, 3610686832419793640, 14583516233903244907, 6183000639999849263, 16958623456508899476]], [[6062704631657259215, 4039639397425609905, 13052241482256341385, 6596093579619890575, 12783151052725692420], [306814715451027192, 1951714221613033939, 2592133679347646570, 10946082741819735742, 1868604314191613340], [13484598999828500583, 15603076529461629815, 11407397145407621856, 7865694854217792256, 18279212256334554458], [13850378934606986821, 10861008350438857947, 2803548195440915264, 14765561949094547520, 2220904747069006769]]], [[[6066938318513836907, 516205953477131115, 10803630264433929477, 922204109121369326, 3919650873770761257], [2195458328180362786, 5369321673267027120, 18062341088011209719, 13070937825908197389, 12382469803780348555], [2991657615334016578, 5980401738353815617, 17875510231650459725, 2462781130216034924, 10097384926516834038], [5896783364719587221, 6880759283552159913, 2045836143063531188, 14282513017700720507, 16858554381219197672]], [[3019235557336824598, 15098707653604243761, 7357471430888267431, 3396280016555403547, 3062657003827910745], [18021465875381576600, 11828364197954736101, 2416627537782669344, 6162882808294176043, 16471879493616246095], [13710455675254856237, 15635611061081628387, 8784601710903517658, 3760753658109609605, 13063212687289195159], [2278429028398875297, 2274246259846006509, 3619201188566085445, 17759122050292387438, 2977955394661905571]], [[3354823745620068825, 5704289921398595817, 11216173278084395673, 2228345488847803023, 3413083898859356318], [10777073917424966264, 16324606036280325696, 12729239276916983077, 1625252367777166706, 4678296668251048205], [10699684882008798678, 1387867731806937033, 7216769271242483341, 8233703192445681507, 18428209307546335189], [8748059458291448114, 15252205793235153408, 4371735888829650292, 7406100004274498822, 9511291074936806672]], [[12169683619827430040, 8374255763313137910, 8784839293451600155, 12150627673941347696, 11415497896237584230], [10972624201286339761, 11968240517019244455, 5010602067984621196, 8626123437071022964, 7851568780542112013], [15037265309894495996, 16623529597637378817, 9510998698003294738, 16016850500629895657, 17098909103398238910], [11266973480958876190, 15913286553338864527, 12326457750370479953, 9986947223260922610, 3149357247515141927]], [[13814526577268569603, 8488550595832290184, 4142275724882121172, 1058074443459328715, 15734188639536208201], [7792034064711915626, 5524187511902635721, 16929574284277991692, 7539604163983674737, 2797624684615984363], [1775162147822030060, 9483819639523641865, 18297316644131906678, 15015079206471463851, 13376321053389197000], [10929075942627788279, 14065366091310700203, 9088734885271304604, 11126447888394247809, 3235941421712825589]], [[3983546120641926624, 10226069939919955059, 17735842390410069023, 9542686220864527182, 9331170570969228211], [13587589498793100163, 11752239248588839802, 7081651242416005403, 14599718455855743893, 17234958081111512577], [7449497939558192930, 13974826886654523835, 376479968488147303, 13782631237004138357, 4360924225523402914], [8378821874986788414, 15407767702826310924, 5854668845335874759, 12096950446780577382, 7171930426647316461]], [[12016662578413837966, 9139554038567486395, 15256079265584331214, 16928056382362278937, 5271324481220398582], [15905475154247374481, 15216540621574408293, 11495747294873844170, 5722975101019863203, 1758692644086980013], [12870783128978732965, 16400293225731174135, 17173065694324614550, 1171639575515407586, 10577923328146421612], [7458756457457659447, 6433275951969054437, 15686042535131096285, 11649016210887337596, 12273094133042433930]], [[14935675328491319288, 2558963126930375611, 11311765006188491746, 15723467151217534880, 18318244718434292159], [7625298655842172138, 13789383332753756986, 16908482187194764518, 8474998271759999270, 2474364818085699659], [9591068025689962069, 5950374992566017995, 14017298591368774803, 576563929795125379, 9652836773863236372], [1172859129041811276, 13513472008613475435, 1092343289938881897, 10320857247627913113, 6855205428581381166]], [[13276139122932377042, 2213691343436584208, 17893248676497504391, 7152439015484092337, 15121557555970276745], [12776137094677761375, 16126896592982694112, 1649864012588418437, 10506273361536587426, 9103662248729027156], [3399453217491935648, 16528389340877307552, 11109144559088896534, 6142993301046272233, 7522387780138122416], [7573981134337361414, 16660609939235535272, 6563175941863942696, 9222355640527643464, 6852121907144469149]], [[10018503085941115441, 12687018922119945961, 3023992924348942477, 10464121489891183876, 12201039006870153602], [5250266286647760867, 8931535298024541481, 16817472383395290736, 15982913882594050763, 17849199288032794635], [8640288046926331842, 10575406926668891346, 17289081435418145351, 15443851585974600458, 2217628616072525574], [11669729727357143130, 7681483499161715155, 17254335523837147129, 10955717055876378937, 16642850897343141405]], [[2214441974050505072, 13860582454934344691, 14137810389048534134, 10084840236819719731, 6306856842123546525], [3990231792567326560, 5372600687368593199, 7518726659285315876, 12626489751876464997, 6550205605801435094], [2624135092833229525, 14915520769196101125, 16211499682602241788, 16670094267103058216, 15064427431869339403], [6821483070944496723, 3188005403780326159, 15242177781450961746, 8137241868137644740, 9791242711746737488]]], [[[17530255593950259094, 4217740908944027516, 7748806914183170868, 9358755299414916059, 13610022078233486963], [731342840210919118, 808906660301124320, 3693291646141986129, 8637588053287781601, 6960448119618058394], [17421578314829054788, 4535198304095432695, 13916848793361831947, 3162946523983608286, 18205082543791325642], [812022568810467905, 1439526595046579625, 3724693729774314217, 14751886793737912946, 5392468038952841999]], [[9532930492112920972, 5471398981218750234, 15802090514122058809, 2279304448645051154, 9212948580976219699], [10865239529732400675, 2856794960471223190, 9925316851973113407, 14744593599193499054, 11483524167468504197], [14722136929048955480, 12903472467093396653, 18294469091101942566, 8561269390918782585, 8625157955848326313], [14468314667906824437, 16474863646417691475, 16249020676819880513, 8585002660686621739, 405864836199684243]], [[14060839921408074043, 13779087224567223629, 13703471161836463684, 11207765788235716573, 13867761269223340823], [5291170748917590349, 10534944892996142873, 15819617298621252348, 5170008456441898756, 12502348972986299254], [6266346233824967769, 10133868765501788270, 4625335388153756650, 18336154308527497194, 1434860176407328405], [18286280039684781564, 8252212869875430161, 4790381113518456274, 2634296372483259881, 10834514333479243110]], [[10400203088745539790, 8176233361624372032, 7004360028618587425, 14621852222567789036, 3936171181552910710], [10700241134130895423, 16175044872028420933, 16526411833322844791, 5788134657049522703, 7037055019297404456], [14224833168782865255, 16539251753915884991, 3235917752922923050, 1832728608765852694, 15106385423963343456], [12433768336127183663, 18227722553633960543, 6833661337905400085, 3651294124034006228, 11290925773559173923]], [[3649211306897224567, 4746764073532247964, 15202687189810130517, 13168719335644854541, 16221425899854625750], [12992102485536292006, 6845035347925658499, 9546562292557765078, 7179819103482614377, 4559158687396833379], [18187921337412651799, 14639831960876614705, 13613405386991937103, 2142501071033669082, 9445557157279390035], [11704952742938572335, 10180883359371553962, 18445782300267045131, 6066494288842996284, 12698330914809090285]], [[16859567271750563717, 17842905099195200559, 10797816325649130546, 9183397633891834384, 14650029587755512742], [8097090801427152828, 15410287647404475871, 3708249558275771039, 17618737597430655338, 13399848645090243083], [5611545608017286333, 940057140943518892, 4190788215468685237, 9847962503401489775, 9459406283903725897], [14678567567580358458, 15875292957222400779, 15938859210045381855, 12747840916749258043, 3499876589053888310]], [[7698432480451708000, 12102198666036233643, 5512209194909751485, 11409986320239711364, 11358376868838479640], [11485259570063044828, 6187754340766603872, 13268674376654473274, 14641405820376693629, 2365429011861050876], [8132643075864871751, 1876886689982665782, 906419673716508913, 10751976225821021839, 4062648959966629122], [16136161769546694683, 15093790509281980522, 193827536739260076, 12419646390372959831, 3656543794705867935]], [[13707194581493329753, 15304252447874109248, 1080120446236401946, 18061544457241038371, 6079724991864922647], [16552354514765621682, 7967095465969710997, 2653610512351390366, 13742110798794530026, 6567940689386750103], [3896508552228202697, 18225567751330995866, 17750586003583329012, 5448981392772461944, 9966943700336676177], [6626201394293927732, 14799991402248627099, 9358456432291159377, 247047361230204433, 13695129691939218195]], [[8933104512521138534, 11441812838004906373, 2084751138073470913, 1875923807065298849, 6411580406621613246], [13406674151247756202, 15507314462302727714, 10940420664353203558, 11719190219862726039, 5297045978909873202], [15617285095341013316, 13553854079444914740, 5173969970866856814, 13423994594457760704, 12920652558734818864], [16690235497833936615, 2075162933729712065, 4041279903049945059, 8175635443081417925, 10150407369151131727]], [[7595795923077521908, 14200695428231550498, 519983718803514702, 3143732083365876784, 18385136916946247497], [2631939856882200408, 8671041112025157184, 8979650248187802017, 7425714223054372817, 8101129097753930423], [8513289290701897951, 18177855038046108947, 3164942939025046172, 2751205490643963843, 14310073420925010688], [8633852641454248836, 623482162818506895, 3793451223120700703, 18158040799130739918, 7327060089842813992]], [[15531157400841217996, 6269516723829086422, 11975265561027327307, 13330863715222143250, 15838663890418574878], [378131668850531030, 18058899736873732781, 5536686430220487002, 7116921856292033517, 11774588394287483752], [11820623813346065906, 15900833206139417530, 3594988133529021992, 15673618637653421495, 15655358131655715531], [712416343495869823, 1385537470114502198, 573019337012328849, 17418525079169599735, 5979989993040377074]]]];
+const MAX_WIDTH: usize = game::MAX_WIDTH as usize;
+const MAX_HEIGHT: usize = game::MAX_HEIGHT as usize;
+const MAX_HEALTH: usize = 100;
+const MAX_HAZARD_STACKS: usize = 8;
+const MAX_SIDE: usize = if MAX_WIDTH > MAX_HEIGHT { MAX_WIDTH } else { MAX_HEIGHT };
+
+// Keys are generated at compile time, so tables follow the board limits from `game`.
+// Changing the seed changes all hashes, but nothing depends on their values.
+const SEED: ValueInt = 0x5EED_5A4E_B0A2_D000;
+
+// Each table takes keys from its own stream.
+const FOOD_STREAM: ValueInt = 1;
+const BODY_DIRECTIONS_STREAM: ValueInt = 2;
+const HEALTH_STREAM: ValueInt = 3;
+const HAZARDS_STREAM: ValueInt = 4;
+const SAFE_ZONE_STREAM: ValueInt = 5;
+const TURN_STREAM: ValueInt = 6;
+
+static FOOD: [[ValueInt; MAX_HEIGHT]; MAX_WIDTH] = {
+    let mut table = [[0; MAX_HEIGHT]; MAX_WIDTH];
+    let mut x = 0;
+    while x < MAX_WIDTH {
+        let mut y = 0;
+        while y < MAX_HEIGHT {
+            table[x][y] = key(FOOD_STREAM, (x * MAX_HEIGHT + y) as ValueInt);
+            y += 1;
+        }
+        x += 1;
+    }
+    table
+};
+
+static BODY_DIRECTIONS: [[[[ValueInt; 5]; MAX_SNAKE_COUNT]; MAX_HEIGHT]; MAX_WIDTH] = {
+    let mut table = [[[[0; 5]; MAX_SNAKE_COUNT]; MAX_HEIGHT]; MAX_WIDTH];
+    let mut x = 0;
+    while x < MAX_WIDTH {
+        let mut y = 0;
+        while y < MAX_HEIGHT {
+            let mut player = 0;
+            while player < MAX_SNAKE_COUNT {
+                let mut direction = 0;
+                while direction < 5 {
+                    let index = ((x * MAX_HEIGHT + y) * MAX_SNAKE_COUNT + player) * 5 + direction;
+                    table[x][y][player][direction] = key(BODY_DIRECTIONS_STREAM, index as ValueInt);
+                    direction += 1;
+                }
+                player += 1;
+            }
+            y += 1;
+        }
+        x += 1;
+    }
+    table
+};
+
+static HEALTH: [[ValueInt; MAX_HEALTH + 1]; MAX_SNAKE_COUNT] = {
+    let mut table = [[0; MAX_HEALTH + 1]; MAX_SNAKE_COUNT];
+    let mut player = 0;
+    while player < MAX_SNAKE_COUNT {
+        let mut health = 0;
+        while health <= MAX_HEALTH {
+            table[player][health] = key(HEALTH_STREAM, (player * (MAX_HEALTH + 1) + health) as ValueInt);
+            health += 1;
+        }
+        player += 1;
+    }
+    table
+};
+
+static HAZARDS: [[[ValueInt; MAX_HAZARD_STACKS]; MAX_HEIGHT]; MAX_WIDTH] = {
+    let mut table = [[[0; MAX_HAZARD_STACKS]; MAX_HEIGHT]; MAX_WIDTH];
+    let mut x = 0;
+    while x < MAX_WIDTH {
+        let mut y = 0;
+        while y < MAX_HEIGHT {
+            let mut stack = 0;
+            while stack < MAX_HAZARD_STACKS {
+                let index = (x * MAX_HEIGHT + y) * MAX_HAZARD_STACKS + stack;
+                table[x][y][stack] = key(HAZARDS_STREAM, index as ValueInt);
+                stack += 1;
+            }
+            y += 1;
+        }
+        x += 1;
+    }
+    table
+};
+
+// Safe zone sides: p0.x, p0.y, p1.x, p1.y
+static SAFE_ZONE: [[ValueInt; MAX_SIDE + 1]; 4] = {
+    let mut table = [[0; MAX_SIDE + 1]; 4];
+    let mut side = 0;
+    while side < 4 {
+        let mut coord = 0;
+        while coord <= MAX_SIDE {
+            table[side][coord] = key(SAFE_ZONE_STREAM, (side * (MAX_SIDE + 1) + coord) as ValueInt);
+            coord += 1;
+        }
+        side += 1;
+    }
+    table
+};
+
+/// Key number `index` of the stream. Streams don't overlap while index is below 2^32.
+const fn key(stream: ValueInt, index: ValueInt) -> ValueInt {
+    splitmix64(SEED ^ (stream << 32) ^ index)
+}
+
+const fn splitmix64(state: ValueInt) -> ValueInt {
+    let mut z = state.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
 
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -57,8 +166,28 @@ impl ZobristHash {
     }
 
     pub fn xor_turn(&mut self, turn: i32) {
-        // Spiral hazard included in turn
-        self.value ^= turn as ValueInt;
+        // Spiral hazards with the same center are the same on the same turn (`maps::spiral_cell`)
+        // Turn isn't bounded, so its key is computed instead of taken from a table.
+        self.value ^= key(TURN_STREAM, turn as u32 as ValueInt);
+    }
+
+    /// Health out of [0, 100] is hashed as the nearest bound.
+    pub fn xor_health(&mut self, player: usize, health: i32) {
+        self.value ^= HEALTH[player][health.clamp(0, MAX_HEALTH as i32) as usize];
+    }
+
+    /// Stacks above `MAX_HAZARD_STACKS` aren't distinguished.
+    pub fn xor_hazard(&mut self, point: PointUsize, stacks: u8) {
+        for stack in 0..(stacks as usize).min(MAX_HAZARD_STACKS) {
+            self.value ^= HAZARDS[point.x][point.y][stack];
+        }
+    }
+
+    pub fn xor_safe_zone(&mut self, safe_zone: &Rectangle) {
+        let sides = [safe_zone.p0.x, safe_zone.p0.y, safe_zone.p1.x, safe_zone.p1.y];
+        for (side, coord) in sides.into_iter().enumerate() {
+            self.value ^= SAFE_ZONE[side][coord.clamp(0, MAX_SIDE as i32) as usize];
+        }
     }
 
     pub fn get_value(&self) -> ValueInt {
@@ -113,9 +242,25 @@ pub fn body_direction(start: GridPoint, target: GridPoint) -> BodyDirections {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use crate::{api::objects::Movement, zobrist::body_direction, game::Point};
 
-    use super::BodyDirections;
+    use super::{BodyDirections, FOOD, BODY_DIRECTIONS, HEALTH, HAZARDS, SAFE_ZONE};
+
+    #[test]
+    fn test_keys_are_unique() {
+        let keys: Vec<u64> = FOOD.iter().flatten().copied()
+            .chain(BODY_DIRECTIONS.iter().flatten().flatten().flatten().copied())
+            .chain(HEALTH.iter().flatten().copied())
+            .chain(HAZARDS.iter().flatten().flatten().copied())
+            .chain(SAFE_ZONE.iter().flatten().copied())
+            .collect();
+
+        let unique_keys: HashSet<u64> = keys.iter().copied().collect();
+        assert_eq!(unique_keys.len(), keys.len());
+        assert!(!unique_keys.contains(&0));
+    }
 
     #[test]
     fn test_body_direction() {
@@ -9,5 +9,6 @@ export MCTS_SEARCH_TIME=400
 # export MCTS_SELECT_DEPTH=
 # export MCTS_WORKERS=
 # export MCTS_ROLLOUT_CUTOFF=
+# export MCTS_SHRINK_EXPECTATION=1
 
 exec target/withpgo/release/sneaky-snickers